@@ -1,6 +1,11 @@
 mod guard;
+mod top_up;
 
 use crate::guard::{caller_is_admin, caller_is_canister_or_admin, caller_is_signer};
+use crate::top_up::{
+    reschedule_top_up, run_cycles_top_up, run_fleet_top_up, schedule_cycles_top_up,
+    schedule_fleet_top_up,
+};
 use b3_utils::{
     api::{bugs::AppBug, AppAccountsNonce, AppInitArgs, AppStatus, Management},
     ledger::{
@@ -23,14 +28,32 @@ use b3wallet_lib::{
         btc::{network::BitcoinNetwork, types::UtxoStatus},
         chain::ChainTrait,
         ckbtc::{minter::Minter, types::RetrieveBtcStatus},
+        error::LedgerError,
+        evm::{
+            ens::resolve_ens_name,
+            error::EvmError,
+            gas::EvmFees,
+            network::EvmNetwork,
+            simulation::EvmSimulation,
+            types::{Erc20Token, NftCollection},
+            u256::U256,
+            user_operation::Erc4337Config,
+        },
+        icrc7::types::Icrc7Token,
+        options::SendOptions,
         subaccount::SubaccountEcdsaTrait,
-        types::{AddressMap, Balance, BtcPending, ChainEnum, PendingEnum, SendResult},
+        token::TokenMap,
+        types::{
+            AddressMap, BtcPending, ChainEnum, ChainId, EvmPending, PendingEnum, SendResult,
+            TransactionPage,
+        },
     },
     setting::WalletSettings,
     store::{
         with_account, with_account_mut, with_chain, with_chain_mut, with_ledger, with_ledger_mut,
         with_setting, with_setting_mut, with_wallet, with_wallet_mut,
     },
+    top_up::{fleet::FleetTopUpReport, TopUpRecord},
     types::{AccountId, WalletAccountView},
 };
 use ic_cdk::{
@@ -46,26 +69,48 @@ use ic_cdk::{
 };
 use operations::{
     error::OperationError,
+    icrc21::{fetch_canister_consent, Icrc21ConsentMessageResponse},
     operation::{
         btc::transfer::BtcTransfer,
+        canister::call::CallCanister,
+        cycles::{
+            top_up::{UnwatchCanister, UpdateCyclesTopUp, UpdateFleetTopUp, WatchCanister},
+            transfer::{CreateCanisterWithCycles, DepositCycles, UpdateCyclesLedger},
+        },
+        evm::{
+            fetch_evm_fees, fetch_evm_simulation, EvmCancelNonce, EvmContractCall,
+            EvmDeployContract, EvmSignPersonalMessage, EvmSignRawTransaction, EvmSignTranscation,
+            EvmSignTypedData, EvmSignUserOperation, EvmTransfer, EvmTransferErc1155,
+            EvmTransferErc20, EvmTransferErc721, UpdateErc4337Config, UpdateEvmGasPolicy,
+            UpdateEvmNetwork, UpdateEvmRpc,
+        },
         global::SendToken,
-        icp::transfer::IcpTransfer,
+        icp::{
+            neuron::{
+                DisburseNeuron, FollowNeuron, IncreaseNeuronDissolveDelay, RefreshNeuron,
+                StakeNeuron, StartNeuronDissolving, StopNeuronDissolving, UpdateGovernanceCanister,
+                VoteNeuron,
+            },
+            transfer::IcpTransfer,
+        },
+        icrc7::transfer::Icrc7Transfer,
         inner::account::{CreateAccount, RemoveAccount, RenameAccount},
         inner::user::AddUser,
         inner::{
             setting::{UpdateCanisterSettings, UpgradeCanister},
             RemoveUser,
         },
-        Operation, OperationState, OperationTrait,
+        legacy::LegacyOperationState,
+        Operation, OperationState,
     },
     pending::RequestArgs,
     processed::ProcessedOperation,
     response::Response,
     role::{AccessLevel, Role, RoleState},
     store::{
-        with_operation, with_operation_mut, with_pending_operation_mut, with_processed_operation,
-        with_processed_operation_mut, with_roles, with_roles_mut, with_user, with_users,
-        with_users_mut, with_users_who_can_operate, with_verified_user,
+        with_operation, with_operation_mut, with_pending_operation, with_pending_operation_mut,
+        with_processed_operation, with_processed_operation_mut, with_roles, with_roles_mut,
+        with_user, with_users, with_users_mut, with_users_who_can_operate, with_verified_user,
     },
     types::{PendingOperations, ProcessedOperations, RoleMap, UserMap, WalletSettingsAndSigners},
     user::{state::UserState, User},
@@ -136,13 +181,22 @@ fn pre_upgrade() {
 fn post_upgrade() {
     log_cycle!("post_upgrade");
     let (sign_prev, user_prev, role_prev): (OperationState, UserState, RoleState) =
-        ic_cdk::storage::stable_restore().unwrap();
+        ic_cdk::storage::stable_restore()
+            .or_else(|_| {
+                // stored before the EVM amounts were 256-bit
+                ic_cdk::storage::stable_restore::<(LegacyOperationState, UserState, RoleState)>()
+                    .map(|(operations, users, roles)| (operations.into(), users, roles))
+            })
+            .unwrap();
 
     with_operation_mut(|permit| *permit = sign_prev);
 
     with_users_mut(|users| *users = user_prev);
 
     with_roles_mut(|roles| *roles = role_prev);
+
+    schedule_cycles_top_up();
+    schedule_fleet_top_up();
 }
 
 #[query(guard = "caller_is_signer")]
@@ -180,6 +234,11 @@ fn get_addresses(account_id: AccountId) -> AddressMap {
     with_ledger(&account_id, |ledger| ledger.address_map().clone()).unwrap_or_else(panic_log)
 }
 
+#[query(guard = "caller_is_signer")]
+fn get_tokens(account_id: AccountId) -> TokenMap {
+    with_ledger(&account_id, |ledger| ledger.token_map()).unwrap_or_else(panic_log)
+}
+
 #[query(guard = "caller_is_signer")]
 async fn retrieve_btc_status(
     network: BitcoinNetwork,
@@ -267,7 +326,28 @@ fn account_restore(env: Environment, nonce: u64) {
 }
 
 #[update(guard = "caller_is_signer")]
-async fn account_balance(account_id: AccountId, chain: ChainEnum) -> Balance {
+async fn account_icp_transactions(
+    account_id: AccountId,
+    start: Option<u64>,
+    max_results: u64,
+) -> TransactionPage {
+    log_cycle!(
+        "Get icp transactions for account: {} from: {:?}",
+        account_id,
+        start
+    );
+
+    let icp = with_chain(&account_id, &ChainEnum::ICP, |chain| chain.icp())
+        .unwrap_or_else(panic_log)
+        .unwrap_or_else(panic_log);
+
+    icp.transactions(start, max_results)
+        .await
+        .unwrap_or_else(panic_log)
+}
+
+#[update(guard = "caller_is_signer")]
+async fn account_balance(account_id: AccountId, chain: ChainEnum) -> TokenAmount {
     log_cycle!(
         "Get balance for account: {} on chain: {:?}",
         account_id,
@@ -284,12 +364,239 @@ async fn account_balance(account_id: AccountId, chain: ChainEnum) -> Balance {
     }
 }
 
+#[query(guard = "caller_is_signer")]
+fn account_evm_tokens(account_id: AccountId, chain_id: ChainId) -> Vec<Erc20Token> {
+    with_chain(&account_id, &ChainEnum::EVM(chain_id), |chain| chain.evm())
+        .unwrap_or_else(panic_log)
+        .unwrap_or_else(panic_log)
+        .tokens
+}
+
+#[update(guard = "caller_is_signer")]
+fn account_evm_add_token(account_id: AccountId, chain_id: ChainId, token: Erc20Token) {
+    log_cycle!(
+        "Add token: {:?} on chain: {} for account: {}",
+        token,
+        chain_id,
+        account_id
+    );
+
+    with_chain_mut(&account_id, ChainEnum::EVM(chain_id), |chain| {
+        chain
+            .evm_mut()?
+            .add_erc20_token(token)
+            .map_err(LedgerError::EvmError)
+    })
+    .unwrap_or_else(panic_log)
+    .unwrap_or_else(panic_log);
+}
+
+#[update(guard = "caller_is_signer")]
+fn account_evm_remove_token(account_id: AccountId, chain_id: ChainId, address: String) {
+    log_cycle!(
+        "Remove token: {} on chain: {} for account: {}",
+        address,
+        chain_id,
+        account_id
+    );
+
+    with_chain_mut(&account_id, ChainEnum::EVM(chain_id), |chain| {
+        chain
+            .evm_mut()?
+            .remove_erc20_token(&address)
+            .map_err(LedgerError::EvmError)
+    })
+    .unwrap_or_else(panic_log)
+    .unwrap_or_else(panic_log);
+}
+
+#[update(guard = "caller_is_signer")]
+async fn account_evm_token_balance(
+    account_id: AccountId,
+    chain_id: ChainId,
+    address: String,
+) -> TokenAmount {
+    log_cycle!(
+        "Get token balance: {} on chain: {} for account: {}",
+        address,
+        chain_id,
+        account_id
+    );
+
+    let evm = with_chain(&account_id, &ChainEnum::EVM(chain_id), |chain| chain.evm())
+        .unwrap_or_else(panic_log)
+        .unwrap_or_else(panic_log);
+
+    let token = evm.erc20_token(&address).unwrap_or_else(panic_log);
+
+    evm.erc20_balance(token).await.unwrap_or_else(panic_log)
+}
+
+#[query(guard = "caller_is_signer")]
+fn account_evm_collections(account_id: AccountId, chain_id: ChainId) -> Vec<NftCollection> {
+    with_chain(&account_id, &ChainEnum::EVM(chain_id), |chain| chain.evm())
+        .unwrap_or_else(panic_log)
+        .unwrap_or_else(panic_log)
+        .collections
+}
+
+#[update(guard = "caller_is_signer")]
+fn account_evm_add_collection(account_id: AccountId, chain_id: ChainId, collection: NftCollection) {
+    log_cycle!(
+        "Add collection: {:?} on chain: {} for account: {}",
+        collection,
+        chain_id,
+        account_id
+    );
+
+    with_chain_mut(&account_id, ChainEnum::EVM(chain_id), |chain| {
+        chain
+            .evm_mut()?
+            .add_nft_collection(collection)
+            .map_err(LedgerError::EvmError)
+    })
+    .unwrap_or_else(panic_log)
+    .unwrap_or_else(panic_log);
+}
+
+#[update(guard = "caller_is_signer")]
+fn account_evm_remove_collection(account_id: AccountId, chain_id: ChainId, address: String) {
+    log_cycle!(
+        "Remove collection: {} on chain: {} for account: {}",
+        address,
+        chain_id,
+        account_id
+    );
+
+    with_chain_mut(&account_id, ChainEnum::EVM(chain_id), |chain| {
+        chain
+            .evm_mut()?
+            .remove_nft_collection(&address)
+            .map_err(LedgerError::EvmError)
+    })
+    .unwrap_or_else(panic_log)
+    .unwrap_or_else(panic_log);
+}
+
+#[update(guard = "caller_is_signer")]
+async fn account_evm_nft_owner(
+    account_id: AccountId,
+    chain_id: ChainId,
+    address: String,
+    token_id: U256,
+) -> String {
+    log_cycle!(
+        "Get owner of token: {} of collection: {} on chain: {} for account: {}",
+        token_id,
+        address,
+        chain_id,
+        account_id
+    );
+
+    let evm = with_chain(&account_id, &ChainEnum::EVM(chain_id), |chain| chain.evm())
+        .unwrap_or_else(panic_log)
+        .unwrap_or_else(panic_log);
+
+    let collection = evm.nft_collection(&address).unwrap_or_else(panic_log);
+
+    evm.nft_owner(collection, &token_id)
+        .await
+        .unwrap_or_else(panic_log)
+}
+
+#[update(guard = "caller_is_signer")]
+async fn account_evm_nft_balance(
+    account_id: AccountId,
+    chain_id: ChainId,
+    address: String,
+    token_id: U256,
+) -> U256 {
+    log_cycle!(
+        "Get balance of token: {} of collection: {} on chain: {} for account: {}",
+        token_id,
+        address,
+        chain_id,
+        account_id
+    );
+
+    let evm = with_chain(&account_id, &ChainEnum::EVM(chain_id), |chain| chain.evm())
+        .unwrap_or_else(panic_log)
+        .unwrap_or_else(panic_log);
+
+    let collection = evm.nft_collection(&address).unwrap_or_else(panic_log);
+
+    evm.nft_balance(collection, &token_id)
+        .await
+        .unwrap_or_else(panic_log)
+}
+
+#[update(guard = "caller_is_signer")]
+async fn account_evm_smart_account(account_id: AccountId, chain_id: ChainId, salt: U256) -> String {
+    log_cycle!(
+        "Get smart account: {} on chain: {} for account: {}",
+        salt,
+        chain_id,
+        account_id
+    );
+
+    let evm = with_chain(&account_id, &ChainEnum::EVM(chain_id), |chain| chain.evm())
+        .unwrap_or_else(panic_log)
+        .unwrap_or_else(panic_log);
+
+    let config = with_setting(|s| s.erc4337_config(chain_id)).unwrap_or_else(panic_log);
+
+    evm.smart_account_address(&config, &salt)
+        .await
+        .unwrap_or_else(panic_log)
+}
+
+#[query(guard = "caller_is_signer")]
+fn account_evm_pendings(account_id: AccountId, chain_id: ChainId) -> Vec<EvmPending> {
+    with_chain(&account_id, &ChainEnum::EVM(chain_id), |chain| chain.evm())
+        .unwrap_or_else(panic_log)
+        .unwrap_or_else(panic_log)
+        .pendings
+}
+
+#[update(guard = "caller_is_admin")]
+fn account_evm_reset_nonce(account_id: AccountId, chain_id: ChainId) {
+    log_cycle!(
+        "Reset nonce on chain: {} for account: {}",
+        chain_id,
+        account_id
+    );
+
+    with_chain_mut(&account_id, ChainEnum::EVM(chain_id), |chain| {
+        chain.evm_mut().map(|evm| evm.reset_nonce())
+    })
+    .unwrap_or_else(panic_log)
+    .unwrap_or_else(panic_log);
+}
+
+#[update(guard = "caller_is_signer")]
+async fn account_nft_tokens(account_id: AccountId, collection: CanisterId) -> Vec<Icrc7Token> {
+    log_cycle!(
+        "Get nft tokens for account: {} on collection: {}",
+        account_id,
+        collection
+    );
+
+    let icrc7 = with_chain(&account_id, &ChainEnum::ICRC7(collection), |chain| {
+        chain.icrc7()
+    })
+    .unwrap_or_else(panic_log)
+    .unwrap_or_else(panic_log);
+
+    icrc7.tokens().await.unwrap_or_else(panic_log)
+}
+
 #[update(guard = "caller_is_signer")]
 async fn account_send(
     account_id: AccountId,
     chain: ChainEnum,
     to: String,
     amount: TokenAmount,
+    options: Option<SendOptions>,
 ) -> SendResult {
     log_cycle!(
         "Send {} on chain: {:?} from account: {} to: {}",
@@ -301,10 +608,17 @@ async fn account_send(
 
     let ledger = with_ledger(&account_id, |ledger| ledger.clone()).unwrap_or_else(panic_log);
 
-    ledger
-        .send(&chain, to, amount)
+    let result = ledger
+        .send(&chain, to, amount, options.unwrap_or_default())
         .await
-        .unwrap_or_else(panic_log)
+        .unwrap_or_else(panic_log);
+
+    if let Some(pending) = result.pending() {
+        with_chain_mut(&account_id, chain, |chain| chain.add_pending(pending))
+            .unwrap_or_else(panic_log);
+    }
+
+    result
 }
 
 #[update(guard = "caller_is_signer")]
@@ -319,17 +633,37 @@ async fn account_check_pending(account_id: AccountId, chain_enum: ChainEnum, pen
     let chain =
         with_chain(&account_id, &chain_enum, |chain| chain.clone()).unwrap_or_else(panic_log);
 
-    let result = chain.check_pending(pending_index).await;
+    let pending = chain
+        .pendings()
+        .get(pending_index)
+        .cloned()
+        .ok_or(LedgerError::PendingIndexError(pending_index))
+        .unwrap_or_else(panic_log);
 
-    match result {
-        Ok(_) => {
-            with_chain_mut(&account_id, chain_enum, |chain| {
-                chain.remove_pending(pending_index)
-            })
+    if let ChainEnum::EVM(_) = chain_enum {
+        let evm = chain.evm().unwrap_or_else(panic_log);
+
+        let receipt = evm
+            .check_receipt(pending_index)
+            .await
+            .unwrap_or_else(panic_log);
+
+        let operation_id = with_processed_operation_mut(|s| s.attach_evm_receipt(receipt.clone()));
+
+        log_cycle!("Receipt: {:?} for operation: {:?}", receipt, operation_id);
+    } else {
+        chain
+            .check_pending(pending_index)
+            .await
             .unwrap_or_else(panic_log);
-        }
-        Err(err) => panic_log(err),
     }
+
+    // the index may point elsewhere by now, e.g. after a cancelled nonce or another check,
+    // and the entry is left alone if it is already gone
+    with_chain_mut(&account_id, chain_enum, |chain| {
+        chain.remove_pending_entry(&pending)
+    })
+    .unwrap_or_else(panic_log);
 }
 
 #[update(guard = "caller_is_signer")]
@@ -583,6 +917,8 @@ async fn response(request_id: OperationId, answer: Response) -> Result<Processed
         let processed = request.execute().await;
         log_cycle!("Request executed: {}", processed.get_result());
 
+        reschedule_top_up(processed.get_result());
+
         with_processed_operation_mut(|s| s.add(request_id, processed.clone()));
         with_operation_mut(|s| s.remove_request(&request_id));
 
@@ -592,6 +928,39 @@ async fn response(request_id: OperationId, answer: Response) -> Result<Processed
     Ok(request.into())
 }
 
+/// Simulate the EVM transaction of the pending operation again at the latest
+/// block, its outcome can change while the signers respond. A simulation run
+/// in the last `SIMULATION_CACHE_NANOS` is returned as it is.
+#[update(guard = "caller_is_signer")]
+async fn simulate_pending(request_id: OperationId) -> EvmSimulation {
+    log_cycle!("simulate_pending: {}", request_id);
+
+    let (request, cached) = with_pending_operation(&request_id, |pending| {
+        let cached = pending.fresh_evm_simulation(&NanoTimeStamp::now()).cloned();
+
+        (pending.request.clone(), cached)
+    })
+    .unwrap_or_else(panic_log);
+
+    // every simulation pays for two calls to the RPC providers
+    if let Some(simulation) = cached {
+        return simulation;
+    }
+
+    let simulation = match fetch_evm_simulation(&request).await {
+        Some(simulation) => simulation,
+        None => panic_log(OperationError::InvalidRequest),
+    };
+
+    with_pending_operation_mut(&request_id, |pending| {
+        pending.set_evm_simulation(simulation);
+
+        pending.evm_simulation.clone()
+    })
+    .unwrap_or_else(panic_log)
+    .unwrap_or_else(|| panic_log(OperationError::InvalidRequest))
+}
+
 #[update(guard = "caller_is_signer")]
 fn reset_accounts() {
     log_cycle!("Reset accounts");
@@ -659,6 +1028,48 @@ async fn refresh_settings() {
     with_wallet_mut(|w| w.set_setting(settings));
 }
 
+#[query(guard = "caller_is_signer")]
+fn get_evm_networks() -> Vec<EvmNetwork> {
+    with_setting(|s| s.evm_networks())
+}
+
+/// The address the ENS name resolves to for the chain, to show it in the send form.
+#[update(guard = "caller_is_signer")]
+async fn resolve_evm_ens_name(chain_id: ChainId, name: String) -> String {
+    resolve_ens_name(chain_id, &name)
+        .await
+        .unwrap_or_else(panic_log)
+}
+
+#[query(guard = "caller_is_signer")]
+fn get_erc4337_configs() -> Vec<Erc4337Config> {
+    with_setting(|s| s.erc4337_configs())
+}
+
+#[update(guard = "caller_is_admin")]
+async fn trigger_cycles_top_up() -> Option<TopUpRecord> {
+    log_cycle!("Trigger cycles top up");
+
+    run_cycles_top_up().await
+}
+
+#[query(guard = "caller_is_signer")]
+fn get_cycles_top_up_history() -> Vec<TopUpRecord> {
+    with_wallet(|s| s.top_ups().clone())
+}
+
+#[update(guard = "caller_is_admin")]
+async fn trigger_fleet_top_up() -> Option<FleetTopUpReport> {
+    log_cycle!("Trigger fleet top up");
+
+    run_fleet_top_up().await
+}
+
+#[query(guard = "caller_is_signer")]
+fn get_fleet_top_up_reports() -> Vec<FleetTopUpReport> {
+    with_wallet(|s| s.fleet_reports().clone())
+}
+
 #[update(guard = "caller_is_signer")]
 fn add_setting_metadata(key: String, value: Value) {
     log_cycle!("Add metadata: {} with value: {}", key, value);
@@ -692,6 +1103,44 @@ fn request_maker(
     request: Operation,
     reason: String,
     deadline: Option<NanoTimeStamp>,
+) -> OperationId {
+    // ENS names are resolved by the transfer endpoints, never taken as given
+    if let Some(name) = request.ens_name() {
+        return panic_log(EvmError::UnresolvedEnsName(name.clone()));
+    }
+
+    new_request(request, reason, deadline, None, None, None)
+}
+
+/// Same as `request_maker`, but first asks the target canister of the
+/// operation for its ICRC-21 consent message, or quotes the fees of the
+/// EVM transaction it sends and simulates it at the latest block.
+async fn request_maker_with_consent(
+    request: Operation,
+    reason: String,
+    deadline: Option<NanoTimeStamp>,
+) -> OperationId {
+    let canister_consent = fetch_canister_consent(&request).await;
+    let evm_fees = fetch_evm_fees(&request).await;
+    let evm_simulation = fetch_evm_simulation(&request).await;
+
+    new_request(
+        request,
+        reason,
+        deadline,
+        canister_consent,
+        evm_fees,
+        evm_simulation,
+    )
+}
+
+fn new_request(
+    request: Operation,
+    reason: String,
+    deadline: Option<NanoTimeStamp>,
+    canister_consent: Option<Icrc21ConsentMessageResponse>,
+    evm_fees: Option<Result<EvmFees, WalletError>>,
+    evm_simulation: Option<Result<EvmSimulation, WalletError>>,
 ) -> OperationId {
     log_cycle!("request_maker: {:?} with reason: {}", request, reason);
 
@@ -714,7 +1163,22 @@ fn request_maker(
     };
 
     with_operation_mut(|s| {
-        let new_request = s.new_request(caller.into(), request_args);
+        let mut new_request = s
+            .new_request(caller.into(), request_args)
+            .unwrap_or_else(panic_log);
+
+        if let Some(response) = canister_consent {
+            new_request.consent_message.set_canister_consent(response);
+        }
+
+        if let Some(quote) = evm_fees {
+            new_request.consent_message.set_evm_fees(quote);
+        }
+
+        if let Some(simulation) = evm_simulation {
+            new_request.set_evm_simulation(simulation);
+        }
+
         s.add(new_request)
     })
 }
@@ -871,18 +1335,533 @@ fn request_transfer_btc(
     request_maker(request.into(), reason, deadline)
 }
 
-#[update(guard = "caller_is_signer")]
-fn request_send(
-    request: SendToken,
+#[update(guard = "caller_is_admin")]
+fn request_stake_neuron(
+    request: StakeNeuron,
     reason: String,
     deadline: Option<NanoTimeStamp>,
 ) -> OperationId {
-    log_cycle!("request_send: {:?} with reason: {}", request, reason);
+    log_cycle!(
+        "request_stake_neuron: {:?} with reason: {}",
+        request,
+        reason
+    );
 
     request_maker(request.into(), reason, deadline)
 }
 
-#[update(guard = "caller_is_signer")]
+#[update(guard = "caller_is_admin")]
+fn request_refresh_neuron(
+    request: RefreshNeuron,
+    reason: String,
+    deadline: Option<NanoTimeStamp>,
+) -> OperationId {
+    log_cycle!(
+        "request_refresh_neuron: {:?} with reason: {}",
+        request,
+        reason
+    );
+
+    request_maker(request.into(), reason, deadline)
+}
+
+#[update(guard = "caller_is_admin")]
+fn request_update_governance_canister(
+    request: UpdateGovernanceCanister,
+    reason: String,
+    deadline: Option<NanoTimeStamp>,
+) -> OperationId {
+    log_cycle!(
+        "request_update_governance_canister: {:?} with reason: {}",
+        request,
+        reason
+    );
+
+    request_maker(request.into(), reason, deadline)
+}
+
+#[update(guard = "caller_is_admin")]
+fn request_increase_neuron_dissolve_delay(
+    request: IncreaseNeuronDissolveDelay,
+    reason: String,
+    deadline: Option<NanoTimeStamp>,
+) -> OperationId {
+    log_cycle!(
+        "request_increase_neuron_dissolve_delay: {:?} with reason: {}",
+        request,
+        reason
+    );
+
+    request_maker(request.into(), reason, deadline)
+}
+
+#[update(guard = "caller_is_admin")]
+fn request_start_neuron_dissolving(
+    request: StartNeuronDissolving,
+    reason: String,
+    deadline: Option<NanoTimeStamp>,
+) -> OperationId {
+    log_cycle!(
+        "request_start_neuron_dissolving: {:?} with reason: {}",
+        request,
+        reason
+    );
+
+    request_maker(request.into(), reason, deadline)
+}
+
+#[update(guard = "caller_is_admin")]
+fn request_stop_neuron_dissolving(
+    request: StopNeuronDissolving,
+    reason: String,
+    deadline: Option<NanoTimeStamp>,
+) -> OperationId {
+    log_cycle!(
+        "request_stop_neuron_dissolving: {:?} with reason: {}",
+        request,
+        reason
+    );
+
+    request_maker(request.into(), reason, deadline)
+}
+
+#[update(guard = "caller_is_admin")]
+fn request_disburse_neuron(
+    request: DisburseNeuron,
+    reason: String,
+    deadline: Option<NanoTimeStamp>,
+) -> OperationId {
+    log_cycle!(
+        "request_disburse_neuron: {:?} with reason: {}",
+        request,
+        reason
+    );
+
+    request_maker(request.into(), reason, deadline)
+}
+
+#[update(guard = "caller_is_admin")]
+fn request_follow_neuron(
+    request: FollowNeuron,
+    reason: String,
+    deadline: Option<NanoTimeStamp>,
+) -> OperationId {
+    log_cycle!(
+        "request_follow_neuron: {:?} with reason: {}",
+        request,
+        reason
+    );
+
+    request_maker(request.into(), reason, deadline)
+}
+
+#[update(guard = "caller_is_admin")]
+fn request_vote_neuron(
+    request: VoteNeuron,
+    reason: String,
+    deadline: Option<NanoTimeStamp>,
+) -> OperationId {
+    log_cycle!("request_vote_neuron: {:?} with reason: {}", request, reason);
+
+    request_maker(request.into(), reason, deadline)
+}
+
+#[update(guard = "caller_is_admin")]
+fn request_deposit_cycles(
+    request: DepositCycles,
+    reason: String,
+    deadline: Option<NanoTimeStamp>,
+) -> OperationId {
+    log_cycle!(
+        "request_deposit_cycles: {:?} with reason: {}",
+        request,
+        reason
+    );
+
+    request_maker(request.into(), reason, deadline)
+}
+
+#[update(guard = "caller_is_admin")]
+fn request_update_cycles_ledger(
+    request: UpdateCyclesLedger,
+    reason: String,
+    deadline: Option<NanoTimeStamp>,
+) -> OperationId {
+    log_cycle!(
+        "request_update_cycles_ledger: {:?} with reason: {}",
+        request,
+        reason
+    );
+
+    request_maker(request.into(), reason, deadline)
+}
+
+#[update(guard = "caller_is_admin")]
+fn request_update_cycles_top_up(
+    request: UpdateCyclesTopUp,
+    reason: String,
+    deadline: Option<NanoTimeStamp>,
+) -> OperationId {
+    log_cycle!(
+        "request_update_cycles_top_up: {:?} with reason: {}",
+        request,
+        reason
+    );
+
+    request_maker(request.into(), reason, deadline)
+}
+
+#[update(guard = "caller_is_admin")]
+fn request_update_fleet_top_up(
+    request: UpdateFleetTopUp,
+    reason: String,
+    deadline: Option<NanoTimeStamp>,
+) -> OperationId {
+    log_cycle!(
+        "request_update_fleet_top_up: {:?} with reason: {}",
+        request,
+        reason
+    );
+
+    request_maker(request.into(), reason, deadline)
+}
+
+#[update(guard = "caller_is_admin")]
+fn request_watch_canister(
+    request: WatchCanister,
+    reason: String,
+    deadline: Option<NanoTimeStamp>,
+) -> OperationId {
+    log_cycle!(
+        "request_watch_canister: {:?} with reason: {}",
+        request,
+        reason
+    );
+
+    request_maker(request.into(), reason, deadline)
+}
+
+#[update(guard = "caller_is_admin")]
+fn request_unwatch_canister(
+    request: UnwatchCanister,
+    reason: String,
+    deadline: Option<NanoTimeStamp>,
+) -> OperationId {
+    log_cycle!(
+        "request_unwatch_canister: {:?} with reason: {}",
+        request,
+        reason
+    );
+
+    request_maker(request.into(), reason, deadline)
+}
+
+#[update(guard = "caller_is_admin")]
+fn request_create_canister_with_cycles(
+    request: CreateCanisterWithCycles,
+    reason: String,
+    deadline: Option<NanoTimeStamp>,
+) -> OperationId {
+    log_cycle!(
+        "request_create_canister_with_cycles: {:?} with reason: {}",
+        request,
+        reason
+    );
+
+    request_maker(request.into(), reason, deadline)
+}
+
+#[update(guard = "caller_is_admin")]
+fn request_transfer_icrc7(
+    request: Icrc7Transfer,
+    reason: String,
+    deadline: Option<NanoTimeStamp>,
+) -> OperationId {
+    log_cycle!(
+        "request_transfer_icrc7: {:?} with reason: {}",
+        request,
+        reason
+    );
+
+    request_maker(request.into(), reason, deadline)
+}
+
+#[update(guard = "caller_is_admin")]
+async fn request_call_canister(
+    request: CallCanister,
+    reason: String,
+    deadline: Option<NanoTimeStamp>,
+) -> OperationId {
+    log_cycle!(
+        "request_call_canister: {:?} with reason: {}",
+        request,
+        reason
+    );
+
+    request_maker_with_consent(request.into(), reason, deadline).await
+}
+
+#[update(guard = "caller_is_admin")]
+async fn request_evm_transfer(
+    request: EvmTransfer,
+    reason: String,
+    deadline: Option<NanoTimeStamp>,
+) -> OperationId {
+    log_cycle!(
+        "request_evm_transfer: {:?} with reason: {}",
+        request,
+        reason
+    );
+
+    let request = request.resolve_ens().await.unwrap_or_else(panic_log);
+
+    request_maker_with_consent(request.into(), reason, deadline).await
+}
+
+#[update(guard = "caller_is_admin")]
+async fn request_evm_transfer_erc20(
+    request: EvmTransferErc20,
+    reason: String,
+    deadline: Option<NanoTimeStamp>,
+) -> OperationId {
+    log_cycle!(
+        "request_evm_transfer_erc20: {:?} with reason: {}",
+        request,
+        reason
+    );
+
+    let request = request.resolve_ens().await.unwrap_or_else(panic_log);
+
+    request_maker_with_consent(request.into(), reason, deadline).await
+}
+
+#[update(guard = "caller_is_admin")]
+async fn request_evm_transfer_erc721(
+    request: EvmTransferErc721,
+    reason: String,
+    deadline: Option<NanoTimeStamp>,
+) -> OperationId {
+    log_cycle!(
+        "request_evm_transfer_erc721: {:?} with reason: {}",
+        request,
+        reason
+    );
+
+    request_maker_with_consent(request.into(), reason, deadline).await
+}
+
+#[update(guard = "caller_is_admin")]
+async fn request_evm_transfer_erc1155(
+    request: EvmTransferErc1155,
+    reason: String,
+    deadline: Option<NanoTimeStamp>,
+) -> OperationId {
+    log_cycle!(
+        "request_evm_transfer_erc1155: {:?} with reason: {}",
+        request,
+        reason
+    );
+
+    request_maker_with_consent(request.into(), reason, deadline).await
+}
+
+#[update(guard = "caller_is_admin")]
+async fn request_evm_deploy_contract(
+    request: EvmDeployContract,
+    reason: String,
+    deadline: Option<NanoTimeStamp>,
+) -> OperationId {
+    log_cycle!(
+        "request_evm_deploy_contract: {:?} with reason: {}",
+        request,
+        reason
+    );
+
+    request_maker_with_consent(request.into(), reason, deadline).await
+}
+
+#[update(guard = "caller_is_admin")]
+async fn request_evm_contract_call(
+    request: EvmContractCall,
+    reason: String,
+    deadline: Option<NanoTimeStamp>,
+) -> OperationId {
+    log_cycle!(
+        "request_evm_contract_call: {:?} with reason: {}",
+        request,
+        reason
+    );
+
+    request_maker_with_consent(request.into(), reason, deadline).await
+}
+
+#[update(guard = "caller_is_admin")]
+async fn request_evm_sign_transaction(
+    request: EvmSignTranscation,
+    reason: String,
+    deadline: Option<NanoTimeStamp>,
+) -> OperationId {
+    log_cycle!(
+        "request_evm_sign_transaction: {:?} with reason: {}",
+        request,
+        reason
+    );
+
+    request_maker_with_consent(request.into(), reason, deadline).await
+}
+
+#[update(guard = "caller_is_admin")]
+async fn request_evm_sign_raw_transaction(
+    request: EvmSignRawTransaction,
+    reason: String,
+    deadline: Option<NanoTimeStamp>,
+) -> OperationId {
+    log_cycle!(
+        "request_evm_sign_raw_transaction: {:?} with reason: {}",
+        request,
+        reason
+    );
+
+    request_maker_with_consent(request.into(), reason, deadline).await
+}
+
+#[update(guard = "caller_is_admin")]
+fn request_evm_cancel_nonce(
+    request: EvmCancelNonce,
+    reason: String,
+    deadline: Option<NanoTimeStamp>,
+) -> OperationId {
+    log_cycle!(
+        "request_evm_cancel_nonce: {:?} with reason: {}",
+        request,
+        reason
+    );
+
+    request_maker(request.into(), reason, deadline)
+}
+
+#[update(guard = "caller_is_admin")]
+async fn request_evm_sign_user_operation(
+    request: EvmSignUserOperation,
+    reason: String,
+    deadline: Option<NanoTimeStamp>,
+) -> OperationId {
+    log_cycle!(
+        "request_evm_sign_user_operation: {:?} with reason: {}",
+        request,
+        reason
+    );
+
+    let request = request
+        .resolve_smart_account()
+        .await
+        .unwrap_or_else(panic_log);
+
+    request_maker(request.into(), reason, deadline)
+}
+
+#[update(guard = "caller_is_admin")]
+fn request_update_erc4337_config(
+    request: UpdateErc4337Config,
+    reason: String,
+    deadline: Option<NanoTimeStamp>,
+) -> OperationId {
+    log_cycle!(
+        "request_update_erc4337_config: {:?} with reason: {}",
+        request,
+        reason
+    );
+
+    request_maker(request.into(), reason, deadline)
+}
+
+#[update(guard = "caller_is_admin")]
+fn request_update_evm_rpc(
+    request: UpdateEvmRpc,
+    reason: String,
+    deadline: Option<NanoTimeStamp>,
+) -> OperationId {
+    log_cycle!(
+        "request_update_evm_rpc: {:?} with reason: {}",
+        request,
+        reason
+    );
+
+    request_maker(request.into(), reason, deadline)
+}
+
+#[update(guard = "caller_is_admin")]
+fn request_update_evm_gas_policy(
+    request: UpdateEvmGasPolicy,
+    reason: String,
+    deadline: Option<NanoTimeStamp>,
+) -> OperationId {
+    log_cycle!(
+        "request_update_evm_gas_policy: {:?} with reason: {}",
+        request,
+        reason
+    );
+
+    request_maker(request.into(), reason, deadline)
+}
+
+#[update(guard = "caller_is_admin")]
+fn request_update_evm_network(
+    request: UpdateEvmNetwork,
+    reason: String,
+    deadline: Option<NanoTimeStamp>,
+) -> OperationId {
+    log_cycle!(
+        "request_update_evm_network: {:?} with reason: {}",
+        request,
+        reason
+    );
+
+    request_maker(request.into(), reason, deadline)
+}
+
+#[update(guard = "caller_is_admin")]
+fn request_evm_sign_personal_message(
+    request: EvmSignPersonalMessage,
+    reason: String,
+    deadline: Option<NanoTimeStamp>,
+) -> OperationId {
+    log_cycle!(
+        "request_evm_sign_personal_message: {:?} with reason: {}",
+        request,
+        reason
+    );
+
+    request_maker(request.into(), reason, deadline)
+}
+
+#[update(guard = "caller_is_admin")]
+fn request_evm_sign_typed_data(
+    request: EvmSignTypedData,
+    reason: String,
+    deadline: Option<NanoTimeStamp>,
+) -> OperationId {
+    log_cycle!(
+        "request_evm_sign_typed_data: {:?} with reason: {}",
+        request,
+        reason
+    );
+
+    request_maker(request.into(), reason, deadline)
+}
+
+#[update(guard = "caller_is_signer")]
+fn request_send(
+    request: SendToken,
+    reason: String,
+    deadline: Option<NanoTimeStamp>,
+) -> OperationId {
+    log_cycle!("request_send: {:?} with reason: {}", request, reason);
+
+    request_maker(request.into(), reason, deadline)
+}
+
+#[update(guard = "caller_is_signer")]
 async fn request_upgrade_canister(wasm_version: String) -> OperationId {
     log_cycle!("request_upgrade_canister: {}", wasm_version);
 
@@ -891,8 +1870,6 @@ async fn request_upgrade_canister(wasm_version: String) -> OperationId {
         wasm_version,
     });
 
-    upgrade_request.validate_request().unwrap_or_else(panic_log);
-
     request_maker(upgrade_request.into(), "Upgrade canister".to_string(), None)
 }
 
@@ -0,0 +1,103 @@
+use b3_utils::log_cycle;
+use b3wallet_lib::{
+    store::{with_setting, with_wallet_mut},
+    top_up::{fleet::FleetTopUpReport, TopUpRecord},
+};
+use ic_cdk_timers::TimerId;
+use operations::operation::result::OperationResult;
+use std::{cell::RefCell, time::Duration};
+
+thread_local! {
+    static TOP_UP_TIMER: RefCell<Option<TimerId>> = RefCell::new(None);
+    static FLEET_TOP_UP_TIMER: RefCell<Option<TimerId>> = RefCell::new(None);
+}
+
+/// Replace the timer with a new one running every `interval_secs`,
+/// or just stop it when there is no interval.
+fn restart_timer(
+    timer: &RefCell<Option<TimerId>>,
+    interval_secs: Option<u64>,
+    run: impl FnMut() + 'static,
+) {
+    if let Some(timer_id) = timer.borrow_mut().take() {
+        ic_cdk_timers::clear_timer(timer_id);
+    }
+
+    if let Some(interval_secs) = interval_secs {
+        let timer_id = ic_cdk_timers::set_timer_interval(Duration::from_secs(interval_secs), run);
+
+        timer.borrow_mut().replace(timer_id);
+    }
+}
+
+/// (Re)start the periodic cycles top-up with the interval of the current policy,
+/// or stop it when no policy is set.
+pub fn schedule_cycles_top_up() {
+    let interval_secs = with_setting(|s| s.cycles_top_up().map(|p| p.interval_secs));
+
+    TOP_UP_TIMER.with(|timer| {
+        restart_timer(timer, interval_secs, || {
+            ic_cdk::spawn(async {
+                run_cycles_top_up().await;
+            })
+        })
+    });
+}
+
+/// Run the cycles top-up policy once and record the run.
+pub async fn run_cycles_top_up() -> Option<TopUpRecord> {
+    let policy = with_setting(|s| s.cycles_top_up().cloned())?;
+
+    let cycles_balance = ic_cdk::api::canister_balance128();
+
+    let outcome = policy.run(ic_cdk::id(), cycles_balance).await;
+
+    log_cycle!("Cycles top up: {:?}", outcome);
+
+    let record = TopUpRecord::new(cycles_balance, outcome);
+
+    with_wallet_mut(|s| s.record_top_up(record.clone()));
+
+    Some(record)
+}
+
+/// (Re)start the periodic fleet top-up with the interval of the current policy,
+/// or stop it when no policy is set.
+pub fn schedule_fleet_top_up() {
+    let interval_secs = with_setting(|s| s.fleet_top_up().map(|p| p.interval_secs));
+
+    FLEET_TOP_UP_TIMER.with(|timer| {
+        restart_timer(timer, interval_secs, || {
+            ic_cdk::spawn(async {
+                run_fleet_top_up().await;
+            })
+        })
+    });
+}
+
+/// Restart the timer of the top-up whose policy the executed operation changed.
+pub fn reschedule_top_up(result: &OperationResult) {
+    match result {
+        OperationResult::CyclesTopUpUpdated(_) => schedule_cycles_top_up(),
+        OperationResult::FleetTopUpUpdated(_) => schedule_fleet_top_up(),
+        _ => {}
+    }
+}
+
+/// Check the watched canisters once, top up the low ones and record the report.
+pub async fn run_fleet_top_up() -> Option<FleetTopUpReport> {
+    let policy = with_setting(|s| s.fleet_top_up().cloned())?;
+
+    let report = policy.run().await;
+
+    log_cycle!(
+        "Fleet top up: {} of {} canisters for {} cycles",
+        report.topped_up().len(),
+        report.canisters.len(),
+        report.cycles
+    );
+
+    with_wallet_mut(|s| s.record_fleet_report(report.clone()));
+
+    Some(report)
+}
@@ -1,7 +1,7 @@
 use b3_utils::{
     api::{bugs::AppBug, AppAccountsNonce, AppInitArgs, AppStatus, Management},
     ledger::currency::{ICPToken, TokenAmount},
-    ledger::{Metadata, NotifyTopUpResult, TransferBlockIndex, Value},
+    ledger::{ICRCAccount, Metadata, NotifyTopUpResult, TransferBlockIndex, Value},
     log_cycle,
     logs::{export_log, export_log_messages_page, LogEntry},
     owner::caller_is_owner,
@@ -17,8 +17,14 @@ use b3wallet_lib::{
         btc::{network::BitcoinNetwork, types::UtxoStatus},
         chain::ChainTrait,
         ckbtc::{minter::Minter, types::RetrieveBtcStatus},
+        icrc::types::TxIndex,
+        icrc7::types::{Icrc7Token, TokenId},
+        options::SendOptions,
         subaccount::SubaccountEcdsaTrait,
-        types::{AddressMap, Balance, BtcPending, ChainEnum, PendingEnum, SendResult},
+        token::TokenMap,
+        types::{
+            AddressMap, BtcPending, ChainEnum, PendingEnum, SendResult, TransactionPage,
+        },
     },
     setting::WalletSettings,
     store::{
@@ -39,6 +45,7 @@ use ic_cdk::{
     },
     init, post_upgrade, pre_upgrade, query, update,
 };
+use std::str::FromStr;
 
 #[init]
 fn init() {
@@ -104,6 +111,11 @@ fn get_addresses(account_id: AccountId) -> AddressMap {
     with_ledger(&account_id, |ledger| ledger.address_map().clone()).unwrap_or_else(panic_log)
 }
 
+#[query(guard = "caller_is_owner")]
+fn get_tokens(account_id: AccountId) -> TokenMap {
+    with_ledger(&account_id, |ledger| ledger.token_map()).unwrap_or_else(panic_log)
+}
+
 #[query(guard = "caller_is_owner")]
 async fn retrieve_btc_status(
     network: BitcoinNetwork,
@@ -198,7 +210,28 @@ fn account_restore(env: Environment, nonce: u64) {
 }
 
 #[update(guard = "caller_is_owner")]
-async fn account_balance(account_id: AccountId, chain: ChainEnum) -> Balance {
+async fn account_icp_transactions(
+    account_id: AccountId,
+    start: Option<u64>,
+    max_results: u64,
+) -> TransactionPage {
+    log_cycle!(
+        "Get icp transactions for account: {} from: {:?}",
+        account_id,
+        start
+    );
+
+    let icp = with_chain(&account_id, &ChainEnum::ICP, |chain| chain.icp())
+        .unwrap_or_else(panic_log)
+        .unwrap_or_else(panic_log);
+
+    icp.transactions(start, max_results)
+        .await
+        .unwrap_or_else(panic_log)
+}
+
+#[update(guard = "caller_is_owner")]
+async fn account_balance(account_id: AccountId, chain: ChainEnum) -> TokenAmount {
     log_cycle!(
         "Get balance for account: {} on chain: {:?}",
         account_id,
@@ -221,6 +254,7 @@ async fn account_send(
     chain: ChainEnum,
     to: String,
     amount: TokenAmount,
+    options: Option<SendOptions>,
 ) -> SendResult {
     log_cycle!(
         "Send {} on chain: {:?} from account: {} to: {}",
@@ -233,7 +267,54 @@ async fn account_send(
     let ledger = with_ledger(&account_id, |ledger| ledger.clone()).unwrap_or_else(panic_log);
 
     ledger
-        .send(&chain, to, amount)
+        .send(&chain, to, amount, options.unwrap_or_default())
+        .await
+        .unwrap_or_else(panic_log)
+}
+
+#[update(guard = "caller_is_owner")]
+async fn account_nft_tokens(account_id: AccountId, collection: CanisterId) -> Vec<Icrc7Token> {
+    log_cycle!(
+        "Get nft tokens for account: {} on collection: {}",
+        account_id,
+        collection
+    );
+
+    let icrc7 = with_chain(&account_id, &ChainEnum::ICRC7(collection), |chain| {
+        chain.icrc7()
+    })
+    .unwrap_or_else(panic_log)
+    .unwrap_or_else(panic_log);
+
+    icrc7.tokens().await.unwrap_or_else(panic_log)
+}
+
+#[update(guard = "caller_is_owner")]
+async fn account_send_nft(
+    account_id: AccountId,
+    collection: CanisterId,
+    to: String,
+    token_id: TokenId,
+    memo: Option<Vec<u8>>,
+) -> TxIndex {
+    log_cycle!(
+        "Send nft {} of collection: {} from account: {} to: {}",
+        token_id,
+        collection,
+        account_id,
+        to
+    );
+
+    let icrc7 = with_chain(&account_id, &ChainEnum::ICRC7(collection), |chain| {
+        chain.icrc7()
+    })
+    .unwrap_or_else(panic_log)
+    .unwrap_or_else(panic_log);
+
+    let to = ICRCAccount::from_str(&to).unwrap_or_else(panic_log);
+
+    icrc7
+        .transfer(to, token_id, memo)
         .await
         .unwrap_or_else(panic_log)
 }
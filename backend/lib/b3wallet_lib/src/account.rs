@@ -1,9 +1,29 @@
-use crate::{ledger::ledger::Ledger, types::WalletAccountView};
+use crate::{
+    ledger::{ledger::Ledger, types::ChainEnum},
+    store::with_setting,
+    types::WalletAccountView,
+};
 use b3_utils::{
     ledger::{Metadata, Value},
     Environment, Subaccount,
 };
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// The names of the chains of the ledger, read from the settings.
+fn chain_names(ledger: &Ledger) -> BTreeMap<ChainEnum, String> {
+    with_setting(|s| {
+        ledger
+            .address_map()
+            .into_keys()
+            .map(|chain| {
+                let name = s.chain_name(&chain);
+
+                (chain, name)
+            })
+            .collect()
+    })
+}
 
 impl From<&WalletAccount> for WalletAccountView {
     fn from(account: &WalletAccount) -> Self {
@@ -15,6 +35,9 @@ impl From<&WalletAccount> for WalletAccountView {
             environment: account.environment().clone(),
             pendings: account.ledger.pendings(),
             addresses: account.ledger.address_map().clone(),
+            chain_names: chain_names(&account.ledger),
+            icp_icrc_address: account.ledger.icp_icrc_address(),
+            tokens: account.ledger.token_map(),
         }
     }
 }
@@ -76,6 +99,9 @@ impl WalletAccount {
             hidden: self.hidden,
             metadata: self.metadata.clone(),
             addresses: self.ledger.address_map(),
+            chain_names: chain_names(&self.ledger),
+            icp_icrc_address: self.ledger.icp_icrc_address(),
+            tokens: self.ledger.token_map(),
             pendings: self.ledger.pendings(),
             environment: self.ledger.subaccount.environment(),
         }
@@ -3,8 +3,9 @@ use candid::{CandidType, Deserialize};
 use std::fmt;
 
 use crate::ledger::{
-    btc::error::BitcoinError, ckbtc::error::CkbtcError, error::LedgerError, evm::error::EvmError,
-    icp::error::IcpError, icrc::error::IcrcError,
+    btc::error::BitcoinError, ckbtc::error::CkbtcError, cycles::error::CyclesError,
+    error::LedgerError, evm::error::EvmError, icp::error::IcpError, icrc::error::IcrcError,
+    icrc7::error::Icrc7Error,
 };
 
 #[rustfmt::skip]
@@ -13,8 +14,10 @@ pub enum WalletError {
     BitcoinError(BitcoinError),
     CkbtcError(CkbtcError),
     IcrcError(IcrcError),
+    Icrc7Error(Icrc7Error),
     EvmError(EvmError),
     IcpError(IcpError),
+    CyclesError(CyclesError),
     LedgerError(LedgerError),
     HelperError(HelperError),
     ManagmentError(ManagementError),
@@ -25,6 +28,8 @@ pub enum WalletError {
     SignerDoesNotExist(StoredPrincipal),
     UpdateSettingsError(String),
     NotifyTopUpError(String),
+    TopUpPolicyError(String),
+    CanisterCallError(String),
     CannotRemoveDefaultAccount,
     WalletAccountNotExists,
     WalletAlreadyInitialized,
@@ -44,12 +49,16 @@ impl fmt::Display for WalletError {
             WalletError::EvmError(ref err) => write!(f, "EVM Error::{}", err),
             WalletError::CkbtcError(ref err) => write!(f, "CKBTC Error::{}", err),
             WalletError::IcrcError(ref err) => write!(f, "ICRC Error::{}", err),
+            WalletError::Icrc7Error(ref err) => write!(f, "ICRC7 Error::{}", err),
             WalletError::IcpError(ref err) => write!(f, "ICP Error::{}", err),
+            WalletError::CyclesError(ref err) => write!(f, "Cycles Error::{}", err),
             WalletError::LedgerError(ref err) => write!(f, "Ledger Error::{}", err),
             WalletError::HelperError(ref err) => write!(f, "Helper Error::{}", err),
             WalletError::ManagmentError(ref err) => write!(f, "Managment Error::{}", err),
             WalletError::ExecutionError(ref msg) => write!(f, "Execution Error::{}", msg),
             WalletError::NotifyTopUpError(ref msg) => write!(f, "Notify top up Error::{}", msg),
+            WalletError::TopUpPolicyError(ref msg) => write!(f, "Top up policy Error::{}", msg),
+            WalletError::CanisterCallError(ref msg) => write!(f, "Canister call Error::{}", msg),
             WalletError::UpdateSettingsError(ref msg) => write!(f, "Update settings Error::{}", msg),
             WalletError::UnknownError => write!(f, "Unknown Error!"),
             WalletError::WasmNotLoaded => write!(f, "Wasm not loaded!"),
@@ -97,8 +106,20 @@ impl From<IcrcError> for WalletError {
     }
 }
 
+impl From<Icrc7Error> for WalletError {
+    fn from(value: Icrc7Error) -> Self {
+        WalletError::Icrc7Error(value)
+    }
+}
+
 impl From<IcpError> for WalletError {
     fn from(value: IcpError) -> Self {
         WalletError::IcpError(value)
     }
 }
+
+impl From<CyclesError> for WalletError {
+    fn from(value: CyclesError) -> Self {
+        WalletError::CyclesError(value)
+    }
+}
@@ -2,7 +2,9 @@ use crate::ledger::{
     chain::ChainTrait,
     ckbtc::minter::Minter,
     error::LedgerError,
-    types::{Balance, BtcPending, PendingEnum, SendResult},
+    options::SendOptions,
+    token::TokenDescriptor,
+    types::{BtcPending, PendingEnum, SendResult},
 };
 use async_trait::async_trait;
 use b3_utils::{
@@ -18,19 +20,31 @@ impl ChainTrait for BtcChain {
         self.address.clone()
     }
 
-    async fn balance(&self) -> Result<Balance, LedgerError> {
+    fn token(&self) -> TokenDescriptor {
+        TokenDescriptor::new(self.btc_network.symbol(), 8, None)
+    }
+
+    async fn balance(&self) -> Result<TokenAmount, LedgerError> {
         let address = self.address.clone();
 
-        self.btc_network
+        let balance = self
+            .btc_network
             .get_balance(address, self.min_confirmations)
             .await
-            .map_err(LedgerError::BitcoinError)
+            .map_err(LedgerError::BitcoinError)?;
+
+        Ok(self.token().amount(balance))
     }
 
-    async fn send(&self, to: String, amount: TokenAmount) -> Result<SendResult, LedgerError> {
-        let amount = amount
-            .to_satoshi()
-            .map_err(|e| LedgerError::InvalidAmountError(e.to_string()))?;
+    async fn send(
+        &self,
+        to: String,
+        amount: TokenAmount,
+        options: SendOptions,
+    ) -> Result<SendResult, LedgerError> {
+        options.reject_all("BTC")?;
+
+        let amount = self.token().to_base_units_u64(&amount)?;
 
         let result = self.transfer(to, amount).await;
 
@@ -35,6 +35,14 @@ impl Default for BitcoinNetwork {
 }
 
 impl BitcoinNetwork {
+    /// The ticker of the network's coin.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            BitcoinNetwork::Mainnet => "BTC",
+            BitcoinNetwork::Testnet | BitcoinNetwork::Regtest => "tBTC",
+        }
+    }
+
     /// Get the fee percentile.
     /// This is used to calculate the fee rate.
     pub async fn fee_percentiles(&self) -> Result<Vec<MillisatoshiPerByte>, BitcoinError> {
@@ -1,26 +1,38 @@
 use super::{
     btc::{btc::BtcChain, network::BitcoinNetwork},
     ckbtc::ckbtc::CkbtcChain,
+    cycles::cycles::{CyclesChain, CyclesLedger},
     ecdsa::ChainAddress,
     error::LedgerError,
     evm::api::EvmChain,
-    icp::icp::IcpChain,
+    icp::{icp::IcpChain, types::IcpRecipient},
     icrc::icrc::IcrcChain,
-    types::{Balance, ChainId, PendingEnum, SendResult},
+    icrc7::icrc7::Icrc7Chain,
+    options::{SendOptions, MAX_MEMO_LENGTH},
+    token::TokenDescriptor,
+    types::{ChainId, PendingEnum, SendResult},
 };
+use crate::store::with_setting;
 use async_trait::async_trait;
 use b3_utils::{ledger::currency::TokenAmount, types::CanisterId, Environment, Subaccount};
 use enum_dispatch::enum_dispatch;
 use libsecp256k1::PublicKey;
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 
 #[async_trait]
 #[enum_dispatch]
 pub trait ChainTrait {
     fn address(&self) -> String;
+    fn token(&self) -> TokenDescriptor;
     fn pendings(&self) -> Vec<PendingEnum>;
-    async fn balance(&self) -> Result<Balance, LedgerError>;
-    async fn send(&self, to: String, amount: TokenAmount) -> Result<SendResult, LedgerError>;
+    async fn balance(&self) -> Result<TokenAmount, LedgerError>;
+    async fn send(
+        &self,
+        to: String,
+        amount: TokenAmount,
+        options: SendOptions,
+    ) -> Result<SendResult, LedgerError>;
     async fn check_pending(&self, pending_index: usize) -> Result<(), LedgerError>;
     fn add_pending(&mut self, pending: PendingEnum);
     fn remove_pending(&mut self, pending_index: usize);
@@ -32,9 +44,11 @@ pub trait ChainTrait {
 pub enum Chain {
     CkbtcChain,
     IcrcChain,
+    Icrc7Chain,
     BtcChain,
     EvmChain,
     IcpChain,
+    CyclesChain,
 }
 
 impl Default for Chain {
@@ -56,6 +70,18 @@ impl Chain {
         Ok(chain)
     }
 
+    pub async fn new_icrc7_chain(
+        canister_id: CanisterId,
+        subaccount: Subaccount,
+    ) -> Result<Self, LedgerError> {
+        let icrc7 = Icrc7Chain::new(canister_id, subaccount)
+            .await
+            .map_err(LedgerError::Icrc7Error)?;
+        let chain = Chain::Icrc7Chain(icrc7);
+
+        Ok(chain)
+    }
+
     pub async fn new_ckbtc_chain(
         btc_network: BitcoinNetwork,
         subaccount: Subaccount,
@@ -87,18 +113,46 @@ impl Chain {
         Ok(chain)
     }
 
-    pub fn new_evm_chain(chain_id: ChainId, address: String) -> Self {
-        Chain::EvmChain(EvmChain {
+    pub fn new_evm_chain(
+        chain_id: ChainId,
+        subaccount: Subaccount,
+        ecdsa_public_key: PublicKey,
+    ) -> Result<Self, LedgerError> {
+        // only the networks of the registry can hold addresses
+        with_setting(|s| s.evm_network(chain_id)).map_err(LedgerError::EvmError)?;
+
+        let address = ecdsa_public_key.eth_address()?;
+
+        let chain = Chain::EvmChain(EvmChain {
+            pendings: Vec::new(),
+            tokens: Vec::new(),
+            collections: Vec::new(),
+            nonce: None,
+            ecdsa_public_key,
+            subaccount,
             chain_id,
             address,
-            pendings: Vec::new(),
-        })
+        });
+
+        Ok(chain)
     }
 
     pub fn new_icp_chain(subaccount: Subaccount) -> Self {
         Chain::IcpChain(IcpChain::new(subaccount))
     }
 
+    pub async fn new_cycles_chain(
+        ledger: CyclesLedger,
+        subaccount: Subaccount,
+    ) -> Result<Self, LedgerError> {
+        let cycles = CyclesChain::new(ledger, subaccount)
+            .await
+            .map_err(LedgerError::CyclesError)?;
+        let chain = Chain::CyclesChain(cycles);
+
+        Ok(chain)
+    }
+
     pub fn icrc(&self) -> Result<IcrcChain, LedgerError> {
         match self {
             Chain::IcrcChain(icrc) => Ok(icrc.clone()),
@@ -113,6 +167,20 @@ impl Chain {
         }
     }
 
+    pub fn icrc7(&self) -> Result<Icrc7Chain, LedgerError> {
+        match self {
+            Chain::Icrc7Chain(icrc7) => Ok(icrc7.clone()),
+            _ => Err(LedgerError::InvalidChain),
+        }
+    }
+
+    pub fn icrc7_mut(&mut self) -> Result<&mut Icrc7Chain, LedgerError> {
+        match self {
+            Chain::Icrc7Chain(icrc7) => Ok(icrc7),
+            _ => Err(LedgerError::InvalidChain),
+        }
+    }
+
     pub fn ckbtc(&self) -> Result<CkbtcChain, LedgerError> {
         match self {
             Chain::CkbtcChain(ckbtc) => Ok(ckbtc.clone()),
@@ -168,4 +236,61 @@ impl Chain {
             _ => Err(LedgerError::InvalidChain),
         }
     }
+
+    pub fn cycles(&self) -> Result<CyclesChain, LedgerError> {
+        match self {
+            Chain::CyclesChain(cycles) => Ok(cycles.clone()),
+            _ => Err(LedgerError::InvalidChain),
+        }
+    }
+
+    pub fn cycles_mut(&mut self) -> Result<&mut CyclesChain, LedgerError> {
+        match self {
+            Chain::CyclesChain(cycles) => Ok(cycles),
+            _ => Err(LedgerError::InvalidChain),
+        }
+    }
+
+    /// Check the send options before the send is approved, `send` checks them again.
+    pub fn check_send_options(&self, to: &str, options: &SendOptions) -> Result<(), LedgerError> {
+        match self {
+            Chain::IcpChain(icp) => {
+                options.from_subaccount(Some(icp.subaccount.clone()))?;
+
+                // account identifiers only take a numeric memo
+                match IcpRecipient::from_str(to) {
+                    Ok(IcpRecipient::AccountIdentifier(_)) => options.memo_u64().map(|_| ()),
+                    _ => options.check_memo(MAX_MEMO_LENGTH),
+                }
+            }
+            Chain::IcrcChain(icrc) => {
+                options.from_subaccount(Some(icrc.subaccount.clone()))?;
+                options.check_memo(MAX_MEMO_LENGTH)
+            }
+            Chain::CkbtcChain(ckbtc) => {
+                options.from_subaccount(ckbtc.account.subaccount())?;
+                options.check_memo(MAX_MEMO_LENGTH)
+            }
+            Chain::CyclesChain(cycles) => {
+                options.from_subaccount(Some(cycles.subaccount.clone()))?;
+                options.check_memo(MAX_MEMO_LENGTH)
+            }
+            Chain::BtcChain(_) => options.reject_all("BTC"),
+            Chain::EvmChain(_) => options.reject_all("EVM"),
+            Chain::Icrc7Chain(_) => Ok(()),
+        }
+    }
+
+    /// Stop tracking the pending transfer, found by value as the list may have
+    /// changed since it was read. Returns false if it is no longer tracked.
+    pub fn remove_pending_entry(&mut self, pending: &PendingEnum) -> bool {
+        match self.pendings().iter().position(|p| p == pending) {
+            Some(index) => {
+                self.remove_pending(index);
+
+                true
+            }
+            None => false,
+        }
+    }
 }
@@ -1,10 +1,12 @@
 use super::{ckbtc::CkbtcChain, error::CkbtcError, types::RetrieveBtcStatus};
 use crate::{
-    ledger::types::{Balance, SendResult},
+    ledger::types::SendResult,
     ledger::{
         chain::ChainTrait,
         error::LedgerError,
         icrc::{error::IcrcError, types::ICRC1TransferArgs},
+        options::{SendOptions, MAX_MEMO_LENGTH},
+        token::TokenDescriptor,
         types::{CkbtcPending, PendingEnum},
     },
 };
@@ -18,27 +20,40 @@ impl ChainTrait for CkbtcChain {
         self.account.to_string()
     }
 
-    async fn balance(&self) -> Result<Balance, LedgerError> {
+    fn token(&self) -> TokenDescriptor {
+        TokenDescriptor::new(self.symbol(), 8, self.fee.clone())
+    }
+
+    async fn balance(&self) -> Result<TokenAmount, LedgerError> {
         let account = self.account.clone();
 
         let result = self.ledger.balance_of(account).await;
 
         match result {
-            Ok(balance) => Ok(balance),
+            Ok(balance) => Ok(self.token().amount(balance)),
             Err(err) => Err(LedgerError::IcrcError(err)),
         }
     }
 
-    async fn send(&self, to: String, amount: TokenAmount) -> Result<SendResult, LedgerError> {
+    async fn send(
+        &self,
+        to: String,
+        amount: TokenAmount,
+        options: SendOptions,
+    ) -> Result<SendResult, LedgerError> {
         let to = ICRCAccount::from_str(&to).map_err(LedgerError::ICRCAccountError)?;
 
+        let token = self.token();
+
+        options.check_memo(MAX_MEMO_LENGTH)?;
+
         let transfer_args = ICRC1TransferArgs {
             to,
-            amount: amount.to_nat(),
-            from_subaccount: self.account.subaccount(),
-            fee: self.fee.clone(),
-            memo: self.memo.clone(),
-            created_at_time: self.created_at_time,
+            amount: token.to_base_units(&amount)?,
+            from_subaccount: options.from_subaccount(self.account.subaccount())?,
+            fee: options.fee_base_units(&token)?.or(self.fee.clone()),
+            memo: options.memo.or(self.memo.clone()),
+            created_at_time: options.created_at_time.or(self.created_at_time),
         };
 
         let result = self
@@ -63,6 +63,13 @@ impl CkbtcChain {
         })
     }
 
+    pub fn symbol(&self) -> &'static str {
+        match self.minter.0 {
+            BitcoinNetwork::Mainnet => "ckBTC",
+            BitcoinNetwork::Testnet | BitcoinNetwork::Regtest => "ckTESTBTC",
+        }
+    }
+
     pub async fn get_btc_address(&self) -> Result<String, CkbtcError> {
         let account = self.account.clone();
 
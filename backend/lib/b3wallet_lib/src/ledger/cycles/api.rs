@@ -0,0 +1,83 @@
+use super::{cycles::CyclesChain, error::CyclesError};
+use crate::ledger::{
+    chain::ChainTrait,
+    error::LedgerError,
+    icrc::types::ICRC1TransferArgs,
+    options::{SendOptions, MAX_MEMO_LENGTH},
+    token::TokenDescriptor,
+    types::{PendingEnum, SendResult},
+};
+use async_trait::async_trait;
+use b3_utils::ledger::{currency::TokenAmount, ICRCAccount};
+use std::str::FromStr;
+
+#[async_trait]
+impl ChainTrait for CyclesChain {
+    fn address(&self) -> String {
+        self.account().to_string()
+    }
+
+    fn token(&self) -> TokenDescriptor {
+        self.token_descriptor()
+    }
+
+    async fn balance(&self) -> Result<TokenAmount, LedgerError> {
+        let balance = self
+            .ledger
+            .balance_of(self.account())
+            .await
+            .map_err(LedgerError::CyclesError)?;
+
+        Ok(self.token().amount(balance))
+    }
+
+    async fn send(
+        &self,
+        to: String,
+        amount: TokenAmount,
+        options: SendOptions,
+    ) -> Result<SendResult, LedgerError> {
+        let to = ICRCAccount::from_str(&to).map_err(LedgerError::ICRCAccountError)?;
+
+        let token = self.token();
+
+        options.check_memo(MAX_MEMO_LENGTH)?;
+
+        let transfer_args = ICRC1TransferArgs {
+            to,
+            amount: token.to_base_units(&amount)?,
+            from_subaccount: options.from_subaccount(Some(self.subaccount.clone()))?,
+            fee: options.fee_base_units(&token)?.or(self.fee.clone()),
+            memo: options.memo,
+            created_at_time: options.created_at_time,
+        };
+
+        let result = self
+            .ledger
+            .transfer(transfer_args)
+            .await
+            .map_err(LedgerError::CyclesError)?;
+
+        match result {
+            Ok(tx_index) => Ok(SendResult::CYCLES(tx_index)),
+            Err(err) => Err(LedgerError::CyclesError(CyclesError::ICRC1TransferError(
+                err,
+            ))),
+        }
+    }
+
+    // transfers are final once the ledger answers, nothing is ever pending
+    async fn check_pending(&self, _pending_index: usize) -> Result<(), LedgerError> {
+        Ok(())
+    }
+
+    fn pendings(&self) -> Vec<PendingEnum> {
+        Vec::new()
+    }
+
+    fn add_pending(&mut self, _pending: PendingEnum) {}
+
+    fn remove_pending(&mut self, _pending_index: usize) {}
+
+    fn clear_pending(&mut self) {}
+}
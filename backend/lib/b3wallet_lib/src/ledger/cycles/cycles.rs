@@ -0,0 +1,150 @@
+use crate::ledger::{
+    icrc::types::{ICRC1TransferArgs, ICRC1TransferResult, ICRCTokens, TxIndex},
+    token::TokenDescriptor,
+};
+use b3_utils::{ledger::ICRCAccount, types::CanisterId, Subaccount};
+use candid::{CandidType, Principal};
+use ic_cdk::api::call::call;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    error::CyclesError,
+    types::{
+        CanisterSettings, CmcCreateCanisterArgs, CreateCanisterArgs, CreateCanisterResult,
+        CreateCanisterSuccess, Cycles, SubnetSelection, WithdrawArgs, WithdrawResult,
+        CYCLES_DECIMALS, CYCLES_LEDGER_CANISTER_ID, CYCLES_SYMBOL,
+    },
+};
+
+#[cfg(test)]
+use b3_utils::mocks::id_mock as ic_cdk_id;
+#[cfg(not(test))]
+use ic_cdk::api::id as ic_cdk_id;
+
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct CyclesLedger(pub CanisterId);
+
+impl Default for CyclesLedger {
+    fn default() -> Self {
+        CyclesLedger(Principal::from_text(CYCLES_LEDGER_CANISTER_ID).unwrap())
+    }
+}
+
+impl CyclesLedger {
+    pub async fn fee(&self) -> Result<ICRCTokens, CyclesError> {
+        let (res,): (ICRCTokens,) = call(self.0, "icrc1_fee", ())
+            .await
+            .map_err(|e| CyclesError::CallError(e.1))?;
+
+        Ok(res)
+    }
+
+    pub async fn balance_of(&self, account: ICRCAccount) -> Result<ICRCTokens, CyclesError> {
+        let (res,): (ICRCTokens,) = call(self.0, "icrc1_balance_of", (account,))
+            .await
+            .map_err(|e| CyclesError::CallError(e.1))?;
+
+        Ok(res)
+    }
+
+    pub async fn transfer(
+        &self,
+        args: ICRC1TransferArgs,
+    ) -> Result<ICRC1TransferResult, CyclesError> {
+        let (res,): (ICRC1TransferResult,) = call(self.0, "icrc1_transfer", (args,))
+            .await
+            .map_err(|e| CyclesError::CallError(e.1))?;
+
+        Ok(res)
+    }
+
+    pub async fn withdraw(&self, args: WithdrawArgs) -> Result<WithdrawResult, CyclesError> {
+        let (res,): (WithdrawResult,) = call(self.0, "withdraw", (args,))
+            .await
+            .map_err(|e| CyclesError::CallError(e.1))?;
+
+        Ok(res)
+    }
+
+    pub async fn create_canister(
+        &self,
+        args: CreateCanisterArgs,
+    ) -> Result<CreateCanisterResult, CyclesError> {
+        let (res,): (CreateCanisterResult,) = call(self.0, "create_canister", (args,))
+            .await
+            .map_err(|e| CyclesError::CallError(e.1))?;
+
+        Ok(res)
+    }
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct CyclesChain {
+    pub ledger: CyclesLedger,
+    pub subaccount: Subaccount,
+    pub fee: Option<ICRCTokens>,
+}
+
+impl CyclesChain {
+    pub async fn new(ledger: CyclesLedger, subaccount: Subaccount) -> Result<Self, CyclesError> {
+        let fee = ledger.fee().await?;
+
+        Ok(CyclesChain {
+            ledger,
+            subaccount,
+            fee: Some(fee),
+        })
+    }
+
+    pub fn account(&self) -> ICRCAccount {
+        ICRCAccount::new(ic_cdk_id(), Some(self.subaccount.clone()))
+    }
+
+    pub fn token_descriptor(&self) -> TokenDescriptor {
+        TokenDescriptor::new(CYCLES_SYMBOL, CYCLES_DECIMALS, self.fee.clone())
+    }
+
+    /// Deposit cycles from the account balance into any canister.
+    pub async fn withdraw(&self, to: CanisterId, amount: Cycles) -> Result<TxIndex, CyclesError> {
+        let args = WithdrawArgs {
+            amount: amount.into(),
+            from_subaccount: Some(self.subaccount.clone()),
+            to,
+            created_at_time: None,
+        };
+
+        match self.ledger.withdraw(args).await? {
+            Ok(block_index) => Ok(block_index),
+            Err(err) => Err(CyclesError::WithdrawError(err)),
+        }
+    }
+
+    /// Create a new canister paid with the given amount of cycles from the account balance.
+    /// Whatever isn't spent on the creation fee ends up in the new canister.
+    pub async fn create_canister(
+        &self,
+        amount: Cycles,
+        settings: Option<CanisterSettings>,
+        subnet_selection: Option<SubnetSelection>,
+    ) -> Result<CreateCanisterSuccess, CyclesError> {
+        let creation_args = match (&settings, &subnet_selection) {
+            (None, None) => None,
+            _ => Some(CmcCreateCanisterArgs {
+                settings,
+                subnet_selection,
+            }),
+        };
+
+        let args = CreateCanisterArgs {
+            from_subaccount: Some(self.subaccount.clone()),
+            created_at_time: None,
+            amount: amount.into(),
+            creation_args,
+        };
+
+        match self.ledger.create_canister(args).await? {
+            Ok(success) => Ok(success),
+            Err(err) => Err(CyclesError::CreateCanisterError(err)),
+        }
+    }
+}
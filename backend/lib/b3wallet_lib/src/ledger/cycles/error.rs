@@ -0,0 +1,94 @@
+use crate::ledger::icrc::{error::ICRC1TransferError, types::ICRCTimestamp};
+use candid::{CandidType, Deserialize, Nat, Principal};
+use std::fmt;
+
+#[rustfmt::skip]
+#[derive(CandidType, Clone, Debug, Deserialize, PartialEq, Eq)]
+pub enum CyclesError {
+    CallError(String),
+    ICRC1TransferError(ICRC1TransferError),
+    WithdrawError(WithdrawError),
+    CreateCanisterError(CreateCanisterError),
+}
+
+#[rustfmt::skip]
+impl fmt::Display for CyclesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CyclesError::CallError(ref msg) => write!(f, "Call Error::{}", msg),
+            CyclesError::ICRC1TransferError(ref err) => write!(f, "ICRC1 transfer Error::{}", err),
+            CyclesError::WithdrawError(ref err) => write!(f, "Withdraw Error::{}", err),
+            CyclesError::CreateCanisterError(ref err) => write!(f, "Create canister Error::{}", err),
+        }
+    }
+}
+
+#[rustfmt::skip]
+#[derive(CandidType, Clone, Debug, Deserialize, PartialEq, Eq)]
+pub enum RejectionCode {
+    NoError,
+    SysFatal,
+    SysTransient,
+    DestinationInvalid,
+    CanisterReject,
+    CanisterError,
+    Unknown,
+}
+
+#[rustfmt::skip]
+#[derive(CandidType, Clone, Debug, Deserialize, PartialEq, Eq)]
+pub enum WithdrawError {
+    BadFee { expected_fee: Nat },
+    InsufficientFunds { balance: Nat },
+    TooOld,
+    CreatedInFuture { ledger_time: ICRCTimestamp },
+    TemporarilyUnavailable,
+    Duplicate { duplicate_of: Nat },
+    FailedToWithdraw { fee_block: Option<Nat>, rejection_code: RejectionCode, rejection_reason: String },
+    GenericError { error_code: Nat, message: String },
+    InvalidReceiver { receiver: Principal },
+}
+
+#[rustfmt::skip]
+impl fmt::Display for WithdrawError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WithdrawError::BadFee { expected_fee } => write!(f, "Bad fee: expected {}", expected_fee),
+            WithdrawError::InsufficientFunds { balance } => write!(f, "Insufficient funds: balance is {}", balance),
+            WithdrawError::TooOld => write!(f, "Transaction is too old"),
+            WithdrawError::CreatedInFuture { ledger_time } => write!(f, "Transaction created in the future: {}", ledger_time),
+            WithdrawError::TemporarilyUnavailable => write!(f, "Temporarily unavailable"),
+            WithdrawError::Duplicate { duplicate_of } => write!(f, "Duplicate transaction: duplicate of {}", duplicate_of),
+            WithdrawError::FailedToWithdraw { rejection_code, rejection_reason, .. } => write!(f, "Failed to withdraw: {:?} - {}", rejection_code, rejection_reason),
+            WithdrawError::GenericError { error_code, message } => write!(f, "Generic error: {} - {}", error_code, message),
+            WithdrawError::InvalidReceiver { receiver } => write!(f, "Invalid receiver: {}", receiver),
+        }
+    }
+}
+
+#[rustfmt::skip]
+#[derive(CandidType, Clone, Debug, Deserialize, PartialEq, Eq)]
+pub enum CreateCanisterError {
+    InsufficientFunds { balance: Nat },
+    TooOld,
+    CreatedInFuture { ledger_time: ICRCTimestamp },
+    TemporarilyUnavailable,
+    Duplicate { duplicate_of: Nat, canister_id: Option<Principal> },
+    FailedToCreate { fee_block: Option<Nat>, refund_block: Option<Nat>, error: String },
+    GenericError { error_code: Nat, message: String },
+}
+
+#[rustfmt::skip]
+impl fmt::Display for CreateCanisterError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CreateCanisterError::InsufficientFunds { balance } => write!(f, "Insufficient funds: balance is {}", balance),
+            CreateCanisterError::TooOld => write!(f, "Transaction is too old"),
+            CreateCanisterError::CreatedInFuture { ledger_time } => write!(f, "Transaction created in the future: {}", ledger_time),
+            CreateCanisterError::TemporarilyUnavailable => write!(f, "Temporarily unavailable"),
+            CreateCanisterError::Duplicate { duplicate_of, .. } => write!(f, "Duplicate transaction: duplicate of {}", duplicate_of),
+            CreateCanisterError::FailedToCreate { error, .. } => write!(f, "Failed to create canister: {}", error),
+            CreateCanisterError::GenericError { error_code, message } => write!(f, "Generic error: {} - {}", error_code, message),
+        }
+    }
+}
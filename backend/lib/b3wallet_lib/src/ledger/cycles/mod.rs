@@ -0,0 +1,4 @@
+pub mod api;
+pub mod cycles;
+pub mod error;
+pub mod types;
@@ -0,0 +1,92 @@
+use crate::ledger::{
+    icrc::types::{ICRCTimestamp, TxIndex},
+    token::format_units,
+};
+use b3_utils::Subaccount;
+use candid::{CandidType, Nat, Principal};
+use serde::{Deserialize, Serialize};
+
+use super::error::{CreateCanisterError, WithdrawError};
+
+pub const CYCLES_LEDGER_CANISTER_ID: &str = "um5iw-rqaaa-aaaaq-qaaba-cai";
+
+/// The cycles ledger denominates balances in cycles, shown as trillions of cycles.
+pub const CYCLES_SYMBOL: &str = "TCYCLES";
+
+pub const CYCLES_DECIMALS: u8 = 12;
+
+pub type Cycles = u128;
+
+/// Cycles in a human readable form, e.g. `1.5 TCYCLES`.
+pub fn format_cycles(cycles: Cycles) -> String {
+    format!("{} {}", format_units(&cycles.into(), CYCLES_DECIMALS), CYCLES_SYMBOL)
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct WithdrawArgs {
+    pub amount: Nat,
+    pub from_subaccount: Option<Subaccount>,
+    pub to: Principal,
+    pub created_at_time: Option<ICRCTimestamp>,
+}
+
+pub type WithdrawResult = Result<TxIndex, WithdrawError>;
+
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Eq, Debug, Default)]
+pub struct CanisterSettings {
+    pub controllers: Option<Vec<Principal>>,
+    pub compute_allocation: Option<Nat>,
+    pub memory_allocation: Option<Nat>,
+    pub freezing_threshold: Option<Nat>,
+    pub reserved_cycles_limit: Option<Nat>,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct SubnetFilter {
+    pub subnet_type: Option<String>,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct SubnetId {
+    pub subnet: Principal,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub enum SubnetSelection {
+    Subnet(SubnetId),
+    Filter(SubnetFilter),
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct CmcCreateCanisterArgs {
+    pub settings: Option<CanisterSettings>,
+    pub subnet_selection: Option<SubnetSelection>,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct CreateCanisterArgs {
+    pub from_subaccount: Option<Subaccount>,
+    pub created_at_time: Option<ICRCTimestamp>,
+    pub amount: Nat,
+    pub creation_args: Option<CmcCreateCanisterArgs>,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct CreateCanisterSuccess {
+    pub block_id: TxIndex,
+    pub canister_id: Principal,
+}
+
+pub type CreateCanisterResult = Result<CreateCanisterSuccess, CreateCanisterError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_cycles() {
+        assert_eq!(format_cycles(1_500_000_000_000), "1.5 TCYCLES");
+        assert_eq!(format_cycles(100_000_000), "0.0001 TCYCLES");
+        assert_eq!(format_cycles(0), "0 TCYCLES");
+    }
+}
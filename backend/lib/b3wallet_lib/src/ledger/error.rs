@@ -3,8 +3,8 @@ use candid::{CandidType, Deserialize};
 use std::fmt;
 
 use crate::ledger::{
-    btc::error::BitcoinError, ckbtc::error::CkbtcError, evm::error::EvmError, icp::error::IcpError,
-    icrc::error::IcrcError,
+    btc::error::BitcoinError, ckbtc::error::CkbtcError, cycles::error::CyclesError,
+    evm::error::EvmError, icp::error::IcpError, icrc::error::IcrcError, icrc7::error::Icrc7Error,
 };
 
 #[rustfmt::skip]
@@ -13,8 +13,10 @@ pub enum LedgerError {
     BitcoinError(BitcoinError),
     CkbtcError(CkbtcError),
     IcrcError(IcrcError),
+    Icrc7Error(Icrc7Error),
     EvmError(EvmError),
     IcpError(IcpError),
+    CyclesError(CyclesError),
     CallError(String),
     PublicKeyError(String),
     SignatureError(String),
@@ -25,6 +27,7 @@ pub enum LedgerError {
     PendingIndexError(usize),
     UpdateBalanceError(String),
     InvalidAmountError(String),
+    UnsupportedSendOption(String),
     InvalidChain,
     MissingAddress,
     MissingEcdsaPublicKey,
@@ -52,12 +55,15 @@ impl fmt::Display for LedgerError {
             LedgerError::EvmError(ref err) => write!(f, "EVM error: {}", err),
             LedgerError::CkbtcError(ref err) => write!(f, "CKBTC error: {}", err),
             LedgerError::IcrcError(ref err) => write!(f, "ICRC error: {}", err),
+            LedgerError::Icrc7Error(ref err) => write!(f, "ICRC7 error: {}", err),
             LedgerError::IcpError(ref err) => write!(f, "ICP error: {}", err),
+            LedgerError::CyclesError(ref err) => write!(f, "Cycles error: {}", err),
             LedgerError::CallError(ref msg) => write!(f, "Call error: {}", msg),
             LedgerError::GenerateError(ref msg) => write!(f, "Generate error: {}", msg),
             LedgerError::UpdateBalanceError(ref msg) => write!(f, "Update balance error: {}", msg),
             LedgerError::EcdsaPublicKeyError(ref msg) => write!(f, "Ecdsa public key error: {}", msg),
             LedgerError::InvalidAmountError(ref msg) => write!(f, "Invalid amount error: {}", msg),
+            LedgerError::UnsupportedSendOption(ref msg) => write!(f, "Unsupported send option: {}", msg),
         }
     }
 }
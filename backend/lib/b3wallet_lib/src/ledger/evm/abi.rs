@@ -0,0 +1,651 @@
+use candid::{CandidType, Deserialize, Int};
+use std::fmt;
+
+use super::{
+    error::EvmError,
+    u256::U256,
+    utils::{keccak256, validate_address},
+};
+
+/// A Solidity type, as it is written in a function signature.
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
+pub enum AbiType {
+    Address,
+    Bool,
+    Uint(usize),
+    Int(usize),
+    FixedBytes(usize),
+    Bytes,
+    String,
+    Array(Box<AbiType>),
+    FixedArray(Box<AbiType>, usize),
+    Tuple(Vec<AbiType>),
+}
+
+/// A value of a Solidity type, the same `Array` is used for `T[]` and `T[N]`.
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
+pub enum AbiValue {
+    Address(String),
+    Bool(bool),
+    Uint(U256),
+    Int(Int),
+    FixedBytes(Vec<u8>),
+    Bytes(Vec<u8>),
+    String(String),
+    Array(Vec<AbiValue>),
+    Tuple(Vec<AbiValue>),
+}
+
+impl AbiType {
+    pub fn parse(kind: &str) -> Result<AbiType, EvmError> {
+        let kind = kind.trim();
+        let invalid = || EvmError::InvalidAbi(format!("unknown type {}", kind));
+
+        if let Some(item) = kind.strip_suffix(']') {
+            let open = item.rfind('[').ok_or_else(invalid)?;
+
+            let item_kind = Box::new(AbiType::parse(&item[..open])?);
+
+            return match &item[open + 1..] {
+                "" => Ok(AbiType::Array(item_kind)),
+                length => {
+                    let length = length.parse::<usize>().map_err(|_| invalid())?;
+
+                    Ok(AbiType::FixedArray(item_kind, length))
+                }
+            };
+        }
+
+        if let Some(items) = kind
+            .strip_prefix('(')
+            .and_then(|kind| kind.strip_suffix(')'))
+        {
+            let kinds = split_top_level(items)?
+                .into_iter()
+                .map(AbiType::parse)
+                .collect::<Result<Vec<AbiType>, EvmError>>()?;
+
+            return Ok(AbiType::Tuple(kinds));
+        }
+
+        let size = |prefix: &str, range: std::ops::RangeInclusive<usize>, step: usize| {
+            kind[prefix.len()..]
+                .parse::<usize>()
+                .ok()
+                .filter(|size| range.contains(size) && size % step == 0)
+                .ok_or_else(invalid)
+        };
+
+        match kind {
+            "address" => Ok(AbiType::Address),
+            "bool" => Ok(AbiType::Bool),
+            "string" => Ok(AbiType::String),
+            "bytes" => Ok(AbiType::Bytes),
+            "uint" => Ok(AbiType::Uint(256)),
+            "int" => Ok(AbiType::Int(256)),
+            _ if kind.starts_with("uint") => Ok(AbiType::Uint(size("uint", 8..=256, 8)?)),
+            _ if kind.starts_with("int") => Ok(AbiType::Int(size("int", 8..=256, 8)?)),
+            _ if kind.starts_with("bytes") => Ok(AbiType::FixedBytes(size("bytes", 1..=32, 1)?)),
+            _ => Err(invalid()),
+        }
+    }
+
+    /// Whether the value is encoded after the head, at an offset written in the head.
+    pub fn is_dynamic(&self) -> bool {
+        match self {
+            AbiType::Bytes | AbiType::String | AbiType::Array(_) => true,
+            AbiType::FixedArray(item, _) => item.is_dynamic(),
+            AbiType::Tuple(kinds) => kinds.iter().any(AbiType::is_dynamic),
+            _ => false,
+        }
+    }
+
+    /// The bytes the value takes in the head of its tuple.
+    pub fn head_size(&self) -> usize {
+        if self.is_dynamic() {
+            return 32;
+        }
+
+        match self {
+            AbiType::FixedArray(item, length) => item.head_size().saturating_mul(*length),
+            AbiType::Tuple(kinds) => kinds
+                .iter()
+                .fold(0, |size, kind| size.saturating_add(kind.head_size())),
+            _ => 32,
+        }
+    }
+}
+
+/// The canonical name of the type, as hashed into the function selector.
+impl fmt::Display for AbiType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AbiType::Address => write!(f, "address"),
+            AbiType::Bool => write!(f, "bool"),
+            AbiType::Uint(bits) => write!(f, "uint{}", bits),
+            AbiType::Int(bits) => write!(f, "int{}", bits),
+            AbiType::FixedBytes(size) => write!(f, "bytes{}", size),
+            AbiType::Bytes => write!(f, "bytes"),
+            AbiType::String => write!(f, "string"),
+            AbiType::Array(item) => write!(f, "{}[]", item),
+            AbiType::FixedArray(item, length) => write!(f, "{}[{}]", item, length),
+            AbiType::Tuple(kinds) => write!(f, "({})", join(kinds, ",")),
+        }
+    }
+}
+
+impl fmt::Display for AbiValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AbiValue::Address(address) => write!(f, "{}", address),
+            AbiValue::Bool(flag) => write!(f, "{}", flag),
+            AbiValue::Uint(number) => write!(f, "{}", number),
+            AbiValue::Int(number) => write!(f, "{}", number.0),
+            AbiValue::FixedBytes(bytes) | AbiValue::Bytes(bytes) => {
+                write!(f, "0x{}", hex::encode(bytes))
+            }
+            AbiValue::String(text) => write!(f, "{:?}", text),
+            AbiValue::Array(items) => write!(f, "[{}]", join(items, ", ")),
+            AbiValue::Tuple(items) => write!(f, "({})", join(items, ", ")),
+        }
+    }
+}
+
+/// A function of a contract, parsed from a signature like `approve(address,uint256)`.
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
+pub struct AbiFunction {
+    pub name: String,
+    pub inputs: Vec<AbiType>,
+}
+
+impl AbiFunction {
+    pub fn parse(signature: &str) -> Result<Self, EvmError> {
+        let signature = signature.trim();
+        let invalid = || EvmError::InvalidAbi(format!("invalid function {}", signature));
+
+        let open = signature.find('(').ok_or_else(invalid)?;
+        let name = signature[..open].trim();
+
+        let is_valid_name = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+
+        if !is_valid_name {
+            return Err(invalid());
+        }
+
+        match AbiType::parse(&signature[open..]).map_err(|_| invalid())? {
+            AbiType::Tuple(inputs) => Ok(AbiFunction {
+                name: name.to_string(),
+                inputs,
+            }),
+            _ => Err(invalid()),
+        }
+    }
+
+    /// The canonical signature, `uint` written as `uint256` and without spaces.
+    pub fn signature(&self) -> String {
+        format!("{}({})", self.name, join(&self.inputs, ","))
+    }
+
+    /// The first 4 bytes of the hash of the signature, that calldata starts with.
+    pub fn selector(&self) -> [u8; 4] {
+        let hash = keccak256(self.signature().as_bytes());
+
+        [hash[0], hash[1], hash[2], hash[3]]
+    }
+
+    pub fn encode_call(&self, args: &[AbiValue]) -> Result<Vec<u8>, EvmError> {
+        Ok([self.selector().to_vec(), encode(&self.inputs, args)?].concat())
+    }
+
+    pub fn decode_call(&self, data: &[u8]) -> Result<Vec<AbiValue>, EvmError> {
+        if data.len() < 4 || data[..4] != self.selector() {
+            return Err(EvmError::InvalidAbi(format!(
+                "the data is not a call to {}",
+                self.signature()
+            )));
+        }
+
+        decode(&self.inputs, &data[4..])
+    }
+
+    /// The call as it reads in Solidity, `approve(0xabc…, 1000000)`.
+    pub fn display_call(&self, args: &[AbiValue]) -> String {
+        format!("{}({})", self.name, join(args, ", "))
+    }
+}
+
+/// The reason a call reverted, from the `Error(string)` or `Panic(uint256)` it returned.
+pub fn decode_revert_reason(data: &[u8]) -> Option<String> {
+    let error = AbiFunction::parse("Error(string)").ok()?;
+    let panic = AbiFunction::parse("Panic(uint256)").ok()?;
+
+    if let Ok(values) = error.decode_call(data) {
+        if let [AbiValue::String(reason)] = values.as_slice() {
+            return Some(reason.clone());
+        }
+    }
+
+    if let Ok(values) = panic.decode_call(data) {
+        if let [AbiValue::Uint(code)] = values.as_slice() {
+            return Some(format!("panic code {}", code.to_hex()));
+        }
+    }
+
+    None
+}
+
+/// Encode the values as the tuple of their types, as the arguments of a call are.
+pub fn encode(kinds: &[AbiType], values: &[AbiValue]) -> Result<Vec<u8>, EvmError> {
+    if kinds.len() != values.len() {
+        return Err(EvmError::InvalidAbi(format!(
+            "expected {} values, found {}",
+            kinds.len(),
+            values.len()
+        )));
+    }
+
+    let head_size = kinds
+        .iter()
+        .fold(0usize, |size, kind| size.saturating_add(kind.head_size()));
+
+    let mut head = vec![];
+    let mut tail = vec![];
+
+    for (kind, value) in kinds.iter().zip(values) {
+        let encoded = encode_value(kind, value)?;
+
+        if kind.is_dynamic() {
+            let offset = U256::from((head_size + tail.len()) as u64);
+
+            head.extend_from_slice(&offset.to_be_bytes32());
+            tail.extend(encoded);
+        } else {
+            head.extend(encoded);
+        }
+    }
+
+    Ok([head, tail].concat())
+}
+
+/// Decode a tuple of the types, as the output of a call is.
+pub fn decode(kinds: &[AbiType], data: &[u8]) -> Result<Vec<AbiValue>, EvmError> {
+    let mut values = vec![];
+    let mut at = 0usize;
+
+    for kind in kinds {
+        let value = if kind.is_dynamic() {
+            let offset = read_length(data, at)?;
+
+            decode_value(kind, &data[offset..])?
+        } else {
+            decode_value(kind, data.get(at..).unwrap_or_default())?
+        };
+
+        values.push(value);
+        at = at.saturating_add(kind.head_size());
+    }
+
+    Ok(values)
+}
+
+fn encode_value(kind: &AbiType, value: &AbiValue) -> Result<Vec<u8>, EvmError> {
+    let mismatch = || EvmError::InvalidAbi(format!("{} is not a valid {}", value, kind));
+
+    match (kind, value) {
+        (AbiType::Address, AbiValue::Address(address)) => {
+            validate_address(address)?;
+
+            let bytes = hex::decode(&address[2..]).map_err(|_| mismatch())?;
+
+            let mut word = [0u8; 32];
+            word[12..].copy_from_slice(&bytes);
+
+            Ok(word.to_vec())
+        }
+        (AbiType::Bool, AbiValue::Bool(flag)) => {
+            Ok(U256::from(*flag as u64).to_be_bytes32().to_vec())
+        }
+        (AbiType::Uint(bits), AbiValue::Uint(number)) if number.bits() <= *bits as u64 => {
+            Ok(number.to_be_bytes32().to_vec())
+        }
+        (AbiType::Int(bits), AbiValue::Int(number)) => {
+            let magnitude = U256::try_from(candid::Nat(number.0.magnitude().clone()))?;
+
+            let word = if *number < Int::from(0) {
+                magnitude.to_negative_bytes32()
+            } else {
+                magnitude.to_be_bytes32()
+            };
+
+            // the sign of the word must be the sign of the number, and fit in the bits
+            let is_negative = word[0] >> 7 == 1;
+
+            if is_negative != (*number < Int::from(0)) || !fits_int(&word, *bits) {
+                return Err(mismatch());
+            }
+
+            Ok(word.to_vec())
+        }
+        (AbiType::FixedBytes(size), AbiValue::FixedBytes(bytes)) if bytes.len() == *size => {
+            let mut word = [0u8; 32];
+            word[..bytes.len()].copy_from_slice(bytes);
+
+            Ok(word.to_vec())
+        }
+        (AbiType::Bytes, AbiValue::Bytes(bytes)) => Ok(encode_bytes(bytes)),
+        (AbiType::String, AbiValue::String(text)) => Ok(encode_bytes(text.as_bytes())),
+        (AbiType::Array(item), AbiValue::Array(items)) => {
+            let length = U256::from(items.len() as u64).to_be_bytes32();
+            let kinds = vec![(**item).clone(); items.len()];
+
+            Ok([length.to_vec(), encode(&kinds, items)?].concat())
+        }
+        (AbiType::FixedArray(item, length), AbiValue::Array(items)) if items.len() == *length => {
+            encode(&vec![(**item).clone(); items.len()], items)
+        }
+        (AbiType::Tuple(kinds), AbiValue::Tuple(items)) => encode(kinds, items),
+        _ => Err(mismatch()),
+    }
+}
+
+fn decode_value(kind: &AbiType, data: &[u8]) -> Result<AbiValue, EvmError> {
+    let invalid = || EvmError::InvalidAbi(format!("invalid data for {}", kind));
+
+    match kind {
+        AbiType::Address => {
+            let word = read_word(data, 0)?;
+
+            if word[..12].iter().any(|byte| *byte != 0) {
+                return Err(invalid());
+            }
+
+            Ok(AbiValue::Address(format!("0x{}", hex::encode(&word[12..]))))
+        }
+        AbiType::Bool => match U256::from_be_bytes(&read_word(data, 0)?)?.to_u64() {
+            Some(0) => Ok(AbiValue::Bool(false)),
+            Some(1) => Ok(AbiValue::Bool(true)),
+            _ => Err(invalid()),
+        },
+        AbiType::Uint(bits) => {
+            let number = U256::from_be_bytes(&read_word(data, 0)?)?;
+
+            if number.bits() > *bits as u64 {
+                return Err(invalid());
+            }
+
+            Ok(AbiValue::Uint(number))
+        }
+        AbiType::Int(bits) => {
+            let word = read_word(data, 0)?;
+
+            if !fits_int(&word, *bits) {
+                return Err(invalid());
+            }
+
+            let number = if word[0] >> 7 == 1 {
+                let magnitude = U256::from_negative_bytes32(&word);
+
+                Int(-Int(magnitude.as_nat().0.clone().into()).0)
+            } else {
+                Int(U256::from_be_bytes(&word)?.as_nat().0.clone().into())
+            };
+
+            Ok(AbiValue::Int(number))
+        }
+        AbiType::FixedBytes(size) => {
+            Ok(AbiValue::FixedBytes(read_word(data, 0)?[..*size].to_vec()))
+        }
+        AbiType::Bytes => Ok(AbiValue::Bytes(read_bytes(data)?.to_vec())),
+        AbiType::String => {
+            let text = std::str::from_utf8(read_bytes(data)?).map_err(|_| invalid())?;
+
+            Ok(AbiValue::String(text.to_string()))
+        }
+        AbiType::Array(item) => {
+            let length = read_length(data, 0)?;
+
+            let kinds = vec![(**item).clone(); length];
+
+            Ok(AbiValue::Array(decode(&kinds, &data[32..])?))
+        }
+        AbiType::FixedArray(item, length) => {
+            // every item takes some bytes, more items than bytes can't be right
+            if *length > data.len() {
+                return Err(invalid());
+            }
+
+            let kinds = vec![(**item).clone(); *length];
+
+            Ok(AbiValue::Array(decode(&kinds, data)?))
+        }
+        AbiType::Tuple(kinds) => Ok(AbiValue::Tuple(decode(kinds, data)?)),
+    }
+}
+
+/// Whether the two's complement word holds an `intN`: the bits above N - 1 repeat the sign.
+fn fits_int(word: &[u8; 32], bits: usize) -> bool {
+    let sign = word[0] >> 7;
+
+    (bits.saturating_sub(1)..256).all(|bit| (word[31 - bit / 8] >> (bit % 8)) & 1 == sign)
+}
+
+fn encode_bytes(bytes: &[u8]) -> Vec<u8> {
+    let length = U256::from(bytes.len() as u64).to_be_bytes32();
+
+    let padding = (32 - bytes.len() % 32) % 32;
+
+    [&length[..], bytes, &vec![0u8; padding]].concat()
+}
+
+fn read_word(data: &[u8], at: usize) -> Result<[u8; 32], EvmError> {
+    at.checked_add(32)
+        .and_then(|end| data.get(at..end))
+        .and_then(|word| <[u8; 32]>::try_from(word).ok())
+        .ok_or_else(|| EvmError::InvalidAbi("the data is too short".to_string()))
+}
+
+/// An offset or a length, which can't be more than the data it is read from.
+fn read_length(data: &[u8], at: usize) -> Result<usize, EvmError> {
+    U256::from_be_bytes(&read_word(data, at)?)?
+        .to_u64()
+        .and_then(|length| usize::try_from(length).ok())
+        .filter(|length| *length <= data.len())
+        .ok_or_else(|| EvmError::InvalidAbi("the data is too short".to_string()))
+}
+
+fn read_bytes(data: &[u8]) -> Result<&[u8], EvmError> {
+    let length = read_length(data, 0)?;
+
+    data.get(32..32 + length)
+        .ok_or_else(|| EvmError::InvalidAbi("the data is too short".to_string()))
+}
+
+/// Split the items of a tuple on the commas that are not inside a nested tuple.
+fn split_top_level(items: &str) -> Result<Vec<&str>, EvmError> {
+    if items.trim().is_empty() {
+        return Ok(vec![]);
+    }
+
+    let mut parts = vec![];
+    let mut depth = 0usize;
+    let mut start = 0;
+
+    for (index, c) in items.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth = depth
+                    .checked_sub(1)
+                    .ok_or_else(|| EvmError::InvalidAbi(format!("unbalanced ({})", items)))?;
+            }
+            ',' if depth == 0 => {
+                parts.push(&items[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+
+    if depth != 0 {
+        return Err(EvmError::InvalidAbi(format!("unbalanced ({})", items)));
+    }
+
+    parts.push(&items[start..]);
+
+    Ok(parts)
+}
+
+fn join<T: fmt::Display>(items: &[T], separator: &str) -> String {
+    items
+        .iter()
+        .map(|item| item.to_string())
+        .collect::<Vec<String>>()
+        .join(separator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ledger::evm::utils::get_transfer_data;
+
+    #[test]
+    fn test_transfer_call() {
+        let function = AbiFunction::parse("transfer(address, uint)").unwrap();
+
+        assert_eq!(function.signature(), "transfer(address,uint256)");
+        assert_eq!(hex::encode(function.selector()), "a9059cbb");
+
+        let to = "0x7a9d2f53fea15e31f0a89d7f5d9e0e82b0b88ad6";
+        let amount = U256::from(12345u64);
+
+        let args = vec![
+            AbiValue::Address(to.to_string()),
+            AbiValue::Uint(amount.clone()),
+        ];
+
+        let data = function.encode_call(&args).unwrap();
+
+        assert_eq!(hex::encode(&data), get_transfer_data(to, &amount).unwrap());
+        assert_eq!(function.decode_call(&data).unwrap(), args);
+        assert_eq!(
+            function.display_call(&args),
+            "transfer(0x7a9d2f53fea15e31f0a89d7f5d9e0e82b0b88ad6, 12345)"
+        );
+    }
+
+    #[test]
+    fn test_revert_reason() {
+        let error = AbiFunction::parse("Error(string)").unwrap();
+        let data = error
+            .encode_call(&[AbiValue::String(
+                "ERC20: insufficient allowance".to_string(),
+            )])
+            .unwrap();
+
+        assert_eq!(hex::encode(&data[..4]), "08c379a0");
+        assert_eq!(
+            decode_revert_reason(&data),
+            Some("ERC20: insufficient allowance".to_string())
+        );
+
+        let panic = AbiFunction::parse("Panic(uint256)").unwrap();
+        let data = panic
+            .encode_call(&[AbiValue::Uint(U256::from(0x11u64))])
+            .unwrap();
+
+        assert_eq!(hex::encode(&data[..4]), "4e487b71");
+        assert_eq!(
+            decode_revert_reason(&data),
+            Some("panic code 0x11".to_string())
+        );
+
+        // a custom error, or no data at all
+        assert_eq!(
+            decode_revert_reason(&hex::decode("fb8f41b2").unwrap()),
+            None
+        );
+        assert_eq!(decode_revert_reason(&[]), None);
+    }
+
+    #[test]
+    fn test_dynamic_call() {
+        // the example of the Solidity ABI specification
+        let function = AbiFunction::parse("f(uint,uint32[],bytes10,bytes)").unwrap();
+
+        let args = vec![
+            AbiValue::Uint(U256::from(0x123u64)),
+            AbiValue::Array(vec![
+                AbiValue::Uint(U256::from(0x456u64)),
+                AbiValue::Uint(U256::from(0x789u64)),
+            ]),
+            AbiValue::FixedBytes(b"1234567890".to_vec()),
+            AbiValue::Bytes(b"Hello, world!".to_vec()),
+        ];
+
+        let expected = "8be65246\
+            0000000000000000000000000000000000000000000000000000000000000123\
+            0000000000000000000000000000000000000000000000000000000000000080\
+            3132333435363738393000000000000000000000000000000000000000000000\
+            00000000000000000000000000000000000000000000000000000000000000e0\
+            0000000000000000000000000000000000000000000000000000000000000002\
+            0000000000000000000000000000000000000000000000000000000000000456\
+            0000000000000000000000000000000000000000000000000000000000000789\
+            000000000000000000000000000000000000000000000000000000000000000d\
+            48656c6c6f2c20776f726c642100000000000000000000000000000000000000";
+
+        let data = function.encode_call(&args).unwrap();
+
+        assert_eq!(hex::encode(&data), expected);
+        assert_eq!(function.decode_call(&data).unwrap(), args);
+    }
+
+    #[test]
+    fn test_tuple_round_trip() {
+        let kinds = vec![AbiType::parse("(int8,(bool,string)[],address[2])").unwrap()];
+
+        let values = vec![AbiValue::Tuple(vec![
+            AbiValue::Int(Int::from(-128)),
+            AbiValue::Array(vec![AbiValue::Tuple(vec![
+                AbiValue::Bool(true),
+                AbiValue::String("b3".to_string()),
+            ])]),
+            AbiValue::Array(vec![
+                AbiValue::Address("0x7a9d2f53fea15e31f0a89d7f5d9e0e82b0b88ad6".to_string()),
+                AbiValue::Address("0x0000000000000000000000000000000000000001".to_string()),
+            ]),
+        ])];
+
+        let data = encode(&kinds, &values).unwrap();
+
+        assert_eq!(decode(&kinds, &data).unwrap(), values);
+        assert_eq!(kinds[0].to_string(), "(int8,(bool,string)[],address[2])");
+
+        // cut data is never read past its end
+        for length in 0..data.len() {
+            let _ = decode(&kinds, &data[..length]);
+        }
+
+        assert!(decode(&kinds, &data[..64]).is_err());
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(AbiType::parse("uint7").is_err());
+        assert!(AbiType::parse("bytes33").is_err());
+        assert!(AbiType::parse("(uint256").is_err());
+        assert!(AbiType::parse("address[x]").is_err());
+        assert!(AbiFunction::parse("approve").is_err());
+        assert!(AbiFunction::parse("(address)").is_err());
+
+        let int8 = [AbiType::Int(8)];
+
+        assert!(encode(&int8, &[AbiValue::Int(Int::from(-129))]).is_err());
+        assert!(encode(&int8, &[AbiValue::Int(Int::from(128))]).is_err());
+        assert!(encode(&[AbiType::Uint(8)], &[AbiValue::Uint(U256::from(256u64))]).is_err());
+        assert!(encode(&[AbiType::Bool], &[AbiValue::Uint(U256::zero())]).is_err());
+        assert!(encode(&[AbiType::Bool], &[]).is_err());
+    }
+}
@@ -1,20 +1,714 @@
-use crate::ledger::{
-    chain::ChainTrait,
-    error::LedgerError,
-    types::{Balance, ChainId, EvmPending, PendingEnum, SendResult},
+use crate::{
+    ledger::{
+        chain::ChainTrait,
+        error::LedgerError,
+        options::SendOptions,
+        subaccount::SubaccountEcdsaTrait,
+        token::TokenDescriptor,
+        types::{ChainEnum, ChainId, EvmPending, PendingEnum, SendResult},
+    },
+    store::{with_chain_mut, with_setting},
 };
 
-use b3_utils::ledger::currency::TokenAmount;
-use candid::CandidType;
+use super::{
+    abi::{decode, AbiFunction, AbiType, AbiValue},
+    error::EvmError,
+    evm::{get_recovery_id, EvmSignTrait, EvmTransaction},
+    gas::{EvmFees, EvmGasPolicy, GAS_LIMIT_MARGIN_PERCENT},
+    legacy::EvmTransactionLegacy,
+    london::EvmTransaction1559,
+    network::EvmNetwork,
+    rpc::{BlockTag, EvmRpc},
+    simulation::EvmSimulation,
+    types::{Erc20Token, EvmReceipt, NftCollection, NftStandard},
+    u256::U256,
+    user_operation::{Erc4337Config, UserOperation},
+    utils::{get_balance_of_data, hash_personal_message, hex_to_nat},
+};
+use b3_utils::{ledger::currency::TokenAmount, Subaccount};
+use libsecp256k1::PublicKey;
 use serde::{Deserialize, Serialize};
 
 use async_trait::async_trait;
 
-#[derive(CandidType, Clone, Deserialize, Serialize, PartialEq, Debug)]
+/// The gas used by a plain transfer of the native token.
+pub const TRANSFER_GAS_LIMIT: u64 = 21_000;
+
+/// How many blocks are looked at to suggest the fees.
+pub const FEE_HISTORY_BLOCKS: u64 = 5;
+
+/// How much the suggested fees are raised to replace a stuck transaction,
+/// nodes only accept a replacement paying at least 10% more.
+pub const REPLACEMENT_FEE_MULTIPLIER: u64 = 2;
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
 pub struct EvmChain {
     pub chain_id: ChainId,
     pub address: String,
+    pub subaccount: Subaccount,
+    pub ecdsa_public_key: PublicKey,
     pub pendings: Vec<EvmPending>,
+    pub tokens: Vec<Erc20Token>,
+    pub collections: Vec<NftCollection>,
+    /// The next nonce to use, ahead of the chain while transactions are in flight.
+    pub nonce: Option<u64>,
+}
+
+impl EvmChain {
+    /// The nonce for a new transaction, the local counter unless the chain is ahead of it.
+    pub fn next_nonce(&self, pending_count: u64) -> u64 {
+        self.nonce
+            .map_or(pending_count, |nonce| nonce.max(pending_count))
+    }
+
+    /// Take the next nonce, so another transaction sent in the meantime gets the one after.
+    pub fn reserve_nonce(&mut self, pending_count: u64) -> u64 {
+        let nonce = self.next_nonce(pending_count);
+
+        self.nonce = Some(nonce + 1);
+
+        nonce
+    }
+
+    /// Give back a nonce whose transaction never reached the chain,
+    /// unless a later one was taken since, which would leave a gap.
+    pub fn release_nonce(&mut self, nonce: u64) {
+        if self.nonce == Some(nonce + 1) {
+            self.nonce = Some(nonce);
+        }
+    }
+
+    /// Account for a nonce chosen outside the counter, e.g. by a signed transaction.
+    pub fn observe_nonce(&mut self, nonce: u64) {
+        self.nonce = Some(self.next_nonce(nonce + 1));
+    }
+
+    /// Forget the local counter, the next transaction uses the count of the chain.
+    pub fn reset_nonce(&mut self) {
+        self.nonce = None;
+    }
+
+    /// The pending transaction sent with the given nonce.
+    pub fn pending_with_nonce(&self, nonce: u64) -> Result<&EvmPending, EvmError> {
+        self.pendings
+            .iter()
+            .find(|pending| pending.nonce == nonce)
+            .ok_or(EvmError::NonceNotPending(nonce))
+    }
+
+    /// Track the transaction that replaced the pending one with the same nonce.
+    pub fn replace_pending(&mut self, pending: EvmPending) {
+        self.pendings.retain(|p| p.nonce != pending.nonce);
+
+        self.pendings.push(pending);
+    }
+
+    /// Run the callback on the stored chain, which holds the nonce counter.
+    /// The account of the chain is the one its subaccount belongs to.
+    fn with_stored<T, F>(&self, callback: F) -> Result<T, LedgerError>
+    where
+        F: FnOnce(&mut EvmChain) -> T,
+    {
+        let account_id = self.subaccount.id();
+
+        with_chain_mut(&account_id, ChainEnum::EVM(self.chain_id), |chain| {
+            chain.evm_mut().map(callback)
+        })
+        .map_err(|err| LedgerError::CallError(err.to_string()))?
+    }
+
+    /// The RPC canister configured in the wallet settings, with the providers of the network.
+    pub fn rpc(&self) -> EvmRpc {
+        with_setting(|s| s.evm_network_rpc(self.chain_id))
+    }
+
+    /// The network of the chain in the registry of the wallet settings.
+    pub fn network(&self) -> Result<EvmNetwork, EvmError> {
+        with_setting(|s| s.evm_network(self.chain_id))
+    }
+
+    /// Whether transactions pay a base fee and a tip, the default for unregistered networks.
+    pub fn is_eip1559(&self) -> bool {
+        self.network().map_or(true, |network| network.eip1559)
+    }
+
+    /// How the fees are chosen, from the wallet settings.
+    pub fn gas_policy(&self) -> EvmGasPolicy {
+        with_setting(|s| s.evm_gas_policy())
+    }
+
+    /// Refuse a transaction built for another chain or paying above the fee cap.
+    pub fn check_transaction(&self, transaction: &EvmTransaction) -> Result<(), EvmError> {
+        if transaction.chain_id() != self.chain_id {
+            return Err(EvmError::InvalidChainId(
+                self.chain_id,
+                transaction.chain_id(),
+            ));
+        }
+
+        let max_fee_per_gas = transaction.max_fee_per_gas().to_u64().unwrap_or(u64::MAX);
+
+        self.gas_policy().check_max_fee(max_fee_per_gas)
+    }
+
+    pub fn erc20_token(&self, address: &str) -> Result<&Erc20Token, EvmError> {
+        self.tokens
+            .iter()
+            .find(|token| token.is(address))
+            .ok_or_else(|| EvmError::UnknownToken(address.to_string()))
+    }
+
+    pub fn add_erc20_token(&mut self, token: Erc20Token) -> Result<(), EvmError> {
+        token.validate()?;
+
+        if let Some(index) = self.tokens.iter().position(|t| t.is(&token.address)) {
+            self.tokens[index] = token;
+        } else {
+            self.tokens.push(token);
+        }
+
+        Ok(())
+    }
+
+    pub fn remove_erc20_token(&mut self, address: &str) -> Result<(), EvmError> {
+        self.erc20_token(address)?;
+
+        self.tokens.retain(|token| !token.is(address));
+
+        Ok(())
+    }
+
+    /// The balance of the address in the given ERC-20 token, read with `balanceOf`.
+    pub async fn erc20_balance(&self, token: &Erc20Token) -> Result<TokenAmount, LedgerError> {
+        let data = get_balance_of_data(&self.address).map_err(LedgerError::EvmError)?;
+
+        let output = self
+            .rpc()
+            .eth_call(self.chain_id, &token.address, &format!("0x{}", data))
+            .await
+            .map_err(LedgerError::EvmError)?;
+
+        let balance = hex_to_nat(&output).map_err(LedgerError::EvmError)?;
+
+        Ok(token.descriptor().amount(balance))
+    }
+
+    pub fn nft_collection(&self, address: &str) -> Result<&NftCollection, EvmError> {
+        self.collections
+            .iter()
+            .find(|collection| collection.is(address))
+            .ok_or_else(|| EvmError::UnknownCollection(address.to_string()))
+    }
+
+    pub fn add_nft_collection(&mut self, collection: NftCollection) -> Result<(), EvmError> {
+        collection.validate()?;
+
+        if let Some(index) = self
+            .collections
+            .iter()
+            .position(|c| c.is(&collection.address))
+        {
+            self.collections[index] = collection;
+        } else {
+            self.collections.push(collection);
+        }
+
+        Ok(())
+    }
+
+    pub fn remove_nft_collection(&mut self, address: &str) -> Result<(), EvmError> {
+        self.nft_collection(address)?;
+
+        self.collections
+            .retain(|collection| !collection.is(address));
+
+        Ok(())
+    }
+
+    /// Call a view function of the contract with `eth_call`, returns its decoded outputs.
+    pub async fn call_function(
+        &self,
+        contract_address: &str,
+        function: &str,
+        args: &[AbiValue],
+        outputs: &[AbiType],
+    ) -> Result<Vec<AbiValue>, LedgerError> {
+        let data = AbiFunction::parse(function)
+            .and_then(|function| function.encode_call(args))
+            .map_err(LedgerError::EvmError)?;
+
+        let output = self
+            .rpc()
+            .eth_call(
+                self.chain_id,
+                contract_address,
+                &format!("0x{}", hex::encode(data)),
+            )
+            .await
+            .map_err(LedgerError::EvmError)?;
+
+        let output = hex::decode(output.strip_prefix("0x").unwrap_or(&output))
+            .map_err(|_| LedgerError::EvmError(EvmError::InvalidHex(output.clone())))?;
+
+        decode(outputs, &output).map_err(LedgerError::EvmError)
+    }
+
+    /// The owner of the ERC-721 token, read with `ownerOf`.
+    pub async fn nft_owner(
+        &self,
+        collection: &NftCollection,
+        token_id: &U256,
+    ) -> Result<String, LedgerError> {
+        collection
+            .expect_standard(NftStandard::Erc721)
+            .map_err(LedgerError::EvmError)?;
+
+        let outputs = self
+            .call_function(
+                &collection.address,
+                "ownerOf(uint256)",
+                &[AbiValue::Uint(token_id.clone())],
+                &[AbiType::Address],
+            )
+            .await?;
+
+        match outputs.as_slice() {
+            [AbiValue::Address(owner)] => Ok(owner.clone()),
+            _ => Err(LedgerError::EvmError(EvmError::InvalidResponse(
+                "ownerOf".to_string(),
+            ))),
+        }
+    }
+
+    /// How many of the token the address holds, one or zero for an ERC-721 token.
+    pub async fn nft_balance(
+        &self,
+        collection: &NftCollection,
+        token_id: &U256,
+    ) -> Result<U256, LedgerError> {
+        match collection.standard {
+            NftStandard::Erc721 => {
+                let owner = self.nft_owner(collection, token_id).await?;
+
+                if owner.eq_ignore_ascii_case(&self.address) {
+                    Ok(U256::from(1u64))
+                } else {
+                    Ok(U256::zero())
+                }
+            }
+            NftStandard::Erc1155 => {
+                let outputs = self
+                    .call_function(
+                        &collection.address,
+                        "balanceOf(address,uint256)",
+                        &[
+                            AbiValue::Address(self.address.clone()),
+                            AbiValue::Uint(token_id.clone()),
+                        ],
+                        &[AbiType::Uint(256)],
+                    )
+                    .await?;
+
+                match outputs.as_slice() {
+                    [AbiValue::Uint(balance)] => Ok(balance.clone()),
+                    _ => Err(LedgerError::EvmError(EvmError::InvalidResponse(
+                        "balanceOf".to_string(),
+                    ))),
+                }
+            }
+        }
+    }
+
+    /// Sign the transaction with the key of the account, returns the signed transaction.
+    pub async fn sign_transaction<T>(&self, transaction: &mut T) -> Result<Vec<u8>, LedgerError>
+    where
+        T: EvmSignTrait + Send,
+    {
+        let signature = self
+            .subaccount
+            .sign_with_ecdsa(transaction.unsigned_hash())
+            .await?;
+
+        transaction
+            .sign(signature, self.ecdsa_public_key)
+            .map_err(LedgerError::EvmError)
+    }
+
+    /// Sign a 32-byte hash with the key of the account, returns the 65-byte `r ‖ s ‖ v`
+    /// signature dapps expect, `v` being 27 or 28.
+    pub async fn sign_hash(&self, hash: [u8; 32]) -> Result<Vec<u8>, LedgerError> {
+        let signature = self.subaccount.sign_with_ecdsa(hash.to_vec()).await?;
+
+        let recovery_id = get_recovery_id(&hash, &signature, &self.ecdsa_public_key)
+            .map_err(LedgerError::EvmError)?;
+
+        Ok([signature, vec![27 + recovery_id.serialize()]].concat())
+    }
+
+    /// The smart account the key owns through the factory for the salt, deployed or not.
+    pub async fn smart_account_address(
+        &self,
+        config: &Erc4337Config,
+        salt: &U256,
+    ) -> Result<String, LedgerError> {
+        let outputs = self
+            .call_function(
+                &config.factory,
+                "getAddress(address,uint256)",
+                &[
+                    AbiValue::Address(self.address.clone()),
+                    AbiValue::Uint(salt.clone()),
+                ],
+                &[AbiType::Address],
+            )
+            .await?;
+
+        match outputs.as_slice() {
+            [AbiValue::Address(address)] => Ok(address.clone()),
+            _ => Err(LedgerError::EvmError(EvmError::InvalidResponse(
+                "getAddress".to_string(),
+            ))),
+        }
+    }
+
+    /// The next nonce of the smart account in the EntryPoint, with the default key.
+    pub async fn smart_account_nonce(
+        &self,
+        config: &Erc4337Config,
+        sender: &str,
+    ) -> Result<U256, LedgerError> {
+        let outputs = self
+            .call_function(
+                &config.entry_point,
+                "getNonce(address,uint192)",
+                &[
+                    AbiValue::Address(sender.to_string()),
+                    AbiValue::Uint(U256::zero()),
+                ],
+                &[AbiType::Uint(256)],
+            )
+            .await?;
+
+        match outputs.as_slice() {
+            [AbiValue::Uint(nonce)] => Ok(nonce.clone()),
+            _ => Err(LedgerError::EvmError(EvmError::InvalidResponse(
+                "getNonce".to_string(),
+            ))),
+        }
+    }
+
+    /// Whether a contract is deployed at the address.
+    pub async fn is_deployed(&self, address: &str) -> Result<bool, LedgerError> {
+        let code = self
+            .rpc()
+            .get_code(self.chain_id, address)
+            .await
+            .map_err(LedgerError::EvmError)?;
+
+        Ok(code.trim_start_matches("0x").chars().any(|c| c != '0'))
+    }
+
+    /// Sign the operation as the owner of its smart account, the way `SimpleAccount`
+    /// checks it: the EIP-191 hash of the operation hash. Returns the operation hash.
+    pub async fn sign_user_operation(
+        &self,
+        config: &Erc4337Config,
+        user_operation: &mut UserOperation,
+    ) -> Result<[u8; 32], LedgerError> {
+        let hash = user_operation
+            .hash(&config.entry_point, self.chain_id)
+            .map_err(LedgerError::EvmError)?;
+
+        user_operation.signature = self.sign_hash(hash_personal_message(&hash)).await?;
+
+        Ok(hash)
+    }
+
+    /// Sign the transaction and broadcast it through the RPC canister, returns its hash.
+    pub async fn send_transaction<T>(&self, transaction: &mut T) -> Result<String, LedgerError>
+    where
+        T: EvmSignTrait + Send,
+    {
+        let signed = self.sign_transaction(transaction).await?;
+
+        let tx_hash = self
+            .rpc()
+            .send_raw_transaction(self.chain_id, &signed)
+            .await
+            .map_err(LedgerError::EvmError)?;
+
+        Ok(tx_hash.unwrap_or_else(|| format!("0x{}", transaction.tx_id())))
+    }
+
+    /// Sign and broadcast the transaction as the network takes it, a legacy one
+    /// paying the max fee as its gas price on the networks without EIP-1559.
+    async fn send_for_network(
+        &self,
+        transaction: &mut EvmTransaction1559,
+    ) -> Result<String, LedgerError> {
+        if self.is_eip1559() {
+            self.send_transaction(transaction).await
+        } else {
+            self.send_transaction(&mut EvmTransactionLegacy::from(&*transaction))
+                .await
+        }
+    }
+
+    /// Sign and broadcast the transaction with the next nonce of the address.
+    /// The nonce is taken from the stored counter before signing, so transactions sent
+    /// at the same time never share one, and given back if the broadcast fails.
+    pub async fn send_with_next_nonce(
+        &self,
+        transaction: &mut EvmTransaction1559,
+    ) -> Result<EvmPending, LedgerError> {
+        let pending_count = self.pending_nonce().await?;
+
+        let nonce = self.with_stored(|evm| evm.reserve_nonce(pending_count))?;
+
+        transaction.nonce = nonce;
+
+        let sent = self.send_for_network(transaction).await;
+
+        match sent {
+            Ok(tx_hash) => Ok(EvmPending { tx_hash, nonce }),
+            Err(err) => {
+                self.with_stored(|evm| evm.release_nonce(nonce)).ok();
+
+                Err(err)
+            }
+        }
+    }
+
+    /// The unsigned transaction calling `to` with the fees, an empty `to` deploys `data`.
+    pub fn call_transaction(
+        &self,
+        nonce: u64,
+        to: &str,
+        value: U256,
+        data: String,
+        fees: &EvmFees,
+    ) -> EvmTransaction1559 {
+        EvmTransaction1559 {
+            nonce,
+            chain_id: self.chain_id,
+            max_priority_fee_per_gas: fees.max_priority_fee_per_gas.into(),
+            max_fee_per_gas: fees.max_fee_per_gas.into(),
+            gas_limit: fees.gas_limit,
+            to: to.to_string(),
+            value,
+            data,
+            access_list: vec![],
+            v: "0x00".to_string(),
+            r: "0x00".to_string(),
+            s: "0x00".to_string(),
+        }
+    }
+
+    /// Send the call with the next nonce, as a legacy transaction on the networks
+    /// without EIP-1559, and track it as pending. Fees above the cap are refused.
+    pub async fn send_call(
+        &self,
+        to: &str,
+        value: U256,
+        data: String,
+        fees: &EvmFees,
+    ) -> Result<EvmPending, LedgerError> {
+        self.gas_policy()
+            .check_max_fee(fees.max_fee_per_gas)
+            .map_err(LedgerError::EvmError)?;
+
+        let mut transaction = self.call_transaction(0, to, value, data, fees);
+
+        let pending = self.send_with_next_nonce(&mut transaction).await?;
+
+        self.with_stored(|evm| evm.add_pending(PendingEnum::EVM(pending.clone())))?;
+
+        Ok(pending)
+    }
+
+    /// Sign and broadcast a transaction that already has its nonce,
+    /// the counter is moved past it so the next ones do not collide.
+    /// An EIP-1559 transaction is sent as a legacy one where the network needs it.
+    pub async fn send_with_own_nonce(
+        &self,
+        transaction: &mut EvmTransaction,
+    ) -> Result<EvmPending, LedgerError> {
+        self.check_transaction(transaction)
+            .map_err(LedgerError::EvmError)?;
+
+        let nonce = transaction.nonce();
+
+        let tx_hash = match transaction {
+            EvmTransaction::EvmTransaction1559(transaction) => {
+                self.send_for_network(transaction).await?
+            }
+            transaction => self.send_transaction(transaction).await?,
+        };
+
+        self.with_stored(|evm| evm.observe_nonce(nonce))?;
+
+        Ok(EvmPending { tx_hash, nonce })
+    }
+
+    /// The outcome of the pending transaction, `None` while it can still be mined.
+    pub async fn receipt(&self, pending: &EvmPending) -> Result<Option<EvmReceipt>, LedgerError> {
+        let rpc = self.rpc();
+
+        let receipt = rpc
+            .get_transaction_receipt(self.chain_id, &pending.tx_hash)
+            .await
+            .map_err(LedgerError::EvmError)?;
+
+        if receipt.is_some() {
+            return Ok(receipt);
+        }
+
+        let mined_count = rpc
+            .get_transaction_count(self.chain_id, self.address.clone(), BlockTag::Latest)
+            .await
+            .map_err(LedgerError::EvmError)?;
+
+        if mined_count <= pending.nonce {
+            return Ok(None);
+        }
+
+        // the nonce is used, look again in case it was mined in between
+        let receipt = rpc
+            .get_transaction_receipt(self.chain_id, &pending.tx_hash)
+            .await
+            .map_err(LedgerError::EvmError)?;
+
+        Ok(Some(
+            receipt.unwrap_or_else(|| EvmReceipt::dropped(&pending.tx_hash)),
+        ))
+    }
+
+    /// The receipt of the pending transaction at the index, fails while it isn't mined.
+    pub async fn check_receipt(&self, pending_index: usize) -> Result<EvmReceipt, LedgerError> {
+        let pending = self
+            .pendings
+            .get(pending_index)
+            .ok_or(LedgerError::PendingIndexError(pending_index))?;
+
+        self.receipt(pending).await?.ok_or_else(|| {
+            LedgerError::EvmError(EvmError::TransactionPending(pending.tx_hash.clone()))
+        })
+    }
+
+    /// The next nonce of the address, counting the transactions still in the mempool.
+    pub async fn pending_nonce(&self) -> Result<u64, LedgerError> {
+        self.rpc()
+            .get_transaction_count(self.chain_id, self.address.clone(), BlockTag::Pending)
+            .await
+            .map_err(LedgerError::EvmError)
+    }
+
+    /// The `(max_fee_per_gas, max_priority_fee_per_gas)` suggested by the recent blocks,
+    /// following the gas policy of the wallet.
+    pub async fn suggested_fees(&self) -> Result<(u64, u64), LedgerError> {
+        let policy = self.gas_policy();
+
+        if !self.is_eip1559() {
+            // a legacy transaction pays the gas price as its max fee, all of it a tip
+            let gas_price = self
+                .rpc()
+                .gas_price(self.chain_id)
+                .await
+                .and_then(|gas_price| policy.check_max_fee(gas_price).map(|_| gas_price))
+                .map_err(LedgerError::EvmError)?;
+
+            return Ok((gas_price, gas_price));
+        }
+
+        self.rpc()
+            .fee_history(
+                self.chain_id,
+                FEE_HISTORY_BLOCKS,
+                vec![policy.speed.reward_percentile()],
+            )
+            .await
+            .and_then(|history| policy.suggested_fees(&history))
+            .map_err(LedgerError::EvmError)
+    }
+
+    /// The gas limit for the transaction, the estimate of the chain with some margin.
+    pub async fn estimate_gas(
+        &self,
+        to: &str,
+        value: &U256,
+        data: &str,
+    ) -> Result<u64, LedgerError> {
+        let estimate = self
+            .rpc()
+            .estimate_gas(self.chain_id, &self.address, to, value, data)
+            .await
+            .map_err(LedgerError::EvmError)?;
+
+        Ok(estimate + estimate * GAS_LIMIT_MARGIN_PERCENT / 100)
+    }
+
+    /// Run the transaction against the latest block without sending it, a revert
+    /// comes back with the reason the contract gave.
+    pub async fn simulate(&self, to: &str, value: &U256, data: &str) -> EvmSimulation {
+        let rpc = self.rpc();
+
+        let output = match rpc
+            .simulate_call(self.chain_id, &self.address, to, value, data)
+            .await
+        {
+            Ok(output) => output,
+            Err(err) => return EvmSimulation::from_error(&err),
+        };
+
+        match rpc
+            .estimate_gas(self.chain_id, &self.address, to, value, data)
+            .await
+        {
+            Ok(gas) => EvmSimulation::Success { output, gas },
+            Err(err) => EvmSimulation::from_error(&err),
+        }
+    }
+
+    /// The fees to send the transaction with, the ones left empty are filled in
+    /// from the chain and the gas policy of the wallet.
+    pub async fn fees(
+        &self,
+        to: &str,
+        value: &U256,
+        data: &str,
+        gas_limit: Option<u64>,
+        max_fee_per_gas: Option<u64>,
+        max_priority_fee_per_gas: Option<u64>,
+    ) -> Result<EvmFees, LedgerError> {
+        let gas_limit = match gas_limit {
+            Some(gas_limit) => gas_limit,
+            None => self.estimate_gas(to, value, data).await?,
+        };
+
+        let (max_fee_per_gas, max_priority_fee_per_gas) =
+            match (max_fee_per_gas, max_priority_fee_per_gas) {
+                (Some(max_fee), Some(priority_fee)) => (max_fee, priority_fee),
+                (max_fee, priority_fee) => {
+                    let (suggested_max_fee, suggested_priority_fee) = self.suggested_fees().await?;
+
+                    (
+                        max_fee.unwrap_or(suggested_max_fee),
+                        priority_fee.unwrap_or(suggested_priority_fee),
+                    )
+                }
+            };
+
+        self.gas_policy()
+            .check_max_fee(max_fee_per_gas)
+            .map_err(LedgerError::EvmError)?;
+
+        Ok(EvmFees {
+            gas_limit,
+            max_fee_per_gas,
+            // the tip can never be more than the whole fee
+            max_priority_fee_per_gas: max_priority_fee_per_gas.min(max_fee_per_gas),
+        })
+    }
 }
 
 #[async_trait]
@@ -25,16 +719,50 @@ impl ChainTrait for EvmChain {
         address
     }
 
-    async fn balance(&self) -> Result<Balance, LedgerError> {
-        Ok(Balance::from(0u8))
+    fn token(&self) -> TokenDescriptor {
+        self.network()
+            .map(|network| network.token())
+            .unwrap_or_else(|_| TokenDescriptor::new("ETH", 18, None))
+    }
+
+    async fn balance(&self) -> Result<TokenAmount, LedgerError> {
+        let balance = self
+            .rpc()
+            .get_balance(self.chain_id, &self.address)
+            .await
+            .map_err(LedgerError::EvmError)?;
+
+        Ok(self.token().amount(balance))
     }
 
-    async fn send(&self, _to: String, _amount: TokenAmount) -> Result<SendResult, LedgerError> {
-        todo!("implement the async method for EVM...")
+    async fn send(
+        &self,
+        to: String,
+        amount: TokenAmount,
+        options: SendOptions,
+    ) -> Result<SendResult, LedgerError> {
+        options.reject_all("EVM")?;
+
+        let value = self
+            .token()
+            .to_base_units(&amount)
+            .and_then(|base_units| U256::try_from(base_units).map_err(LedgerError::EvmError))?;
+
+        let (max_fee_per_gas, max_priority_fee_per_gas) = self.suggested_fees().await?;
+
+        let fees = EvmFees {
+            gas_limit: TRANSFER_GAS_LIMIT,
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+        };
+
+        let pending = self.send_call(&to, value, "".to_string(), &fees).await?;
+
+        Ok(SendResult::EVM(pending))
     }
 
-    async fn check_pending(&self, _pending_index: usize) -> Result<(), LedgerError> {
-        todo!("implement the async method for EVM...")
+    async fn check_pending(&self, pending_index: usize) -> Result<(), LedgerError> {
+        self.check_receipt(pending_index).await.map(|_| ())
     }
 
     fn pendings(&self) -> Vec<PendingEnum> {
@@ -44,9 +772,16 @@ impl ChainTrait for EvmChain {
             .collect()
     }
 
+    /// A transaction already tracked, e.g. by `send_call`, is not added twice.
     fn add_pending(&mut self, pending: PendingEnum) {
         if let PendingEnum::EVM(p) = pending {
-            self.pendings.push(p);
+            if !self
+                .pendings
+                .iter()
+                .any(|tracked| tracked.tx_hash == p.tx_hash)
+            {
+                self.pendings.push(p);
+            }
         }
     }
 
@@ -1,7 +1,9 @@
 use super::error::EvmError;
 use super::evm::{decode_access_list, encode_access_list, get_recovery_id, EvmSignTrait};
+use super::u256::U256;
 use super::utils::{
-    remove_leading, string_to_vec_u8, u64_to_vec_u8, vec_u8_to_string, vec_u8_to_u64,
+    remove_leading, rlp_address, rlp_bytes, rlp_hex, rlp_items, rlp_u256, rlp_u64,
+    string_to_vec_u8, u64_to_vec_u8, vec_u8_to_string,
 };
 use candid::{CandidType, Deserialize};
 use libsecp256k1::{PublicKey, RecoveryId};
@@ -11,10 +13,10 @@ use tiny_keccak::{Hasher, Keccak};
 pub struct EvmTransaction2930 {
     pub chain_id: u64,
     pub nonce: u64,
-    pub gas_price: u64,
+    pub gas_price: U256,
     pub gas_limit: u64,
     pub to: String,
-    pub value: u64,
+    pub value: U256,
     pub data: String,
     pub access_list: Vec<(String, Vec<String>)>,
     pub v: String,
@@ -22,56 +24,34 @@ pub struct EvmTransaction2930 {
     pub s: String,
 }
 
-impl From<Vec<u8>> for EvmTransaction2930 {
-    fn from(data: Vec<u8>) -> Self {
-        let rlp = rlp::Rlp::new(&data[1..]);
+impl TryFrom<Vec<u8>> for EvmTransaction2930 {
+    type Error = EvmError;
 
-        let chain_id_hex = rlp.at(0).as_val::<Vec<u8>>();
-        let chain_id = vec_u8_to_u64(&chain_id_hex);
-
-        let nonce_hex = rlp.at(1).as_val::<Vec<u8>>();
-        let nonce = vec_u8_to_u64(&nonce_hex);
-
-        let gas_price_hex = rlp.at(2).as_val::<Vec<u8>>();
-        let gas_price = vec_u8_to_u64(&gas_price_hex);
-
-        let gas_limit_hex = rlp.at(3).as_val::<Vec<u8>>();
-        let gas_limit = vec_u8_to_u64(&gas_limit_hex);
-
-        let to_hex = rlp.at(4).as_val::<Vec<u8>>();
-        let to = vec_u8_to_string(&to_hex);
-
-        let value_hex = rlp.at(5).as_val::<Vec<u8>>();
-        let value = vec_u8_to_u64(&value_hex);
-
-        let data_tx_hex = rlp.at(6).as_val::<Vec<u8>>();
-        let data_tx = vec_u8_to_string(&data_tx_hex);
-
-        let access_list = decode_access_list(&rlp.at(7).as_raw().to_vec());
-
-        let v_hex = rlp.at(8).as_val::<Vec<u8>>();
-        let v = vec_u8_to_string(&v_hex);
-
-        let r_hex = rlp.at(9).as_val::<Vec<u8>>();
-        let r = vec_u8_to_string(&r_hex);
+    fn try_from(data: Vec<u8>) -> Result<Self, Self::Error> {
+        let payload = match data.split_first() {
+            Some((0x01, payload)) => payload,
+            Some(_) => return Err(EvmError::InvalidTransactionType),
+            None => return Err(EvmError::InvalidLength("transaction".to_string(), 0)),
+        };
 
-        let s_hex = rlp.at(10).as_val::<Vec<u8>>();
-        let s = vec_u8_to_string(&s_hex);
-        EvmTransaction2930 {
-            chain_id,
-            nonce,
-            gas_price,
-            gas_limit,
-            to,
-            data: data_tx,
-            value,
-            access_list,
-            v,
-            r,
-            s,
-        }
+        let items = rlp_items(payload, 11)?;
+
+        Ok(EvmTransaction2930 {
+            chain_id: rlp_u64(&items[0], "chain_id")?,
+            nonce: rlp_u64(&items[1], "nonce")?,
+            gas_price: rlp_u256(&items[2], "gas_price")?,
+            gas_limit: rlp_u64(&items[3], "gas_limit")?,
+            to: rlp_address(&items[4])?,
+            value: rlp_u256(&items[5], "value")?,
+            data: vec_u8_to_string(&rlp_bytes(&items[6])?),
+            access_list: decode_access_list(items[7].as_raw())?,
+            v: rlp_hex(&items[8], "v", 8)?,
+            r: rlp_hex(&items[9], "r", 32)?,
+            s: rlp_hex(&items[10], "s", 32)?,
+        })
     }
 }
+
 impl EvmSignTrait for EvmTransaction2930 {
     fn sign(&mut self, signature: Vec<u8>, public_key: PublicKey) -> Result<Vec<u8>, EvmError> {
         let r_remove_leading_zeros = remove_leading(signature[..32].to_vec(), 0);
@@ -81,7 +61,7 @@ impl EvmSignTrait for EvmTransaction2930 {
 
         let s = vec_u8_to_string(&s_remove_leading_zeros);
 
-        let message = self.unsigned_serialized();
+        let message = self.unsigned_hash();
 
         let recovery_id = get_recovery_id(&message, &signature, &public_key)?;
 
@@ -149,10 +129,10 @@ impl EvmSignTrait for EvmTransaction2930 {
         let items = [
             u64_to_vec_u8(&self.chain_id),
             u64_to_vec_u8(&self.nonce),
-            u64_to_vec_u8(&self.gas_price),
+            self.gas_price.to_be_bytes(),
             u64_to_vec_u8(&self.gas_limit),
             string_to_vec_u8(&self.to),
-            u64_to_vec_u8(&self.value),
+            self.value.to_be_bytes(),
             string_to_vec_u8(&self.data),
         ];
 
@@ -179,7 +159,7 @@ impl EvmSignTrait for EvmTransaction2930 {
         let nonce = u64_to_vec_u8(&self.nonce);
         stream.append(&nonce);
 
-        let gas_price = u64_to_vec_u8(&self.gas_price);
+        let gas_price = self.gas_price.to_be_bytes();
         stream.append(&gas_price);
 
         let gas_limit = u64_to_vec_u8(&self.gas_limit);
@@ -188,7 +168,7 @@ impl EvmSignTrait for EvmTransaction2930 {
         let to = string_to_vec_u8(&self.to[..]);
         stream.append(&to);
 
-        let value = u64_to_vec_u8(&self.value);
+        let value = self.value.to_be_bytes();
         stream.append(&value);
 
         let data = string_to_vec_u8(&self.data[..]);
@@ -263,13 +243,13 @@ mod tests {
             nonce: 0,
             gas_limit: 0,
             to: "0x".to_string(),
-            value: 0,
+            value: U256::zero(),
             data: "0x".to_string(),
             access_list: vec![],
             v: "".to_string(),
             r: "".to_string(),
             s: "".to_string(),
-            gas_price: 0,
+            gas_price: U256::zero(),
         };
 
         // compute the unsigned serialization
@@ -304,4 +284,23 @@ mod tests {
 
         assert_eq!(hash, expected);
     }
+
+    #[test]
+    fn test_round_trip_above_u64() {
+        let tx = EvmTransaction2930 {
+            chain_id: 1,
+            nonce: 7,
+            gas_price: U256::from(u64::MAX as u128 + 1),
+            gas_limit: 21_000,
+            to: "7a9d2f53fea15e31f0a89d7f5d9e0e82b0b88ad6".to_string(),
+            value: U256::from(100_000_000_000_000_000_000u128),
+            data: "a9059cbb".to_string(),
+            access_list: vec![],
+            v: "01".to_string(),
+            r: "0a".to_string(),
+            s: "0b".to_string(),
+        };
+
+        assert_eq!(EvmTransaction2930::try_from(tx.serialized()).unwrap(), tx);
+    }
 }
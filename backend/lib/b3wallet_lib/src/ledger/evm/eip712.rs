@@ -0,0 +1,483 @@
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use super::{error::EvmError, u256::U256, utils::keccak256};
+
+/// The struct the domain of the typed data is described with.
+pub const DOMAIN_TYPE: &str = "EIP712Domain";
+
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+pub struct TypedDataField {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+/// The typed data of an `eth_signTypedData_v4` request, as the dapp sends it.
+#[derive(Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TypedData {
+    pub types: BTreeMap<String, Vec<TypedDataField>>,
+    pub primary_type: String,
+    pub domain: Value,
+    pub message: Value,
+}
+
+impl TypedData {
+    pub fn from_json(json: &str) -> Result<Self, EvmError> {
+        serde_json::from_str(json).map_err(|err| EvmError::InvalidTypedData(err.to_string()))
+    }
+
+    /// The chain the domain binds the signature to, if it names one.
+    pub fn chain_id(&self) -> Result<Option<u64>, EvmError> {
+        let chain_id = match self.domain.get("chainId") {
+            Some(chain_id) => parse_uint(chain_id)?,
+            None => return Ok(None),
+        };
+
+        chain_id
+            .to_u64()
+            .map(Some)
+            .ok_or_else(|| EvmError::InvalidTypedData(format!("chain id {}", chain_id)))
+    }
+
+    /// The hash to sign, `keccak256(0x1901 ‖ domainSeparator ‖ hashStruct(message))`.
+    pub fn hash(&self) -> Result<[u8; 32], EvmError> {
+        let domain_separator = self.hash_struct(DOMAIN_TYPE, &self.domain)?;
+        let message_hash = self.hash_struct(&self.primary_type, &self.message)?;
+
+        Ok(keccak256(
+            &[
+                &[0x19u8, 0x01][..],
+                &domain_separator[..],
+                &message_hash[..],
+            ]
+            .concat(),
+        ))
+    }
+
+    /// The struct as `Name(type name,...)` followed by the structs it uses, sorted by name.
+    pub fn encode_type(&self, name: &str) -> Result<String, EvmError> {
+        let mut dependencies = BTreeSet::new();
+        self.collect_dependencies(name, &mut dependencies);
+        dependencies.remove(name);
+
+        let mut encoded = self.encode_single_type(name)?;
+
+        for dependency in dependencies {
+            encoded += &self.encode_single_type(&dependency)?;
+        }
+
+        Ok(encoded)
+    }
+
+    pub fn hash_struct(&self, name: &str, value: &Value) -> Result<[u8; 32], EvmError> {
+        let object = value
+            .as_object()
+            .ok_or_else(|| EvmError::InvalidTypedData(format!("{} is not an object", name)))?;
+
+        let mut encoded = keccak256(self.encode_type(name)?.as_bytes()).to_vec();
+
+        for field in self.fields(name)? {
+            let value = object.get(&field.name).ok_or_else(|| {
+                EvmError::InvalidTypedData(format!("missing {}.{}", name, field.name))
+            })?;
+
+            encoded.extend_from_slice(&self.encode_value(&field.kind, value)?);
+        }
+
+        Ok(keccak256(&encoded))
+    }
+
+    fn fields(&self, name: &str) -> Result<&Vec<TypedDataField>, EvmError> {
+        self.types
+            .get(name)
+            .ok_or_else(|| EvmError::InvalidTypedData(format!("unknown type {}", name)))
+    }
+
+    fn encode_single_type(&self, name: &str) -> Result<String, EvmError> {
+        let fields = self
+            .fields(name)?
+            .iter()
+            .map(|field| format!("{} {}", field.kind, field.name))
+            .collect::<Vec<String>>()
+            .join(",");
+
+        Ok(format!("{}({})", name, fields))
+    }
+
+    fn collect_dependencies(&self, name: &str, found: &mut BTreeSet<String>) {
+        let fields = match self.types.get(name) {
+            Some(fields) => fields,
+            None => return,
+        };
+
+        if !found.insert(name.to_string()) {
+            return;
+        }
+
+        for field in fields {
+            self.collect_dependencies(base_type(&field.kind), found);
+        }
+    }
+
+    fn encode_value(&self, kind: &str, value: &Value) -> Result<[u8; 32], EvmError> {
+        if let Some((item_kind, length)) = array_type(kind)? {
+            let items = value.as_array().ok_or_else(|| {
+                EvmError::InvalidTypedData(format!("{} is not a {}", value, kind))
+            })?;
+
+            if length.is_some_and(|length| length != items.len()) {
+                return Err(EvmError::InvalidTypedData(format!(
+                    "{} items for a {}",
+                    items.len(),
+                    kind
+                )));
+            }
+
+            let mut encoded = vec![];
+            for item in items {
+                encoded.extend_from_slice(&self.encode_value(item_kind, item)?);
+            }
+
+            return Ok(keccak256(&encoded));
+        }
+
+        if self.types.contains_key(kind) {
+            return self.hash_struct(kind, value);
+        }
+
+        encode_atomic(kind, value)
+    }
+
+    fn write_value(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        label: &str,
+        kind: &str,
+        value: &Value,
+        depth: usize,
+    ) -> fmt::Result {
+        let indent = "  ".repeat(depth);
+
+        if let Ok(Some((item_kind, _))) = array_type(kind) {
+            writeln!(f, "{}{}:", indent, label)?;
+
+            for (index, item) in value.as_array().into_iter().flatten().enumerate() {
+                self.write_value(f, &format!("[{}]", index), item_kind, item, depth + 1)?;
+            }
+
+            return Ok(());
+        }
+
+        if let (Some(fields), Some(object)) = (self.types.get(kind), value.as_object()) {
+            writeln!(f, "{}{}:", indent, label)?;
+
+            for field in fields {
+                let value = object.get(&field.name).unwrap_or(&Value::Null);
+
+                self.write_value(f, &field.name, &field.kind, value, depth + 1)?;
+            }
+
+            return Ok(());
+        }
+
+        match value {
+            Value::String(text) => writeln!(f, "{}{}: {}", indent, label, text),
+            _ => writeln!(f, "{}{}: {}", indent, label, value),
+        }
+    }
+}
+
+/// The domain and the message field by field, as the signer should read them.
+impl fmt::Display for TypedData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_value(f, "Domain", DOMAIN_TYPE, &self.domain, 0)?;
+        self.write_value(f, &self.primary_type, &self.primary_type, &self.message, 0)
+    }
+}
+
+/// The type without its array dimensions, `Person` for `Person[][2]`.
+fn base_type(kind: &str) -> &str {
+    kind.split('[').next().unwrap_or(kind)
+}
+
+/// The item type and the length of an array type, none if it is not an array.
+fn array_type(kind: &str) -> Result<Option<(&str, Option<usize>)>, EvmError> {
+    let open = match kind.strip_suffix(']').and_then(|kind| kind.rfind('[')) {
+        Some(open) => open,
+        None => return Ok(None),
+    };
+
+    let length = &kind[open + 1..kind.len() - 1];
+
+    let length = match length.is_empty() {
+        true => None,
+        false => Some(
+            length
+                .parse::<usize>()
+                .map_err(|_| EvmError::InvalidTypedData(format!("unknown type {}", kind)))?,
+        ),
+    };
+
+    Ok(Some((&kind[..open], length)))
+}
+
+/// The size in `uintN`, `intN` or `bytesN`, `default` when there is none.
+fn type_size(kind: &str, prefix: &str, default: usize) -> Result<usize, EvmError> {
+    let size = &kind[prefix.len()..];
+
+    if size.is_empty() {
+        return Ok(default);
+    }
+
+    size.parse::<usize>()
+        .map_err(|_| EvmError::InvalidTypedData(format!("unknown type {}", kind)))
+}
+
+fn parse_uint(value: &Value) -> Result<U256, EvmError> {
+    match value {
+        Value::Number(number) => number.as_u64().map(U256::from).ok_or_else(|| {
+            EvmError::InvalidTypedData(format!("{} is not an unsigned integer", number))
+        }),
+        Value::String(number) => U256::from_dec_or_hex(number),
+        _ => Err(EvmError::InvalidTypedData(format!(
+            "{} is not a number",
+            value
+        ))),
+    }
+}
+
+fn parse_bytes(value: &Value) -> Option<Vec<u8>> {
+    let hex = value.as_str()?;
+
+    hex::decode(hex.strip_prefix("0x").unwrap_or(hex)).ok()
+}
+
+fn encode_int(kind: &str, value: &Value) -> Result<[u8; 32], EvmError> {
+    let invalid = || EvmError::InvalidTypedData(format!("{} is not a valid {}", value, kind));
+
+    let bits = type_size(kind, "int", 256)?;
+
+    let number = match value {
+        Value::Number(number) => number.as_i64().ok_or_else(invalid)?.to_string(),
+        Value::String(number) => number.clone(),
+        _ => return Err(invalid()),
+    };
+
+    let (negative, magnitude) = match number.strip_prefix('-') {
+        Some(magnitude) => (true, U256::from_dec_or_hex(magnitude)?),
+        None => (false, U256::from_dec_or_hex(&number)?),
+    };
+
+    if !negative || magnitude.is_zero() {
+        if magnitude.bits() >= bits as u64 {
+            return Err(invalid());
+        }
+
+        return Ok(magnitude.to_be_bytes32());
+    }
+
+    let word = magnitude.to_negative_bytes32();
+
+    // a negative intN has at least 257 - N leading ones on 256 bits
+    let mut leading_ones = 0;
+    for byte in word {
+        leading_ones += byte.leading_ones() as usize;
+
+        if byte != 0xff {
+            break;
+        }
+    }
+
+    if leading_ones < 257 - bits {
+        return Err(invalid());
+    }
+
+    Ok(word)
+}
+
+fn encode_atomic(kind: &str, value: &Value) -> Result<[u8; 32], EvmError> {
+    let invalid = || EvmError::InvalidTypedData(format!("{} is not a valid {}", value, kind));
+
+    match kind {
+        "string" => value
+            .as_str()
+            .map(|text| keccak256(text.as_bytes()))
+            .ok_or_else(invalid),
+        "bytes" => parse_bytes(value)
+            .map(|bytes| keccak256(&bytes))
+            .ok_or_else(invalid),
+        "bool" => value
+            .as_bool()
+            .map(|flag| U256::from(flag as u64).to_be_bytes32())
+            .ok_or_else(invalid),
+        "address" => {
+            let bytes = parse_bytes(value)
+                .filter(|bytes| bytes.len() == 20)
+                .ok_or_else(invalid)?;
+
+            let mut word = [0u8; 32];
+            word[12..].copy_from_slice(&bytes);
+
+            Ok(word)
+        }
+        _ if kind.starts_with("bytes") => {
+            let size = type_size(kind, "bytes", 0)?;
+
+            let bytes = parse_bytes(value)
+                .filter(|bytes| (1..=32).contains(&size) && bytes.len() <= size)
+                .ok_or_else(invalid)?;
+
+            let mut word = [0u8; 32];
+            word[..bytes.len()].copy_from_slice(&bytes);
+
+            Ok(word)
+        }
+        _ if kind.starts_with("uint") => {
+            let bits = type_size(kind, "uint", 256)?;
+
+            let number = parse_uint(value)?;
+
+            if bits == 0 || bits > 256 || number.bits() > bits as u64 {
+                return Err(invalid());
+            }
+
+            Ok(number.to_be_bytes32())
+        }
+        _ if kind.starts_with("int") => {
+            let bits = type_size(kind, "int", 256)?;
+
+            if bits == 0 || bits > 256 {
+                return Err(invalid());
+            }
+
+            encode_int(kind, value)
+        }
+        _ => Err(EvmError::InvalidTypedData(format!("unknown type {}", kind))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The example of the EIP-712 specification.
+    const MAIL: &str = r#"{
+        "types": {
+            "EIP712Domain": [
+                { "name": "name", "type": "string" },
+                { "name": "version", "type": "string" },
+                { "name": "chainId", "type": "uint256" },
+                { "name": "verifyingContract", "type": "address" }
+            ],
+            "Person": [
+                { "name": "name", "type": "string" },
+                { "name": "wallet", "type": "address" }
+            ],
+            "Mail": [
+                { "name": "from", "type": "Person" },
+                { "name": "to", "type": "Person" },
+                { "name": "contents", "type": "string" }
+            ]
+        },
+        "primaryType": "Mail",
+        "domain": {
+            "name": "Ether Mail",
+            "version": "1",
+            "chainId": 1,
+            "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+        },
+        "message": {
+            "from": { "name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826" },
+            "to": { "name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB" },
+            "contents": "Hello, Bob!"
+        }
+    }"#;
+
+    #[test]
+    fn test_mail_hash() {
+        let typed_data = TypedData::from_json(MAIL).unwrap();
+
+        assert_eq!(
+            typed_data.encode_type("Mail").unwrap(),
+            "Mail(Person from,Person to,string contents)Person(string name,address wallet)"
+        );
+        assert_eq!(
+            hex::encode(
+                typed_data
+                    .hash_struct(DOMAIN_TYPE, &typed_data.domain)
+                    .unwrap()
+            ),
+            "f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f"
+        );
+        assert_eq!(
+            hex::encode(typed_data.hash_struct("Mail", &typed_data.message).unwrap()),
+            "c52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e"
+        );
+        assert_eq!(
+            hex::encode(typed_data.hash().unwrap()),
+            "be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"
+        );
+        assert_eq!(typed_data.chain_id(), Ok(Some(1)));
+    }
+
+    #[test]
+    fn test_mail_display() {
+        let typed_data = TypedData::from_json(MAIL).unwrap();
+
+        assert_eq!(
+            typed_data.to_string(),
+            "Domain:\n\
+             \x20 name: Ether Mail\n\
+             \x20 version: 1\n\
+             \x20 chainId: 1\n\
+             \x20 verifyingContract: 0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC\n\
+             Mail:\n\
+             \x20 from:\n\
+             \x20   name: Cow\n\
+             \x20   wallet: 0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826\n\
+             \x20 to:\n\
+             \x20   name: Bob\n\
+             \x20   wallet: 0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB\n\
+             \x20 contents: Hello, Bob!\n"
+        );
+    }
+
+    #[test]
+    fn test_encode_atomic() {
+        assert_eq!(encode_atomic("int8", &Value::from(-1)).unwrap(), [0xff; 32]);
+        assert_eq!(encode_atomic("int8", &Value::from(-128)).unwrap()[31], 0x80);
+        assert!(encode_atomic("int8", &Value::from(-129)).is_err());
+        assert!(encode_atomic("int8", &Value::from(128)).is_err());
+
+        assert_eq!(
+            encode_atomic("uint256", &Value::from("0x3039")).unwrap(),
+            U256::from(12345u64).to_be_bytes32()
+        );
+        assert!(encode_atomic("uint8", &Value::from(256)).is_err());
+
+        let mut expected = [0u8; 32];
+        expected[0] = 0xab;
+        assert_eq!(
+            encode_atomic("bytes4", &Value::from("0xab")).unwrap(),
+            expected
+        );
+        assert!(encode_atomic("address", &Value::from("0xab")).is_err());
+        assert!(encode_atomic("uint7x", &Value::from(1)).is_err());
+    }
+
+    #[test]
+    fn test_array_type() {
+        assert_eq!(array_type("uint256").unwrap(), None);
+        assert_eq!(array_type("Person[]").unwrap(), Some(("Person", None)));
+        assert_eq!(
+            array_type("Person[][2]").unwrap(),
+            Some(("Person[]", Some(2)))
+        );
+        assert!(array_type("Person[x]").is_err());
+        assert_eq!(base_type("Person[][2]"), "Person");
+    }
+}
@@ -0,0 +1,172 @@
+use super::{
+    abi::{decode, AbiFunction, AbiType, AbiValue},
+    error::EvmError,
+    rpc::EvmRpc,
+    utils::keccak256,
+};
+use crate::{ledger::types::ChainId, store::with_setting};
+
+/// The ENS registry, at the same address on Ethereum and its testnets.
+pub const ENS_REGISTRY: &str = "0x00000000000C2E074eC69A0dFb2997BA6C7d2e1e";
+
+/// The networks with an ENS deployment, Ethereum and its testnets Sepolia and Holesky.
+const ENS_CHAINS: [ChainId; 3] = [1, 11155111, 17000];
+
+const ZERO_ADDRESS: &str = "0x0000000000000000000000000000000000000000";
+
+/// Whether the destination is an ENS name like `treasury.eth` rather than an address.
+pub fn is_ens_name(destination: &str) -> bool {
+    let destination = destination.trim();
+
+    !destination.starts_with("0x") && destination.contains('.')
+}
+
+/// Lowercase the name and check its labels. Only ASCII letters, digits and
+/// hyphens are accepted, so a name can't be spelled with lookalike characters.
+pub fn normalize_ens_name(name: &str) -> Result<String, EvmError> {
+    let name = name.trim().to_ascii_lowercase();
+
+    let valid = name.split('.').all(|label| {
+        !label.is_empty()
+            && label
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+    });
+
+    if !valid || !name.contains('.') {
+        return Err(EvmError::InvalidEnsName(name));
+    }
+
+    Ok(name)
+}
+
+/// The EIP-137 node of the name, the key of its records in the registry and resolver.
+pub fn namehash(name: &str) -> [u8; 32] {
+    let mut node = [0u8; 32];
+
+    if name.is_empty() {
+        return node;
+    }
+
+    for label in name.rsplit('.') {
+        let label_hash = keccak256(label.as_bytes());
+
+        node = keccak256(&[node, label_hash].concat());
+    }
+
+    node
+}
+
+/// Check the names used on the given chain can be resolved. `addr(bytes32)`
+/// is the Ethereum address of a name, it is not its address on other networks.
+pub fn check_ens_chain(chain_id: ChainId) -> Result<(), EvmError> {
+    if ENS_CHAINS.contains(&chain_id) {
+        Ok(())
+    } else {
+        Err(EvmError::EnsUnsupportedChain(chain_id))
+    }
+}
+
+/// Resolve the name to the address its resolver has for it, asking the registry
+/// for the resolver first, both with `eth_call` so every provider of the
+/// network has to agree on the answer.
+pub async fn resolve_ens_name(chain_id: ChainId, name: &str) -> Result<String, EvmError> {
+    check_ens_chain(chain_id)?;
+
+    let name = normalize_ens_name(name)?;
+
+    let rpc = with_setting(|s| s.evm_network_rpc(chain_id));
+
+    let node = AbiValue::FixedBytes(namehash(&name).to_vec());
+
+    let resolver = call_address(&rpc, chain_id, ENS_REGISTRY, "resolver(bytes32)", &node)
+        .await?
+        .ok_or_else(|| EvmError::EnsNameNotFound(name.clone()))?;
+
+    call_address(&rpc, chain_id, &resolver, "addr(bytes32)", &node)
+        .await?
+        .ok_or(EvmError::EnsNameNotFound(name))
+}
+
+/// Call a function of the contract taking the node, `None` for an empty or zero address.
+async fn call_address(
+    rpc: &EvmRpc,
+    chain_id: ChainId,
+    contract_address: &str,
+    function: &str,
+    node: &AbiValue,
+) -> Result<Option<String>, EvmError> {
+    let data = AbiFunction::parse(function)?.encode_call(&[node.clone()])?;
+
+    let output = rpc
+        .eth_call(
+            chain_id,
+            contract_address,
+            &format!("0x{}", hex::encode(data)),
+        )
+        .await?;
+
+    let output = hex::decode(output.strip_prefix("0x").unwrap_or(&output))
+        .map_err(|_| EvmError::InvalidHex(output.clone()))?;
+
+    if output.is_empty() {
+        return Ok(None);
+    }
+
+    match decode(&[AbiType::Address], &output)?.pop() {
+        Some(AbiValue::Address(address)) if address != ZERO_ADDRESS => Ok(Some(address)),
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_namehash() {
+        assert_eq!(namehash(""), [0u8; 32]);
+        assert_eq!(
+            hex::encode(namehash("eth")),
+            "93cdeb708b7545dc668eb9280176169d1c33cfd8ed6f04690a0bcc88a93fc4ae"
+        );
+        assert_eq!(
+            hex::encode(namehash("foo.eth")),
+            "de9b09fd7c5f901e23a3f19fecc54828e9c848539801e86591bd9801b019f84f"
+        );
+    }
+
+    #[test]
+    fn test_normalize_ens_name() {
+        assert!(is_ens_name("treasury.eth"));
+        assert!(!is_ens_name("0x7a9d2f53fea15e31f0a89d7f5d9e0e82b0b88ad6"));
+
+        assert_eq!(
+            normalize_ens_name(" Treasury.ETH ").unwrap(),
+            "treasury.eth"
+        );
+        assert_eq!(
+            normalize_ens_name("pay.my-dao.eth").unwrap(),
+            "pay.my-dao.eth"
+        );
+
+        assert!(normalize_ens_name("eth").is_err());
+        assert!(normalize_ens_name("treasury..eth").is_err());
+        assert!(normalize_ens_name("tre asury.eth").is_err());
+        assert!(normalize_ens_name("tre_asury.eth").is_err());
+        // Cyrillic "а" in place of the Latin "a".
+        assert!(normalize_ens_name("tre\u{0430}sury.eth").is_err());
+        assert!(normalize_ens_name("trésor.eth").is_err());
+    }
+
+    #[test]
+    fn test_check_ens_chain() {
+        assert!(check_ens_chain(1).is_ok());
+        assert!(check_ens_chain(11155111).is_ok());
+        assert!(check_ens_chain(17000).is_ok());
+        assert_eq!(
+            check_ens_chain(42161),
+            Err(EvmError::EnsUnsupportedChain(42161))
+        );
+    }
+}
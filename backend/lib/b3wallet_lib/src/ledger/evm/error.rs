@@ -11,6 +11,43 @@ pub enum EvmError {
     InvalidRecoveryId(String),
     InvalidSignature(String),
     InvalidAddress(String),
+    UnsupportedChain(u64),
+    CallError(String),
+    RpcError(String),
+    InconsistentRpcResults(String),
+    NonceTooLow,
+    NonceTooHigh,
+    InsufficientFunds,
+    InvalidHex(String),
+    InvalidResponse(String),
+    UnknownToken(String),
+    NonceNotPending(u64),
+    FeeAboveCap(u64, u64),
+    ValueOverflow(String),
+    InvalidRlp(String),
+    InvalidLength(String, usize),
+    InvalidFieldCount(usize, usize),
+    InvalidChainId(u64, u64),
+    InvalidTypedData(String),
+    InvalidNumber(String),
+    InvalidAbi(String),
+    UnknownCollection(String),
+    WrongNftStandard(String, String),
+    NftNotOwned(String),
+    InsufficientNftBalance(String, String),
+    TransactionPending(String),
+    UnknownNetwork(u64),
+    InvalidNetwork(String),
+    InvalidRpc(String),
+    NetworkInUse(u64),
+    ExecutionReverted(String),
+    InvalidEnsName(String),
+    EnsNameNotFound(String),
+    EnsUnsupportedChain(u64),
+    UnresolvedEnsName(String),
+    EnsNameMismatch(String, String),
+    Erc4337NotConfigured(u64),
+    Erc4337ConfigChanged(u64),
 }
 
 #[rustfmt::skip]
@@ -24,6 +61,43 @@ impl fmt::Display for EvmError {
             EvmError::InvalidPublicKey(msg) => write!(f, "Invalid public key: {}", msg),
             EvmError::InvalidRecoveryId(msg) => write!(f, "Invalid recovery id: {}", msg),
             EvmError::InvalidSignature(msg) => write!(f, "Invalid signature: {}", msg),
+            EvmError::UnsupportedChain(chain_id) => write!(f, "No RPC providers for chain {}", chain_id),
+            EvmError::CallError(msg) => write!(f, "Call error: {}", msg),
+            EvmError::RpcError(msg) => write!(f, "RPC error: {}", msg),
+            EvmError::InconsistentRpcResults(msg) => write!(f, "Inconsistent RPC results: {}", msg),
+            EvmError::NonceTooLow => write!(f, "Nonce too low"),
+            EvmError::NonceTooHigh => write!(f, "Nonce too high"),
+            EvmError::InsufficientFunds => write!(f, "Insufficient funds"),
+            EvmError::InvalidHex(hex) => write!(f, "Invalid hex: {}", hex),
+            EvmError::InvalidResponse(msg) => write!(f, "Invalid response: {}", msg),
+            EvmError::UnknownToken(address) => write!(f, "Unknown token: {}", address),
+            EvmError::NonceNotPending(nonce) => write!(f, "No pending transaction with nonce {}", nonce),
+            EvmError::FeeAboveCap(fee, cap) => write!(f, "Fee of {} wei/gas is above the cap of {}", fee, cap),
+            EvmError::ValueOverflow(value) => write!(f, "Value does not fit in 256 bits: {}", value),
+            EvmError::InvalidRlp(msg) => write!(f, "Invalid RLP: {}", msg),
+            EvmError::InvalidLength(field, length) => write!(f, "Invalid length of {} bytes for {}", length, field),
+            EvmError::InvalidFieldCount(expected, found) => write!(f, "Expected {} fields, found {}", expected, found),
+            EvmError::InvalidChainId(expected, found) => write!(f, "Expected chain id {}, found {}", expected, found),
+            EvmError::InvalidTypedData(msg) => write!(f, "Invalid typed data: {}", msg),
+            EvmError::InvalidNumber(number) => write!(f, "Invalid number: {}", number),
+            EvmError::InvalidAbi(msg) => write!(f, "Invalid ABI: {}", msg),
+            EvmError::UnknownCollection(address) => write!(f, "Unknown NFT collection: {}", address),
+            EvmError::WrongNftStandard(address, standard) => write!(f, "NFT collection {} is {}", address, standard),
+            EvmError::NftNotOwned(token_id) => write!(f, "Token {} is not owned by the account", token_id),
+            EvmError::InsufficientNftBalance(balance, amount) => write!(f, "Balance of {} is below the {} to transfer", balance, amount),
+            EvmError::TransactionPending(tx_hash) => write!(f, "Transaction {} is not mined yet", tx_hash),
+            EvmError::UnknownNetwork(chain_id) => write!(f, "Unknown EVM network: {}", chain_id),
+            EvmError::InvalidNetwork(msg) => write!(f, "Invalid EVM network: {}", msg),
+            EvmError::InvalidRpc(msg) => write!(f, "Invalid EVM RPC: {}", msg),
+            EvmError::NetworkInUse(chain_id) => write!(f, "EVM network {} still has account addresses", chain_id),
+            EvmError::ExecutionReverted(reason) => write!(f, "Execution reverted: {}", reason),
+            EvmError::InvalidEnsName(name) => write!(f, "Invalid ENS name: {}", name),
+            EvmError::EnsNameNotFound(name) => write!(f, "ENS name {} does not resolve to an address", name),
+            EvmError::EnsUnsupportedChain(chain_id) => write!(f, "ENS names can't be resolved for chain {}", chain_id),
+            EvmError::UnresolvedEnsName(name) => write!(f, "ENS name {} has to be resolved by the wallet", name),
+            EvmError::EnsNameMismatch(name, address) => write!(f, "ENS name {} now resolves to {}", name, address),
+            EvmError::Erc4337NotConfigured(chain_id) => write!(f, "No ERC-4337 EntryPoint configured for chain {}", chain_id),
+            EvmError::Erc4337ConfigChanged(chain_id) => write!(f, "ERC-4337 config of chain {} changed since the request", chain_id),
         }
     }
 }
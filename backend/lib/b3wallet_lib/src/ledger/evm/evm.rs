@@ -6,10 +6,12 @@ use super::berlin::EvmTransaction2930;
 use super::error::EvmError;
 use super::legacy::EvmTransactionLegacy;
 use super::london::EvmTransaction1559;
-use super::utils::{string_to_vec_u8, vec_u8_to_string};
+use super::u256::U256;
+use super::utils::{rlp_bytes, rlp_hex, rlp_items, string_to_vec_u8, vec_u8_to_string};
 use candid::{CandidType, Deserialize};
 use enum_dispatch::enum_dispatch;
 use libsecp256k1::{recover, Message, PublicKey, RecoveryId, Signature};
+use rlp::UntrustedRlp;
 
 #[enum_dispatch]
 pub trait EvmSignTrait {
@@ -34,6 +36,58 @@ pub enum EvmTransaction {
     EvmTransaction2930,
 }
 
+impl EvmTransaction {
+    /// The recipient, empty for a contract deployment.
+    pub fn to(&self) -> &str {
+        match self {
+            EvmTransaction::EvmTransactionLegacy(tx) => &tx.to,
+            EvmTransaction::EvmTransaction1559(tx) => &tx.to,
+            EvmTransaction::EvmTransaction2930(tx) => &tx.to,
+        }
+    }
+
+    pub fn value(&self) -> &U256 {
+        match self {
+            EvmTransaction::EvmTransactionLegacy(tx) => &tx.value,
+            EvmTransaction::EvmTransaction1559(tx) => &tx.value,
+            EvmTransaction::EvmTransaction2930(tx) => &tx.value,
+        }
+    }
+
+    pub fn data(&self) -> &str {
+        match self {
+            EvmTransaction::EvmTransactionLegacy(tx) => &tx.data,
+            EvmTransaction::EvmTransaction1559(tx) => &tx.data,
+            EvmTransaction::EvmTransaction2930(tx) => &tx.data,
+        }
+    }
+
+    pub fn gas_limit(&self) -> u64 {
+        match self {
+            EvmTransaction::EvmTransactionLegacy(tx) => tx.gas_limit,
+            EvmTransaction::EvmTransaction1559(tx) => tx.gas_limit,
+            EvmTransaction::EvmTransaction2930(tx) => tx.gas_limit,
+        }
+    }
+
+    /// The most paid per gas, the gas price for the transactions that have one.
+    pub fn max_fee_per_gas(&self) -> &U256 {
+        match self {
+            EvmTransaction::EvmTransactionLegacy(tx) => &tx.gas_price,
+            EvmTransaction::EvmTransaction1559(tx) => &tx.max_fee_per_gas,
+            EvmTransaction::EvmTransaction2930(tx) => &tx.gas_price,
+        }
+    }
+
+    /// The tip to the validator, only set apart on EIP-1559 transactions.
+    pub fn max_priority_fee_per_gas(&self) -> Option<&U256> {
+        match self {
+            EvmTransaction::EvmTransaction1559(tx) => Some(&tx.max_priority_fee_per_gas),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Clone, Deserialize, PartialEq, CandidType, Debug)]
 pub enum EvmTransactionType {
     Legacy,
@@ -57,32 +111,28 @@ pub fn get_evm_transaction(
 ) -> Result<EvmTransaction, EvmError> {
     let tx_type = get_evm_transaction_type(hex_raw_tx)?;
 
-    if tx_type == EvmTransactionType::Legacy {
-        let transaction = EvmTransactionLegacy::from((hex_raw_tx.to_owned(), chain_id));
-
-        Ok(transaction.into())
-    } else if tx_type == EvmTransactionType::EIP1559 {
-        let transaction = EvmTransaction1559::from(hex_raw_tx.to_owned());
-
-        Ok(transaction.into())
-    } else if tx_type == EvmTransactionType::EIP2930 {
-        let transaction = EvmTransaction2930::from(hex_raw_tx.to_owned());
+    let transaction: EvmTransaction = match tx_type {
+        EvmTransactionType::Legacy => {
+            EvmTransactionLegacy::try_from((hex_raw_tx.to_owned(), chain_id))?.into()
+        }
+        EvmTransactionType::EIP1559 => EvmTransaction1559::try_from(hex_raw_tx.to_owned())?.into(),
+        EvmTransactionType::EIP2930 => EvmTransaction2930::try_from(hex_raw_tx.to_owned())?.into(),
+    };
 
-        Ok(transaction.into())
-    } else {
-        Err(EvmError::InvalidTransactionType)
+    if transaction.chain_id() != chain_id {
+        return Err(EvmError::InvalidChainId(chain_id, transaction.chain_id()));
     }
+
+    Ok(transaction)
 }
 
 pub fn get_evm_transaction_type(hex_raw_tx: &Vec<u8>) -> Result<EvmTransactionType, EvmError> {
-    if hex_raw_tx[0] >= 0xc0 {
-        Ok(EvmTransactionType::Legacy)
-    } else if hex_raw_tx[0] == 0x01 {
-        Ok(EvmTransactionType::EIP2930)
-    } else if hex_raw_tx[0] == 0x02 {
-        Ok(EvmTransactionType::EIP1559)
-    } else {
-        Err(EvmError::InvalidTransactionType)
+    match hex_raw_tx.first() {
+        Some(byte) if *byte >= 0xc0 => Ok(EvmTransactionType::Legacy),
+        Some(0x01) => Ok(EvmTransactionType::EIP2930),
+        Some(0x02) => Ok(EvmTransactionType::EIP1559),
+        Some(_) => Err(EvmError::InvalidTransactionType),
+        None => Err(EvmError::InvalidLength("transaction".to_string(), 0)),
     }
 }
 
@@ -137,19 +187,42 @@ pub fn encode_access_list(access_list: &Vec<(String, Vec<String>)>) -> Vec<u8> {
     stream.out().to_vec()
 }
 
-pub fn decode_access_list(access_list: &Vec<u8>) -> Vec<(String, Vec<String>)> {
+pub fn decode_access_list(access_list: &[u8]) -> Result<Vec<(String, Vec<String>)>, EvmError> {
+    let rlp = UntrustedRlp::new(access_list);
+
+    if !rlp.is_list() {
+        return Err(EvmError::InvalidRlp("expected an access list".to_string()));
+    }
+
     let mut decoded_access_list = vec![];
-    let rlp = rlp::Rlp::new(access_list);
     for item in rlp.iter() {
-        let address = item.at(0).as_val();
-        let storage_keys_u8 = item.at(1).as_list::<Vec<u8>>();
-        let storage_keys = storage_keys_u8
+        let fields = rlp_items(item.as_raw(), 2)?;
+
+        let address = rlp_bytes(&fields[0])?;
+        let storage_keys = &fields[1];
+
+        if address.len() != 20 {
+            return Err(EvmError::InvalidLength(
+                "access list address".to_string(),
+                address.len(),
+            ));
+        }
+
+        if !storage_keys.is_list() {
+            return Err(EvmError::InvalidRlp(
+                "expected a list of storage keys".to_string(),
+            ));
+        }
+
+        let storage_keys = storage_keys
             .iter()
-            .map(|x| vec_u8_to_string(x))
-            .collect::<Vec<String>>();
+            .map(|key| rlp_hex(&key, "storage key", 32))
+            .collect::<Result<Vec<String>, EvmError>>()?;
+
         decoded_access_list.push((vec_u8_to_string(&address), storage_keys));
     }
-    decoded_access_list
+
+    Ok(decoded_access_list)
 }
 
 impl Ledger {
@@ -163,11 +236,7 @@ impl Ledger {
         let mut evm_tx =
             get_evm_transaction(&hex_raw_tx, chain_id).map_err(LedgerError::EvmError)?;
 
-        let message = evm_tx.unsigned_serialized();
-
-        if message.len() != 32 {
-            return Err(LedgerError::InvalidMessageLength);
-        }
+        let message = evm_tx.unsigned_hash();
 
         let signature = self.subaccount.sign_with_ecdsa(message).await?;
 
@@ -272,7 +341,7 @@ mod tests {
         let access_list = "f872f85994de0b295669a9fd93d5f28d9ec85e40f4cb697baef842a00000000000000000000000000000000000000000000000000000000000000003a00000000000000000000000000000000000000000000000000000000000000007d694bb9bc244d798123fde783fcc1c72d3bb8c189413c0";
         let access_list_hex = string_to_vec_u8(&access_list);
 
-        let decoded = decode_access_list(&access_list_hex);
+        let decoded = decode_access_list(&access_list_hex).unwrap();
         assert_eq!(decoded, expected);
     }
 }
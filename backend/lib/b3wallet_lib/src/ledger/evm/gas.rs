@@ -0,0 +1,214 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use super::{error::EvmError, rpc::FeeHistory, u256::U256};
+use crate::{
+    error::WalletError,
+    ledger::types::{ChainEnum, ChainId},
+    store::with_chain,
+    types::AccountId,
+};
+
+/// How much gas is added on top of the estimate, in percent.
+pub const GAS_LIMIT_MARGIN_PERCENT: u64 = 20;
+
+/// How fast the transactions of the wallet should be included.
+#[derive(CandidType, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum EvmFeeSpeed {
+    /// Waits for a quiet block, may stall if the base fee goes up.
+    Slow,
+    #[default]
+    Standard,
+    /// Outbids most of the recent transactions.
+    Fast,
+}
+
+impl EvmFeeSpeed {
+    /// The percentile of the recent tips the priority fee is taken from.
+    pub fn reward_percentile(&self) -> u8 {
+        match self {
+            EvmFeeSpeed::Slow => 10,
+            EvmFeeSpeed::Standard => 50,
+            EvmFeeSpeed::Fast => 90,
+        }
+    }
+
+    /// How many times the current base fee the transaction is ready to pay.
+    pub fn base_fee_multiplier(&self) -> u64 {
+        match self {
+            EvmFeeSpeed::Slow => 1,
+            EvmFeeSpeed::Standard => 2,
+            EvmFeeSpeed::Fast => 3,
+        }
+    }
+}
+
+/// How the fees of the EVM transactions are chosen when they are left empty.
+#[derive(CandidType, Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct EvmGasPolicy {
+    pub speed: EvmFeeSpeed,
+    /// The most the wallet pays per gas, in wei.
+    pub max_fee_per_gas_cap: Option<u64>,
+}
+
+impl EvmGasPolicy {
+    /// The `(max_fee_per_gas, max_priority_fee_per_gas)` to pay given the recent blocks,
+    /// lowered to the cap if it is above the current base fee.
+    pub fn suggested_fees(&self, history: &FeeHistory) -> Result<(u64, u64), EvmError> {
+        let (max_fee_per_gas, max_priority_fee_per_gas) =
+            history.suggested_fees(self.speed.base_fee_multiplier())?;
+
+        let cap = match self.max_fee_per_gas_cap {
+            Some(cap) => cap,
+            None => return Ok((max_fee_per_gas, max_priority_fee_per_gas)),
+        };
+
+        let base_fee = history.base_fee()?;
+
+        if base_fee >= cap {
+            return Err(EvmError::FeeAboveCap(base_fee, cap));
+        }
+
+        let max_fee_per_gas = max_fee_per_gas.min(cap);
+
+        Ok((
+            max_fee_per_gas,
+            max_priority_fee_per_gas.min(max_fee_per_gas - base_fee),
+        ))
+    }
+
+    /// Refuse a fee set by the requester above the cap.
+    pub fn check_max_fee(&self, max_fee_per_gas: u64) -> Result<(), EvmError> {
+        match self.max_fee_per_gas_cap {
+            Some(cap) if max_fee_per_gas > cap => Err(EvmError::FeeAboveCap(max_fee_per_gas, cap)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The gas an EVM transaction is sent with.
+#[derive(CandidType, Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct EvmFees {
+    pub gas_limit: u64,
+    pub max_fee_per_gas: u64,
+    pub max_priority_fee_per_gas: u64,
+}
+
+impl EvmFees {
+    /// The most the transaction can cost in fees, in wei.
+    pub fn max_cost(&self) -> u128 {
+        self.gas_limit as u128 * self.max_fee_per_gas as u128
+    }
+}
+
+impl fmt::Display for EvmFees {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "gas limit {}, max fee {} wei/gas, priority fee {} wei/gas, at most {} wei",
+            self.gas_limit,
+            self.max_fee_per_gas,
+            self.max_priority_fee_per_gas,
+            self.max_cost()
+        )
+    }
+}
+
+/// The transaction of an EVM operation, to quote its fees before it is approved.
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
+pub struct EvmFeeRequest {
+    pub account_id: AccountId,
+    pub chain_id: ChainId,
+    pub to: String,
+    pub value: U256,
+    pub data: String,
+    pub gas_limit: Option<u64>,
+    pub max_fee_per_gas: Option<u64>,
+    pub max_priority_fee_per_gas: Option<u64>,
+}
+
+impl EvmFeeRequest {
+    /// The fees the transaction would be sent with right now.
+    pub async fn quote(&self) -> Result<EvmFees, WalletError> {
+        let evm = with_chain(&self.account_id, &ChainEnum::EVM(self.chain_id), |chain| {
+            chain.evm()
+        })??;
+
+        let fees = evm
+            .fees(
+                &self.to,
+                &self.value,
+                &self.data,
+                self.gas_limit,
+                self.max_fee_per_gas,
+                self.max_priority_fee_per_gas,
+            )
+            .await?;
+
+        Ok(fees)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use candid::Nat;
+
+    fn history() -> FeeHistory {
+        FeeHistory {
+            oldest_block: Nat::from(100u64),
+            base_fee_per_gas: vec![Nat::from(10u64), Nat::from(20u64)],
+            gas_used_ratio: vec![0.5],
+            reward: vec![vec![Nat::from(5u64)], vec![Nat::from(5u64)]],
+        }
+    }
+
+    #[test]
+    fn test_suggested_fees_by_speed() {
+        let policy = EvmGasPolicy::default();
+
+        assert_eq!(policy.suggested_fees(&history()), Ok((45, 5)));
+
+        let policy = EvmGasPolicy {
+            speed: EvmFeeSpeed::Fast,
+            max_fee_per_gas_cap: None,
+        };
+
+        assert_eq!(policy.suggested_fees(&history()), Ok((65, 5)));
+    }
+
+    #[test]
+    fn test_suggested_fees_with_cap() {
+        let policy = EvmGasPolicy {
+            speed: EvmFeeSpeed::Standard,
+            max_fee_per_gas_cap: Some(23),
+        };
+
+        assert_eq!(policy.suggested_fees(&history()), Ok((23, 3)));
+
+        let policy = EvmGasPolicy {
+            max_fee_per_gas_cap: Some(20),
+            ..policy
+        };
+
+        assert_eq!(
+            policy.suggested_fees(&history()),
+            Err(EvmError::FeeAboveCap(20, 20))
+        );
+
+        assert_eq!(policy.check_max_fee(20), Ok(()));
+        assert_eq!(policy.check_max_fee(21), Err(EvmError::FeeAboveCap(21, 20)));
+    }
+
+    #[test]
+    fn test_max_cost() {
+        let fees = EvmFees {
+            gas_limit: 21_000,
+            max_fee_per_gas: 100_000_000_000,
+            max_priority_fee_per_gas: 1_000_000_000,
+        };
+
+        assert_eq!(fees.max_cost(), 2_100_000_000_000_000);
+    }
+}
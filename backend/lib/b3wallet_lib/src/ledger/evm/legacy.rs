@@ -1,7 +1,10 @@
 use super::error::EvmError;
 use super::evm::{get_recovery_id, EvmSignTrait};
+use super::london::EvmTransaction1559;
+use super::u256::U256;
 use super::utils::{
-    remove_leading, string_to_vec_u8, u64_to_vec_u8, vec_u8_to_string, vec_u8_to_u64,
+    remove_leading, rlp_address, rlp_bytes, rlp_hex, rlp_items, rlp_u256, rlp_u64,
+    string_to_vec_u8, u64_to_vec_u8, vec_u8_to_string, vec_u8_to_u64,
 };
 use candid::{CandidType, Deserialize};
 use libsecp256k1::PublicKey;
@@ -11,63 +14,81 @@ use tiny_keccak::{Hasher, Keccak};
 pub struct EvmTransactionLegacy {
     pub chain_id: u64,
     pub nonce: u64,
-    pub gas_price: u64,
+    pub gas_price: U256,
     pub gas_limit: u64,
     pub to: String,
-    pub value: u64,
+    pub value: U256,
     pub data: String,
     pub v: String,
     pub r: String,
     pub s: String,
 }
 
-impl From<(Vec<u8>, u64)> for EvmTransactionLegacy {
-    fn from(data: (Vec<u8>, u64)) -> Self {
-        let rlp = rlp::Rlp::new(&data.0[..]);
-
-        let nonce_hex = rlp.at(0).as_val::<Vec<u8>>();
-        let nonce = vec_u8_to_u64(&nonce_hex);
-
-        let gas_price_hex = rlp.at(1).as_val::<Vec<u8>>();
-        let gas_price = vec_u8_to_u64(&gas_price_hex);
-
-        let gas_limit_hex = rlp.at(2).as_val::<Vec<u8>>();
-        let gas_limit = vec_u8_to_u64(&gas_limit_hex);
+/// The same transaction for a network without EIP-1559, paying its max fee as gas price.
+impl From<&EvmTransaction1559> for EvmTransactionLegacy {
+    fn from(transaction: &EvmTransaction1559) -> Self {
+        EvmTransactionLegacy {
+            chain_id: transaction.chain_id,
+            nonce: transaction.nonce,
+            gas_price: transaction.max_fee_per_gas.clone(),
+            gas_limit: transaction.gas_limit,
+            to: transaction.to.clone(),
+            value: transaction.value.clone(),
+            data: transaction.data.clone(),
+            v: "0x00".to_string(),
+            r: "0x00".to_string(),
+            s: "0x00".to_string(),
+        }
+    }
+}
 
-        let to_hex = rlp.at(3).as_val::<Vec<u8>>();
-        let to = vec_u8_to_string(&to_hex);
+impl TryFrom<(Vec<u8>, u64)> for EvmTransactionLegacy {
+    type Error = EvmError;
 
-        let value_hex = rlp.at(4).as_val::<Vec<u8>>();
-        let value = vec_u8_to_u64(&value_hex);
+    fn try_from((data, chain_id): (Vec<u8>, u64)) -> Result<Self, Self::Error> {
+        let items = rlp_items(&data, 9)?;
 
-        let data_tx_hex = rlp.at(5).as_val::<Vec<u8>>();
-        let data_tx = vec_u8_to_string(&data_tx_hex);
+        let transaction = EvmTransactionLegacy {
+            chain_id,
+            nonce: rlp_u64(&items[0], "nonce")?,
+            gas_price: rlp_u256(&items[1], "gas_price")?,
+            gas_limit: rlp_u64(&items[2], "gas_limit")?,
+            to: rlp_address(&items[3])?,
+            value: rlp_u256(&items[4], "value")?,
+            data: vec_u8_to_string(&rlp_bytes(&items[5])?),
+            v: rlp_hex(&items[6], "v", 8)?,
+            r: rlp_hex(&items[7], "r", 32)?,
+            s: rlp_hex(&items[8], "s", 32)?,
+        };
 
-        let v_hex = rlp.at(6).as_val::<Vec<u8>>();
-        let v = vec_u8_to_string(&v_hex);
+        match transaction.encoded_chain_id()? {
+            Some(encoded) if encoded != chain_id => {
+                Err(EvmError::InvalidChainId(chain_id, encoded))
+            }
+            _ => Ok(transaction),
+        }
+    }
+}
 
-        let r_hex = rlp.at(7).as_val::<Vec<u8>>();
-        let r = vec_u8_to_string(&r_hex);
+impl EvmTransactionLegacy {
+    /// The chain id the `v` commits to, as of EIP-155.
+    /// None for a transaction from before EIP-155 (`v` of 27 or 28, or empty when unsigned).
+    pub fn encoded_chain_id(&self) -> Result<Option<u64>, EvmError> {
+        let v = vec_u8_to_u64(&string_to_vec_u8(&self.v))?;
 
-        let s_hex = rlp.at(8).as_val::<Vec<u8>>();
-        let s = vec_u8_to_string(&s_hex);
+        let signed = !self.r.is_empty() || !self.s.is_empty();
 
-        let chain_id = data.1;
+        let chain_id = match (signed, v) {
+            (false, 0) | (true, 27) | (true, 28) => None,
+            // an unsigned transaction carries the chain id as `v`
+            (false, v) => Some(v),
+            (true, v) => Some(v.saturating_sub(35) / 2),
+        };
 
-        EvmTransactionLegacy {
-            chain_id,
-            nonce,
-            gas_price,
-            gas_limit,
-            to,
-            value,
-            data: data_tx,
-            v,
-            r,
-            s,
-        }
+        Ok(chain_id)
     }
 }
+
 impl EvmSignTrait for EvmTransactionLegacy {
     fn sign(&mut self, signature: Vec<u8>, public_key: PublicKey) -> Result<Vec<u8>, EvmError> {
         let chain_id = u64::try_from(self.chain_id).unwrap();
@@ -79,7 +100,7 @@ impl EvmSignTrait for EvmTransactionLegacy {
 
         let s = vec_u8_to_string(&s_remove_leading_zeros);
 
-        let message = self.unsigned_serialized();
+        let message = self.unsigned_hash();
         let recovery_id = get_recovery_id(&message, &signature, &public_key)?;
 
         let v_number = chain_id * 2 + 35 + u64::from(recovery_id.serialize());
@@ -138,10 +159,10 @@ impl EvmSignTrait for EvmTransactionLegacy {
 
         let items = [
             u64_to_vec_u8(&self.nonce),
-            u64_to_vec_u8(&self.gas_price),
+            self.gas_price.to_be_bytes(),
             u64_to_vec_u8(&self.gas_limit),
             string_to_vec_u8(&self.to),
-            u64_to_vec_u8(&self.value),
+            self.value.to_be_bytes(),
             string_to_vec_u8(&self.data),
             u64_to_vec_u8(&self.chain_id),
         ];
@@ -164,7 +185,7 @@ impl EvmSignTrait for EvmTransactionLegacy {
         let nonce = u64_to_vec_u8(&self.nonce);
         stream.append(&nonce);
 
-        let gas_price = u64_to_vec_u8(&self.gas_price);
+        let gas_price = self.gas_price.to_be_bytes();
         stream.append(&gas_price);
 
         let gas_limit = u64_to_vec_u8(&self.gas_limit);
@@ -173,7 +194,7 @@ impl EvmSignTrait for EvmTransactionLegacy {
         let to = string_to_vec_u8(&self.to[..]);
         stream.append(&to);
 
-        let value = u64_to_vec_u8(&self.value);
+        let value = self.value.to_be_bytes();
         stream.append(&value);
 
         let data = string_to_vec_u8(&self.data[..]);
@@ -243,12 +264,12 @@ mod tests {
             nonce: 0,
             gas_limit: 0,
             to: "0x".to_string(),
-            value: 0,
+            value: U256::zero(),
             data: "0x".to_string(),
             v: "".to_string(),
             r: "".to_string(),
             s: "".to_string(),
-            gas_price: 0,
+            gas_price: U256::zero(),
         };
 
         // compute the unsigned serialization
@@ -283,4 +304,50 @@ mod tests {
 
         assert_eq!(hash, expected);
     }
+
+    #[test]
+    fn test_round_trip_above_u64() {
+        let tx = EvmTransactionLegacy {
+            chain_id: 1,
+            nonce: 7,
+            gas_price: U256::from(u64::MAX as u128 + 1),
+            gas_limit: 21_000,
+            to: "7a9d2f53fea15e31f0a89d7f5d9e0e82b0b88ad6".to_string(),
+            value: U256::from(100_000_000_000_000_000_000u128),
+            data: "a9059cbb".to_string(),
+            v: "25".to_string(),
+            r: "0a".to_string(),
+            s: "0b".to_string(),
+        };
+
+        assert_eq!(
+            EvmTransactionLegacy::try_from((tx.serialized(), 1)).unwrap(),
+            tx
+        );
+    }
+
+    #[test]
+    fn test_from_1559() {
+        let tx = EvmTransaction1559 {
+            chain_id: 56,
+            nonce: 3,
+            max_priority_fee_per_gas: U256::from(1_000_000_000u64),
+            max_fee_per_gas: U256::from(5_000_000_000u64),
+            gas_limit: 21_000,
+            to: "0x7a9d2f53fea15e31f0a89d7f5d9e0e82b0b88ad6".to_string(),
+            value: U256::from(1u64),
+            data: "".to_string(),
+            access_list: vec![],
+            v: "0x00".to_string(),
+            r: "0x00".to_string(),
+            s: "0x00".to_string(),
+        };
+
+        let legacy = EvmTransactionLegacy::from(&tx);
+
+        assert_eq!(legacy.chain_id, 56);
+        assert_eq!(legacy.nonce, 3);
+        assert_eq!(legacy.gas_price, tx.max_fee_per_gas);
+        assert!(!legacy.is_signed());
+    }
 }
@@ -1,7 +1,9 @@
 use super::error::EvmError;
 use super::evm::{decode_access_list, encode_access_list, get_recovery_id, EvmSignTrait};
+use super::u256::U256;
 use super::utils::{
-    remove_leading, string_to_vec_u8, u64_to_vec_u8, vec_u8_to_string, vec_u8_to_u64,
+    remove_leading, rlp_address, rlp_bytes, rlp_hex, rlp_items, rlp_u256, rlp_u64,
+    string_to_vec_u8, u64_to_vec_u8, vec_u8_to_string,
 };
 use candid::{CandidType, Deserialize};
 use libsecp256k1::{PublicKey, RecoveryId};
@@ -11,11 +13,11 @@ use tiny_keccak::{Hasher, Keccak};
 pub struct EvmTransaction1559 {
     pub chain_id: u64,
     pub nonce: u64,
-    pub max_priority_fee_per_gas: u64,
+    pub max_priority_fee_per_gas: U256,
     pub gas_limit: u64,
-    pub max_fee_per_gas: u64,
+    pub max_fee_per_gas: U256,
     pub to: String,
-    pub value: u64,
+    pub value: U256,
     pub data: String,
     pub access_list: Vec<(String, Vec<String>)>,
     pub v: String,
@@ -23,62 +25,35 @@ pub struct EvmTransaction1559 {
     pub s: String,
 }
 
-impl From<Vec<u8>> for EvmTransaction1559 {
-    fn from(data: Vec<u8>) -> Self {
-        let rlp = rlp::Rlp::new(&data[1..]);
+impl TryFrom<Vec<u8>> for EvmTransaction1559 {
+    type Error = EvmError;
 
-        let chain_id_hex = rlp.at(0).as_val::<Vec<u8>>();
-        let chain_id = vec_u8_to_u64(&chain_id_hex);
-
-        let nonce_hex = rlp.at(1).as_val::<Vec<u8>>();
-        let nonce = vec_u8_to_u64(&nonce_hex);
-
-        let max_priority_fee_per_gas_hex = rlp.at(2).as_val::<Vec<u8>>();
-        let max_priority_fee_per_gas = vec_u8_to_u64(&max_priority_fee_per_gas_hex);
-
-        let max_fee_per_gas_hex = rlp.at(3).as_val::<Vec<u8>>();
-
-        let max_fee_per_gas = vec_u8_to_u64(&max_fee_per_gas_hex);
-
-        let gas_limit_hex = rlp.at(4).as_val::<Vec<u8>>();
-        let gas_limit = vec_u8_to_u64(&gas_limit_hex);
-
-        let to_hex = rlp.at(5).as_val::<Vec<u8>>();
-        let to = vec_u8_to_string(&to_hex);
-
-        let value_hex = rlp.at(6).as_val::<Vec<u8>>();
-        let value = vec_u8_to_u64(&value_hex);
-
-        let data_tx_hex = rlp.at(7).as_val::<Vec<u8>>();
-        let data_tx = vec_u8_to_string(&data_tx_hex);
-
-        let access_list = decode_access_list(&rlp.at(8).as_raw().to_vec());
-
-        let v_hex = rlp.at(9).as_val::<Vec<u8>>();
-        let v = vec_u8_to_string(&v_hex);
-
-        let r_hex = rlp.at(10).as_val::<Vec<u8>>();
-        let r = vec_u8_to_string(&r_hex);
-
-        let s_hex = rlp.at(11).as_val::<Vec<u8>>();
-        let s = vec_u8_to_string(&s_hex);
+    fn try_from(data: Vec<u8>) -> Result<Self, Self::Error> {
+        let payload = match data.split_first() {
+            Some((0x02, payload)) => payload,
+            Some(_) => return Err(EvmError::InvalidTransactionType),
+            None => return Err(EvmError::InvalidLength("transaction".to_string(), 0)),
+        };
 
-        EvmTransaction1559 {
-            chain_id,
-            nonce,
-            max_priority_fee_per_gas,
-            max_fee_per_gas,
-            gas_limit,
-            to,
-            value,
-            data: data_tx,
-            access_list,
-            v,
-            r,
-            s,
-        }
+        let items = rlp_items(payload, 12)?;
+
+        Ok(EvmTransaction1559 {
+            chain_id: rlp_u64(&items[0], "chain_id")?,
+            nonce: rlp_u64(&items[1], "nonce")?,
+            max_priority_fee_per_gas: rlp_u256(&items[2], "max_priority_fee_per_gas")?,
+            max_fee_per_gas: rlp_u256(&items[3], "max_fee_per_gas")?,
+            gas_limit: rlp_u64(&items[4], "gas_limit")?,
+            to: rlp_address(&items[5])?,
+            value: rlp_u256(&items[6], "value")?,
+            data: vec_u8_to_string(&rlp_bytes(&items[7])?),
+            access_list: decode_access_list(items[8].as_raw())?,
+            v: rlp_hex(&items[9], "v", 8)?,
+            r: rlp_hex(&items[10], "r", 32)?,
+            s: rlp_hex(&items[11], "s", 32)?,
+        })
     }
 }
+
 impl EvmSignTrait for EvmTransaction1559 {
     fn sign(&mut self, signature: Vec<u8>, public_key: PublicKey) -> Result<Vec<u8>, EvmError> {
         let r_remove_leading_zeros = remove_leading(signature[..32].to_vec(), 0);
@@ -88,7 +63,7 @@ impl EvmSignTrait for EvmTransaction1559 {
 
         let s = vec_u8_to_string(&s_remove_leading_zeros);
 
-        let message = self.unsigned_serialized();
+        let message = self.unsigned_hash();
 
         let recovery_id = get_recovery_id(&message, &signature, &public_key)?;
 
@@ -156,11 +131,11 @@ impl EvmSignTrait for EvmTransaction1559 {
         let items = [
             u64_to_vec_u8(&self.chain_id),
             u64_to_vec_u8(&self.nonce),
-            u64_to_vec_u8(&self.max_priority_fee_per_gas),
-            u64_to_vec_u8(&self.max_fee_per_gas),
+            self.max_priority_fee_per_gas.to_be_bytes(),
+            self.max_fee_per_gas.to_be_bytes(),
             u64_to_vec_u8(&self.gas_limit),
             string_to_vec_u8(&self.to),
-            u64_to_vec_u8(&self.value),
+            self.value.to_be_bytes(),
             string_to_vec_u8(&self.data),
         ];
 
@@ -186,10 +161,10 @@ impl EvmSignTrait for EvmTransaction1559 {
         let nonce = u64_to_vec_u8(&self.nonce);
         stream.append(&nonce);
 
-        let max_priority_fee_per_gas = u64_to_vec_u8(&self.max_priority_fee_per_gas);
+        let max_priority_fee_per_gas = self.max_priority_fee_per_gas.to_be_bytes();
         stream.append(&max_priority_fee_per_gas);
 
-        let max_fee_per_gas = u64_to_vec_u8(&self.max_fee_per_gas);
+        let max_fee_per_gas = self.max_fee_per_gas.to_be_bytes();
         stream.append(&max_fee_per_gas);
 
         let gas_limit = u64_to_vec_u8(&self.gas_limit);
@@ -198,7 +173,7 @@ impl EvmSignTrait for EvmTransaction1559 {
         let to = string_to_vec_u8(&self.to[..]);
         stream.append(&to);
 
-        let value = u64_to_vec_u8(&self.value);
+        let value = self.value.to_be_bytes();
         stream.append(&value);
 
         let data = string_to_vec_u8(&self.data[..]);
@@ -272,11 +247,11 @@ mod tests {
         let tx = EvmTransaction1559 {
             chain_id: 1,
             nonce: 0,
-            max_priority_fee_per_gas: 0,
-            max_fee_per_gas: 0,
+            max_priority_fee_per_gas: U256::zero(),
+            max_fee_per_gas: U256::zero(),
             gas_limit: 0,
             to: "0x".to_string(),
-            value: 0,
+            value: U256::zero(),
             data: "0x".to_string(),
             access_list: vec![],
             v: "".to_string(),
@@ -318,4 +293,28 @@ mod tests {
 
         assert_eq!(hash, expected);
     }
+
+    #[test]
+    fn test_round_trip_above_u64() {
+        // 100 ETH and fees past 2^64 wei
+        let tx = EvmTransaction1559 {
+            chain_id: 1,
+            nonce: 7,
+            max_priority_fee_per_gas: U256::from(u64::MAX as u128 + 1),
+            max_fee_per_gas: U256::from(u128::MAX),
+            gas_limit: 21_000,
+            to: "7a9d2f53fea15e31f0a89d7f5d9e0e82b0b88ad6".to_string(),
+            value: U256::from(100_000_000_000_000_000_000u128),
+            data: "a9059cbb".to_string(),
+            access_list: vec![],
+            v: "01".to_string(),
+            r: "0a".to_string(),
+            s: "0b".to_string(),
+        };
+
+        let decoded = EvmTransaction1559::try_from(tx.serialized()).unwrap();
+
+        assert_eq!(decoded, tx);
+        assert_eq!(decoded.value.to_string(), "100000000000000000000");
+    }
 }
@@ -1,8 +1,19 @@
+pub mod abi;
 pub mod api;
 pub mod berlin;
+pub mod eip712;
+pub mod ens;
 pub mod error;
 pub mod evm;
+pub mod gas;
 pub mod legacy;
 pub mod london;
+pub mod network;
+pub mod rpc;
+pub mod simulation;
+#[cfg(test)]
+mod test;
 pub mod types;
+pub mod u256;
+pub mod user_operation;
 pub mod utils;
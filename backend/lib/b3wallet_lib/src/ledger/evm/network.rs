@@ -0,0 +1,146 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+use super::{error::EvmError, rpc::RpcApi};
+use crate::ledger::{token::TokenDescriptor, types::ChainId};
+
+/// An EVM network the accounts can hold addresses on.
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct EvmNetwork {
+    pub chain_id: ChainId,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    /// Whether the network prices gas with a base fee and a tip,
+    /// legacy transactions are sent otherwise.
+    pub eip1559: bool,
+    /// The block explorer, e.g. `https://arbiscan.io`.
+    pub explorer_url: Option<String>,
+    /// Providers of the network, instead of the ones of the wallet RPC settings.
+    pub providers: Option<Vec<RpcApi>>,
+}
+
+impl EvmNetwork {
+    fn known(chain_id: ChainId, name: &str) -> Self {
+        EvmNetwork {
+            chain_id,
+            name: name.to_string(),
+            symbol: "ETH".to_string(),
+            decimals: 18,
+            eip1559: true,
+            explorer_url: None,
+            providers: None,
+        }
+    }
+
+    fn with_explorer(self, explorer_url: &str) -> Self {
+        EvmNetwork {
+            explorer_url: Some(explorer_url.to_string()),
+            ..self
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), EvmError> {
+        if self.chain_id == 0 {
+            return Err(EvmError::InvalidNetwork("chain id 0".to_string()));
+        }
+
+        if self.name.trim().is_empty() || self.symbol.trim().is_empty() {
+            return Err(EvmError::InvalidNetwork(format!(
+                "empty name or symbol for chain {}",
+                self.chain_id
+            )));
+        }
+
+        if let Some(ref providers) = self.providers {
+            if providers.is_empty() {
+                return Err(EvmError::InvalidNetwork(format!(
+                    "no providers for chain {}",
+                    self.chain_id
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The native currency of the network.
+    pub fn token(&self) -> TokenDescriptor {
+        TokenDescriptor::new(&self.symbol, self.decimals, None)
+    }
+
+    /// The page of the transaction on the block explorer, if the network has one.
+    pub fn transaction_url(&self, tx_hash: &str) -> Option<String> {
+        self.explorer_url
+            .as_ref()
+            .map(|url| format!("{}/tx/{}", url.trim_end_matches('/'), tx_hash))
+    }
+}
+
+/// The networks the RPC canister has providers for, the registry until the admin sets one.
+pub fn default_evm_networks() -> Vec<EvmNetwork> {
+    vec![
+        EvmNetwork::known(1, "Ethereum").with_explorer("https://etherscan.io"),
+        EvmNetwork::known(11155111, "Sepolia").with_explorer("https://sepolia.etherscan.io"),
+        EvmNetwork::known(42161, "Arbitrum One").with_explorer("https://arbiscan.io"),
+        EvmNetwork::known(8453, "Base").with_explorer("https://basescan.org"),
+        EvmNetwork::known(10, "OP Mainnet").with_explorer("https://optimistic.etherscan.io"),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_networks() {
+        let networks = default_evm_networks();
+
+        assert!(networks.iter().all(|network| network.validate().is_ok()));
+
+        let arbitrum = networks
+            .iter()
+            .find(|network| network.chain_id == 42161)
+            .unwrap();
+
+        assert_eq!(arbitrum.name, "Arbitrum One");
+        assert_eq!(
+            arbitrum.transaction_url("0xabc"),
+            Some("https://arbiscan.io/tx/0xabc".to_string())
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let network = EvmNetwork {
+            chain_id: 56,
+            name: "BNB Smart Chain".to_string(),
+            symbol: "BNB".to_string(),
+            decimals: 18,
+            eip1559: false,
+            explorer_url: Some("https://bscscan.com/".to_string()),
+            providers: Some(vec![RpcApi {
+                url: "https://bsc-dataseed.bnbchain.org".to_string(),
+                headers: None,
+            }]),
+        };
+
+        assert!(network.validate().is_ok());
+        assert_eq!(
+            network.transaction_url("0xabc"),
+            Some("https://bscscan.com/tx/0xabc".to_string())
+        );
+
+        let invalid = EvmNetwork {
+            providers: Some(vec![]),
+            ..network.clone()
+        };
+        assert!(invalid.validate().is_err());
+
+        let invalid = EvmNetwork {
+            name: " ".to_string(),
+            ..network
+        };
+        assert!(invalid.validate().is_err());
+    }
+}
@@ -0,0 +1,995 @@
+use b3_utils::{types::CanisterId, vec_to_hex_string_with_0x};
+use candid::{utils::ArgumentEncoder, CandidType, Nat, Principal};
+use ic_cdk::api::call::call_with_payment128;
+use num_traits::ToPrimitive;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{collections::BTreeMap, fmt};
+
+use super::{
+    abi::decode_revert_reason,
+    error::EvmError,
+    types::{EvmReceipt, EvmReceiptStatus},
+    u256::U256,
+    user_operation::UserOperation,
+    utils::hex_to_nat,
+};
+use crate::ledger::{cycles::error::RejectionCode, types::ChainId};
+
+pub const EVM_RPC_CANISTER_ID: &str = "7hfb6-caaaa-aaaar-qadga-cai";
+
+/// Attached to every call, the RPC canister refunds what the providers didn't use.
+pub const EVM_RPC_CYCLES: u128 = 10_000_000_000;
+
+/// The most cycles a call can be set to attach, the refund is up to the RPC canister.
+pub const MAX_EVM_RPC_CYCLES: u128 = 100_000_000_000;
+
+/// Bounds the size of a JSON-RPC response, the providers are paid for it upfront.
+pub const MAX_RESPONSE_BYTES: u64 = 4_096;
+
+/// Receipts carry the logs of the transaction, which can outgrow the usual bound.
+pub const RECEIPT_RESPONSE_BYTES: u64 = 32_768;
+
+/// Contracts are up to 24 KB, twice that once hex encoded.
+pub const CODE_RESPONSE_BYTES: u64 = 65_536;
+
+/// Used when the providers have no fee history to suggest a priority fee from.
+pub const DEFAULT_PRIORITY_FEE_PER_GAS: u64 = 1_500_000_000;
+
+#[derive(CandidType, Clone, Deserialize, Serialize, PartialEq, Eq, Debug)]
+pub struct HttpHeader {
+    pub name: String,
+    pub value: String,
+}
+
+/// A JSON-RPC endpoint, e.g. a local node behind a stand-in RPC canister.
+#[derive(CandidType, Clone, Deserialize, Serialize, PartialEq, Eq, Debug)]
+pub struct RpcApi {
+    pub url: String,
+    pub headers: Option<Vec<HttpHeader>>,
+}
+
+#[derive(CandidType, Clone, Deserialize, Serialize, PartialEq, Eq, Debug)]
+pub struct RpcConfig {
+    #[serde(rename = "responseSizeEstimate")]
+    pub response_size_estimate: Option<u64>,
+}
+
+#[derive(CandidType, Clone, Deserialize, Serialize, PartialEq, Eq, Debug)]
+pub enum EthMainnetService {
+    Alchemy,
+    Ankr,
+    BlockPi,
+    PublicNode,
+    Cloudflare,
+    Llama,
+}
+
+#[derive(CandidType, Clone, Deserialize, Serialize, PartialEq, Eq, Debug)]
+pub enum EthSepoliaService {
+    Alchemy,
+    Ankr,
+    BlockPi,
+    PublicNode,
+    Sepolia,
+}
+
+#[derive(CandidType, Clone, Deserialize, Serialize, PartialEq, Eq, Debug)]
+pub enum L2MainnetService {
+    Alchemy,
+    Ankr,
+    BlockPi,
+    PublicNode,
+    Llama,
+}
+
+/// The providers a call is sent to, `None` lets the RPC canister pick its defaults.
+#[derive(CandidType, Clone, Deserialize, Serialize, PartialEq, Eq, Debug)]
+pub enum RpcServices {
+    EthMainnet(Option<Vec<EthMainnetService>>),
+    EthSepolia(Option<Vec<EthSepoliaService>>),
+    ArbitrumOne(Option<Vec<L2MainnetService>>),
+    BaseMainnet(Option<Vec<L2MainnetService>>),
+    OptimismMainnet(Option<Vec<L2MainnetService>>),
+    Custom {
+        #[serde(rename = "chainId")]
+        chain_id: u64,
+        services: Vec<RpcApi>,
+    },
+}
+
+/// The custom providers of each chain.
+pub type ProviderMap = BTreeMap<ChainId, Vec<RpcApi>>;
+
+/// The provider that answered, only seen when the providers disagree.
+#[derive(CandidType, Clone, Deserialize, Serialize, PartialEq, Eq, Debug)]
+pub enum RpcService {
+    Provider(u64),
+    Custom(RpcApi),
+    EthMainnet(EthMainnetService),
+    EthSepolia(EthSepoliaService),
+    ArbitrumOne(L2MainnetService),
+    BaseMainnet(L2MainnetService),
+    OptimismMainnet(L2MainnetService),
+}
+
+#[derive(CandidType, Clone, Deserialize, Serialize, PartialEq, Eq, Debug)]
+pub enum BlockTag {
+    Earliest,
+    Safe,
+    Finalized,
+    Latest,
+    Number(Nat),
+    Pending,
+}
+
+#[derive(CandidType, Clone, Deserialize, Serialize, PartialEq, Eq, Debug)]
+pub struct GetTransactionCountArgs {
+    pub address: String,
+    pub block: BlockTag,
+}
+
+/// The transaction of an `eth_call`, the fields left out are `null`.
+#[derive(CandidType, Clone, Deserialize, Serialize, PartialEq, Eq, Debug)]
+pub struct TransactionRequest {
+    pub to: Option<String>,
+    pub input: Option<String>,
+}
+
+#[derive(CandidType, Clone, Deserialize, Serialize, PartialEq, Eq, Debug)]
+pub struct CallArgs {
+    pub transaction: TransactionRequest,
+    pub block: Option<BlockTag>,
+}
+
+/// The fields of the receipt the wallet reads, the RPC canister sends more.
+#[derive(CandidType, Clone, Deserialize, Serialize, PartialEq, Eq, Debug)]
+pub struct TransactionReceipt {
+    #[serde(rename = "transactionHash")]
+    pub transaction_hash: String,
+    #[serde(rename = "blockNumber")]
+    pub block_number: Nat,
+    pub status: Option<Nat>,
+    #[serde(rename = "gasUsed")]
+    pub gas_used: Nat,
+    #[serde(rename = "effectiveGasPrice")]
+    pub effective_gas_price: Nat,
+}
+
+#[derive(CandidType, Clone, Deserialize, Serialize, PartialEq, Eq, Debug)]
+pub struct FeeHistoryArgs {
+    #[serde(rename = "blockCount")]
+    pub block_count: Nat,
+    #[serde(rename = "newestBlock")]
+    pub newest_block: BlockTag,
+    #[serde(rename = "rewardPercentiles")]
+    pub reward_percentiles: Option<Vec<u8>>,
+}
+
+#[derive(CandidType, Clone, Deserialize, Serialize, PartialEq, Debug)]
+pub struct FeeHistory {
+    #[serde(rename = "oldestBlock")]
+    pub oldest_block: Nat,
+    #[serde(rename = "baseFeePerGas")]
+    pub base_fee_per_gas: Vec<Nat>,
+    #[serde(rename = "gasUsedRatio")]
+    pub gas_used_ratio: Vec<f64>,
+    pub reward: Vec<Vec<Nat>>,
+}
+
+#[derive(CandidType, Clone, Deserialize, Serialize, PartialEq, Eq, Debug)]
+pub enum SendRawTransactionStatus {
+    Ok(Option<String>),
+    NonceTooLow,
+    NonceTooHigh,
+    InsufficientFunds,
+}
+
+#[derive(CandidType, Clone, Deserialize, PartialEq, Eq, Debug)]
+pub struct JsonRpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+#[derive(CandidType, Clone, Deserialize, PartialEq, Eq, Debug)]
+pub enum ProviderError {
+    TooFewCycles { expected: Nat, received: Nat },
+    MissingRequiredProvider,
+    ProviderNotFound,
+    NoPermission,
+    InvalidRpcConfig(String),
+}
+
+#[derive(CandidType, Clone, Deserialize, PartialEq, Eq, Debug)]
+pub enum ValidationError {
+    Custom(String),
+    InvalidHex(String),
+}
+
+#[derive(CandidType, Clone, Deserialize, PartialEq, Eq, Debug)]
+pub enum HttpOutcallError {
+    IcError {
+        code: RejectionCode,
+        message: String,
+    },
+    InvalidHttpJsonRpcResponse {
+        status: u16,
+        body: String,
+        #[serde(rename = "parsingError")]
+        parsing_error: Option<String>,
+    },
+}
+
+#[derive(CandidType, Clone, Deserialize, PartialEq, Eq, Debug)]
+pub enum RpcError {
+    ProviderError(ProviderError),
+    HttpOutcallError(HttpOutcallError),
+    JsonRpcError(JsonRpcError),
+    ValidationError(ValidationError),
+}
+
+#[rustfmt::skip]
+impl fmt::Display for RpcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RpcError::ProviderError(err) => write!(f, "Provider error: {:?}", err),
+            RpcError::HttpOutcallError(err) => write!(f, "HTTP outcall error: {:?}", err),
+            RpcError::JsonRpcError(err) => write!(f, "JSON-RPC error {}: {}", err.code, err.message),
+            RpcError::ValidationError(err) => write!(f, "Validation error: {:?}", err),
+        }
+    }
+}
+
+#[derive(CandidType, Clone, Deserialize, Debug)]
+pub enum MultiRpcResult<T> {
+    Consistent(Result<T, RpcError>),
+    Inconsistent(Vec<(RpcService, Result<T, RpcError>)>),
+}
+
+impl<T: fmt::Debug> MultiRpcResult<T> {
+    /// The answer all the providers agreed on.
+    pub fn consistent(self) -> Result<T, EvmError> {
+        match self {
+            MultiRpcResult::Consistent(Ok(value)) => Ok(value),
+            MultiRpcResult::Consistent(Err(err)) => Err(EvmError::RpcError(err.to_string())),
+            MultiRpcResult::Inconsistent(results) => {
+                Err(EvmError::InconsistentRpcResults(format!("{:?}", results)))
+            }
+        }
+    }
+}
+
+/// The result of a JSON-RPC response, or the error the node answered with.
+/// A revert whose data carries a reason is returned as `ExecutionReverted`.
+pub fn parse_json_rpc_response(response: &str) -> Result<Value, EvmError> {
+    let mut response: Value =
+        serde_json::from_str(response).map_err(|err| EvmError::InvalidResponse(err.to_string()))?;
+
+    if let Some(error) = response.get("error") {
+        let message = error
+            .get("message")
+            .and_then(Value::as_str)
+            .unwrap_or("Unknown error");
+
+        let reason = error
+            .get("data")
+            .and_then(Value::as_str)
+            .and_then(|data| hex::decode(data.strip_prefix("0x").unwrap_or(data)).ok())
+            .and_then(|data| decode_revert_reason(&data));
+
+        if let Some(reason) = reason {
+            return Err(EvmError::ExecutionReverted(reason));
+        }
+
+        return Err(EvmError::RpcError(message.to_string()));
+    }
+
+    response
+        .get_mut("result")
+        .map(Value::take)
+        .ok_or_else(|| EvmError::InvalidResponse("Missing result".to_string()))
+}
+
+/// A hex encoded JSON-RPC quantity, e.g. a balance.
+pub fn parse_quantity(value: &Value) -> Result<Nat, EvmError> {
+    let quantity = value
+        .as_str()
+        .ok_or_else(|| EvmError::InvalidResponse(format!("Not a quantity: {}", value)))?;
+
+    hex_to_nat(quantity)
+}
+
+/// The transaction object of `eth_call` and `eth_estimateGas`, the hex fields `0x` prefixed.
+/// An empty `to` is a contract deployment.
+pub fn call_object(from: &str, to: &str, value: &U256, data: &str) -> Value {
+    let with_0x = |hex: &str| format!("0x{}", hex.strip_prefix("0x").unwrap_or(hex));
+
+    let mut transaction = json!({ "from": with_0x(from), "value": value.to_hex() });
+
+    if !to.is_empty() && to != "0x" {
+        transaction["to"] = json!(with_0x(to));
+    }
+
+    if !data.is_empty() && data != "0x" {
+        transaction["data"] = json!(with_0x(data));
+    }
+
+    transaction
+}
+
+/// A hex encoded JSON-RPC quantity that fits in a `u64`, e.g. a block number.
+pub fn parse_u64(value: &Value) -> Result<u64, EvmError> {
+    let quantity = parse_quantity(value)?;
+
+    quantity
+        .0
+        .to_u64()
+        .ok_or_else(|| EvmError::InvalidResponse(format!("Not a u64: {}", quantity)))
+}
+
+impl TransactionReceipt {
+    /// The receipt as the wallet keeps it, the status must be `0` or `1`.
+    pub fn receipt(&self) -> Result<EvmReceipt, EvmError> {
+        let to_u64 = |name: &str, value: &Nat| {
+            value.0.to_u64().ok_or_else(|| {
+                EvmError::InvalidResponse(format!("Invalid receipt {}: {}", name, value))
+            })
+        };
+
+        let status = match self.status.as_ref().map(|status| to_u64("status", status)) {
+            Some(Ok(1)) => EvmReceiptStatus::Success,
+            Some(Ok(0)) => EvmReceiptStatus::Reverted,
+            _ => {
+                return Err(EvmError::InvalidResponse(format!(
+                    "Invalid receipt status: {:?}",
+                    self.status
+                )))
+            }
+        };
+
+        Ok(EvmReceipt {
+            tx_hash: self.transaction_hash.clone(),
+            status,
+            block_number: Some(to_u64("block number", &self.block_number)?),
+            gas_used: Some(to_u64("gas used", &self.gas_used)?),
+            effective_gas_price: Some(U256::try_from(self.effective_gas_price.clone())?),
+        })
+    }
+}
+
+impl FeeHistory {
+    /// The base fee of the next block.
+    pub fn base_fee(&self) -> Result<u64, EvmError> {
+        self.base_fee_per_gas
+            .last()
+            .and_then(|fee| fee.0.to_u64())
+            .ok_or_else(|| EvmError::RpcError("Missing base fee".to_string()))
+    }
+
+    /// The fees to pay to be included in the next blocks: the median tip of the recent
+    /// blocks, and room for the base fee to grow `base_fee_multiplier` times before the
+    /// transaction is mined.
+    pub fn suggested_fees(&self, base_fee_multiplier: u64) -> Result<(u64, u64), EvmError> {
+        let base_fee = self.base_fee()?;
+
+        let mut rewards = self
+            .reward
+            .iter()
+            .filter_map(|rewards| rewards.first().and_then(|reward| reward.0.to_u64()))
+            .collect::<Vec<_>>();
+
+        rewards.sort_unstable();
+
+        let max_priority_fee_per_gas = rewards
+            .get(rewards.len() / 2)
+            .copied()
+            .unwrap_or(DEFAULT_PRIORITY_FEE_PER_GAS);
+
+        let max_fee_per_gas = base_fee
+            .saturating_mul(base_fee_multiplier)
+            .saturating_add(max_priority_fee_per_gas);
+
+        Ok((max_fee_per_gas, max_priority_fee_per_gas))
+    }
+}
+
+/// Client of the EVM RPC canister, which relays JSON-RPC calls to Ethereum providers.
+#[derive(CandidType, Clone, Deserialize, Serialize, PartialEq, Debug)]
+pub struct EvmRpc {
+    pub canister_id: CanisterId,
+    /// Custom providers of the chains, used instead of the defaults of the RPC canister.
+    pub providers: ProviderMap,
+    /// The cycles attached to each call, `EVM_RPC_CYCLES` if not set.
+    pub cycles: Option<u128>,
+}
+
+impl Default for EvmRpc {
+    fn default() -> Self {
+        EvmRpc {
+            canister_id: Principal::from_text(EVM_RPC_CANISTER_ID).unwrap(),
+            providers: ProviderMap::new(),
+            cycles: None,
+        }
+    }
+}
+
+impl EvmRpc {
+    pub fn new(canister_id: CanisterId) -> Self {
+        EvmRpc {
+            canister_id,
+            ..Default::default()
+        }
+    }
+
+    /// The same client, with the given providers for the chain.
+    pub fn with_providers(mut self, chain_id: ChainId, providers: Vec<RpcApi>) -> Self {
+        self.providers.insert(chain_id, providers);
+
+        self
+    }
+
+    /// The providers to ask for the given chain, its custom ones if set.
+    pub fn services(&self, chain_id: ChainId) -> Result<RpcServices, EvmError> {
+        if let Some(providers) = self.providers.get(&chain_id) {
+            return Ok(RpcServices::Custom {
+                chain_id,
+                services: providers.clone(),
+            });
+        }
+
+        match chain_id {
+            1 => Ok(RpcServices::EthMainnet(None)),
+            11155111 => Ok(RpcServices::EthSepolia(None)),
+            42161 => Ok(RpcServices::ArbitrumOne(None)),
+            8453 => Ok(RpcServices::BaseMainnet(None)),
+            10 => Ok(RpcServices::OptimismMainnet(None)),
+            _ => Err(EvmError::UnsupportedChain(chain_id)),
+        }
+    }
+
+    /// The single provider to ask for the given chain, for the calls without consensus.
+    pub fn service(&self, chain_id: ChainId) -> Result<RpcService, EvmError> {
+        if let Some(provider) = self.providers.get(&chain_id).and_then(|p| p.first()) {
+            return Ok(RpcService::Custom(provider.clone()));
+        }
+
+        match chain_id {
+            1 => Ok(RpcService::EthMainnet(EthMainnetService::PublicNode)),
+            11155111 => Ok(RpcService::EthSepolia(EthSepoliaService::PublicNode)),
+            42161 => Ok(RpcService::ArbitrumOne(L2MainnetService::PublicNode)),
+            8453 => Ok(RpcService::BaseMainnet(L2MainnetService::PublicNode)),
+            10 => Ok(RpcService::OptimismMainnet(L2MainnetService::PublicNode)),
+            _ => Err(EvmError::UnsupportedChain(chain_id)),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), EvmError> {
+        match self.cycles {
+            Some(cycles) if cycles == 0 || cycles > MAX_EVM_RPC_CYCLES => {
+                return Err(EvmError::InvalidRpc(format!(
+                    "{} cycles per call, must be between 1 and {}",
+                    cycles, MAX_EVM_RPC_CYCLES
+                )));
+            }
+            _ => {}
+        }
+
+        for (chain_id, providers) in self.providers.iter() {
+            if providers.is_empty() || providers.iter().any(|p| p.url.trim().is_empty()) {
+                return Err(EvmError::InvalidRpc(format!(
+                    "empty providers for chain {}",
+                    chain_id
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn cycles(&self) -> u128 {
+        self.cycles
+            .unwrap_or(EVM_RPC_CYCLES)
+            .min(MAX_EVM_RPC_CYCLES)
+    }
+
+    /// Send a raw JSON-RPC request, for the methods the RPC canister has no endpoint for.
+    pub async fn request(
+        &self,
+        chain_id: ChainId,
+        method: &str,
+        params: Value,
+    ) -> Result<Value, EvmError> {
+        self.request_with_limit(chain_id, method, params, MAX_RESPONSE_BYTES)
+            .await
+    }
+
+    /// Same as `request`, for responses larger than `MAX_RESPONSE_BYTES`.
+    pub async fn request_with_limit(
+        &self,
+        chain_id: ChainId,
+        method: &str,
+        params: Value,
+        max_response_bytes: u64,
+    ) -> Result<Value, EvmError> {
+        let service = self.service(chain_id)?;
+
+        let json = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": params,
+        })
+        .to_string();
+
+        let (res,): (Result<String, RpcError>,) = call_with_payment128(
+            self.canister_id,
+            "request",
+            (service, json, max_response_bytes),
+            self.cycles(),
+        )
+        .await
+        .map_err(|e| EvmError::CallError(e.1))?;
+
+        let response = res.map_err(|err| EvmError::RpcError(err.to_string()))?;
+
+        parse_json_rpc_response(&response)
+    }
+
+    /// The native balance of the address at the latest block, in wei.
+    pub async fn get_balance(&self, chain_id: ChainId, address: &str) -> Result<Nat, EvmError> {
+        let result = self
+            .request(chain_id, "eth_getBalance", json!([address, "latest"]))
+            .await?;
+
+        parse_quantity(&result)
+    }
+
+    /// The gas price of the network, for the transactions without EIP-1559 fees.
+    pub async fn gas_price(&self, chain_id: ChainId) -> Result<u64, EvmError> {
+        let result = self.request(chain_id, "eth_gasPrice", json!([])).await?;
+
+        parse_u64(&result)
+    }
+
+    /// Run a read-only contract call at the latest block, returns the hex encoded output.
+    /// The providers must agree on it, the wallet acts on what the contracts answer.
+    pub async fn eth_call(
+        &self,
+        chain_id: ChainId,
+        to: &str,
+        data: &str,
+    ) -> Result<String, EvmError> {
+        let services = self.services(chain_id)?;
+
+        let args = CallArgs {
+            transaction: TransactionRequest {
+                to: Some(to.to_string()),
+                input: Some(data.to_string()),
+            },
+            block: Some(BlockTag::Latest),
+        };
+
+        let result: MultiRpcResult<String> = self
+            .call("eth_call", (services, None::<RpcConfig>, args))
+            .await?;
+
+        result.consistent()
+    }
+
+    /// Run the transaction as a call from the address at the latest block, without
+    /// sending it, returns the hex encoded output or the reason it reverted.
+    pub async fn simulate_call(
+        &self,
+        chain_id: ChainId,
+        from: &str,
+        to: &str,
+        value: &U256,
+        data: &str,
+    ) -> Result<String, EvmError> {
+        let transaction = call_object(from, to, value, data);
+
+        let result = self
+            .request(chain_id, "eth_call", json!([transaction, "latest"]))
+            .await?;
+
+        result
+            .as_str()
+            .map(|output| output.to_string())
+            .ok_or_else(|| EvmError::InvalidResponse(format!("Not call output: {}", result)))
+    }
+
+    /// The gas the transaction would use if it was mined now.
+    /// An empty `to` estimates a contract deployment.
+    pub async fn estimate_gas(
+        &self,
+        chain_id: ChainId,
+        from: &str,
+        to: &str,
+        value: &U256,
+        data: &str,
+    ) -> Result<u64, EvmError> {
+        let transaction = call_object(from, to, value, data);
+
+        let result = self
+            .request(chain_id, "eth_estimateGas", json!([transaction]))
+            .await?;
+
+        parse_quantity(&result)?
+            .0
+            .to_u64()
+            .ok_or_else(|| EvmError::InvalidResponse(format!("Gas too large: {}", result)))
+    }
+
+    async fn call<A, T>(&self, method: &str, args: A) -> Result<MultiRpcResult<T>, EvmError>
+    where
+        A: ArgumentEncoder + Send,
+        T: CandidType + DeserializeOwned + Send,
+    {
+        let (res,): (MultiRpcResult<T>,) =
+            call_with_payment128(self.canister_id, method, args, self.cycles())
+                .await
+                .map_err(|e| EvmError::CallError(e.1))?;
+
+        Ok(res)
+    }
+
+    /// Broadcast a signed transaction, returns its hash if the providers report it.
+    /// One provider accepting it is enough, the others will see it through the mempool.
+    pub async fn send_raw_transaction(
+        &self,
+        chain_id: ChainId,
+        raw_tx: &[u8],
+    ) -> Result<Option<String>, EvmError> {
+        let services = self.services(chain_id)?;
+
+        let raw_tx = vec_to_hex_string_with_0x(raw_tx);
+
+        let result: MultiRpcResult<SendRawTransactionStatus> = self
+            .call(
+                "eth_sendRawTransaction",
+                (services, None::<RpcConfig>, raw_tx),
+            )
+            .await?;
+
+        let status = match result {
+            MultiRpcResult::Inconsistent(results) => results
+                .iter()
+                .find_map(|(_, result)| match result {
+                    Ok(status @ SendRawTransactionStatus::Ok(_)) => Some(status.clone()),
+                    _ => None,
+                })
+                .ok_or_else(|| EvmError::InconsistentRpcResults(format!("{:?}", results)))?,
+            result => result.consistent()?,
+        };
+
+        match status {
+            SendRawTransactionStatus::Ok(tx_hash) => Ok(tx_hash),
+            SendRawTransactionStatus::NonceTooLow => Err(EvmError::NonceTooLow),
+            SendRawTransactionStatus::NonceTooHigh => Err(EvmError::NonceTooHigh),
+            SendRawTransactionStatus::InsufficientFunds => Err(EvmError::InsufficientFunds),
+        }
+    }
+
+    /// The code at the address, `0x` when no contract is deployed there.
+    pub async fn get_code(&self, chain_id: ChainId, address: &str) -> Result<String, EvmError> {
+        let result = self
+            .request_with_limit(
+                chain_id,
+                "eth_getCode",
+                json!([address, "latest"]),
+                CODE_RESPONSE_BYTES,
+            )
+            .await?;
+
+        result
+            .as_str()
+            .map(|code| code.to_string())
+            .ok_or_else(|| EvmError::InvalidResponse(format!("Not code: {}", result)))
+    }
+
+    /// Hand the signed operation to the bundler, which returns its hash once accepted.
+    pub async fn send_user_operation(
+        &self,
+        chain_id: ChainId,
+        bundler: &RpcApi,
+        user_operation: &UserOperation,
+        entry_point: &str,
+    ) -> Result<String, EvmError> {
+        let bundler_rpc = self.clone().with_providers(chain_id, vec![bundler.clone()]);
+
+        let result = bundler_rpc
+            .request(
+                chain_id,
+                "eth_sendUserOperation",
+                json!([user_operation.to_json(), entry_point]),
+            )
+            .await?;
+
+        result
+            .as_str()
+            .map(|hash| hash.to_string())
+            .ok_or_else(|| EvmError::InvalidResponse(format!("Not an operation hash: {}", result)))
+    }
+
+    pub async fn get_transaction_count(
+        &self,
+        chain_id: ChainId,
+        address: String,
+        block: BlockTag,
+    ) -> Result<u64, EvmError> {
+        let services = self.services(chain_id)?;
+
+        let args = GetTransactionCountArgs { address, block };
+
+        let result: MultiRpcResult<Nat> = self
+            .call(
+                "eth_getTransactionCount",
+                (services, None::<RpcConfig>, args),
+            )
+            .await?;
+
+        let count = result.consistent()?;
+
+        count
+            .0
+            .to_u64()
+            .ok_or_else(|| EvmError::RpcError(format!("Invalid transaction count: {}", count)))
+    }
+
+    /// The receipt of the transaction, `None` while it is unknown or not mined.
+    /// The providers must agree on it, the pending transaction is settled from it.
+    pub async fn get_transaction_receipt(
+        &self,
+        chain_id: ChainId,
+        tx_hash: &str,
+    ) -> Result<Option<EvmReceipt>, EvmError> {
+        let services = self.services(chain_id)?;
+
+        let config = RpcConfig {
+            response_size_estimate: Some(RECEIPT_RESPONSE_BYTES),
+        };
+
+        let result: MultiRpcResult<Option<TransactionReceipt>> = self
+            .call(
+                "eth_getTransactionReceipt",
+                (services, Some(config), tx_hash.to_string()),
+            )
+            .await?;
+
+        result
+            .consistent()?
+            .map(|receipt| receipt.receipt())
+            .transpose()
+    }
+
+    /// The base fees and the tips paid at the given percentiles in the last blocks.
+    pub async fn fee_history(
+        &self,
+        chain_id: ChainId,
+        block_count: u64,
+        reward_percentiles: Vec<u8>,
+    ) -> Result<FeeHistory, EvmError> {
+        let services = self.services(chain_id)?;
+
+        let args = FeeHistoryArgs {
+            block_count: block_count.into(),
+            newest_block: BlockTag::Latest,
+            reward_percentiles: Some(reward_percentiles),
+        };
+
+        let result: MultiRpcResult<FeeHistory> = self
+            .call("eth_feeHistory", (services, None::<RpcConfig>, args))
+            .await?;
+
+        result.consistent()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        assert!(EvmRpc::default().validate().is_ok());
+
+        let rpc = EvmRpc {
+            cycles: Some(MAX_EVM_RPC_CYCLES),
+            ..Default::default()
+        };
+        assert!(rpc.validate().is_ok());
+
+        let rpc = EvmRpc {
+            cycles: Some(MAX_EVM_RPC_CYCLES + 1),
+            ..Default::default()
+        };
+        assert!(matches!(rpc.validate(), Err(EvmError::InvalidRpc(_))));
+
+        let rpc = EvmRpc::default().with_providers(1337, vec![]);
+        assert!(matches!(rpc.validate(), Err(EvmError::InvalidRpc(_))));
+    }
+
+    #[test]
+    fn test_services() {
+        let rpc = EvmRpc::default();
+
+        assert_eq!(rpc.services(1), Ok(RpcServices::EthMainnet(None)));
+        assert_eq!(rpc.services(1337), Err(EvmError::UnsupportedChain(1337)));
+
+        let provider = RpcApi {
+            url: "http://127.0.0.1:8545".to_string(),
+            headers: None,
+        };
+
+        let rpc = rpc.with_providers(1337, vec![provider.clone()]);
+
+        assert_eq!(
+            rpc.services(1337),
+            Ok(RpcServices::Custom {
+                chain_id: 1337,
+                services: vec![provider],
+            })
+        );
+        // the providers of a chain are not used for the others
+        assert_eq!(rpc.services(1), Ok(RpcServices::EthMainnet(None)));
+        assert_eq!(
+            rpc.service(1),
+            Ok(RpcService::EthMainnet(EthMainnetService::PublicNode))
+        );
+    }
+
+    #[test]
+    fn test_parse_json_rpc_response() {
+        let result =
+            parse_json_rpc_response(r#"{"jsonrpc":"2.0","id":1,"result":"0x1bc16d674ec80000"}"#);
+
+        assert_eq!(
+            parse_quantity(&result.unwrap()),
+            Ok(Nat::from(2_000_000_000_000_000_000u64))
+        );
+
+        let result = parse_json_rpc_response(
+            r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32000,"message":"execution reverted"}}"#,
+        );
+
+        assert_eq!(
+            result,
+            Err(EvmError::RpcError("execution reverted".to_string()))
+        );
+
+        // the reason encoded as `Error(string)` in the data
+        let result = parse_json_rpc_response(
+            r#"{"jsonrpc":"2.0","id":1,"error":{"code":3,"message":"execution reverted: ERC20: insufficient allowance","data":"0x08c379a00000000000000000000000000000000000000000000000000000000000000020000000000000000000000000000000000000000000000000000000000000001d45524332303a20696e73756666696369656e7420616c6c6f77616e6365000000"}}"#,
+        );
+
+        assert_eq!(
+            result,
+            Err(EvmError::ExecutionReverted(
+                "ERC20: insufficient allowance".to_string()
+            ))
+        );
+
+        assert!(parse_json_rpc_response("not json").is_err());
+        assert!(parse_json_rpc_response(r#"{"jsonrpc":"2.0","id":1}"#).is_err());
+    }
+
+    #[test]
+    fn test_receipt() {
+        let tx_hash = "0xd8b1b0b6c6b2f4f3a1a0e8d3e1e3c0f9a4f2e0c1b7c8d9e0f1a2b3c4d5e6f7a8";
+
+        let receipt = TransactionReceipt {
+            transaction_hash: tx_hash.to_string(),
+            block_number: Nat::from(18_000_000u64),
+            status: Some(Nat::from(1u64)),
+            gas_used: Nat::from(21_000u64),
+            effective_gas_price: Nat::from(1_000_000_000u64),
+        };
+
+        assert_eq!(
+            receipt.receipt(),
+            Ok(EvmReceipt {
+                tx_hash: tx_hash.to_string(),
+                status: EvmReceiptStatus::Success,
+                block_number: Some(18_000_000),
+                gas_used: Some(21_000),
+                effective_gas_price: Some(U256::from(1_000_000_000u64)),
+            })
+        );
+
+        let reverted = TransactionReceipt {
+            status: Some(Nat::from(0u64)),
+            ..receipt.clone()
+        };
+
+        assert_eq!(
+            reverted.receipt().unwrap().status,
+            EvmReceiptStatus::Reverted
+        );
+
+        let invalid = TransactionReceipt {
+            status: Some(Nat::from(2u64)),
+            ..receipt.clone()
+        };
+        assert!(invalid.receipt().is_err());
+
+        // receipts from before Byzantium have no status
+        let missing = TransactionReceipt {
+            status: None,
+            ..receipt
+        };
+        assert!(missing.receipt().is_err());
+    }
+
+    #[test]
+    fn test_call_object() {
+        let from = "0x7a9d2f53fea15e31f0a89d7f5d9e0e82b0b88ad6";
+
+        assert_eq!(
+            call_object(
+                from,
+                "1f9840a85d5af5bf1d1762f925bdaddc4201f984",
+                &U256::zero(),
+                "a9059cbb"
+            ),
+            json!({
+                "from": from,
+                "to": "0x1f9840a85d5af5bf1d1762f925bdaddc4201f984",
+                "value": "0x0",
+                "data": "0xa9059cbb",
+            })
+        );
+
+        // a deployment has no recipient
+        assert_eq!(
+            call_object(from, "", &U256::from(1u64), "0x"),
+            json!({ "from": from, "value": "0x1" })
+        );
+    }
+
+    #[test]
+    fn test_consistent() {
+        let result: MultiRpcResult<Nat> = MultiRpcResult::Consistent(Ok(Nat::from(7u64)));
+        assert_eq!(result.consistent(), Ok(Nat::from(7u64)));
+
+        let result: MultiRpcResult<Nat> =
+            MultiRpcResult::Consistent(Err(RpcError::JsonRpcError(JsonRpcError {
+                code: -32000,
+                message: "nonce too low".to_string(),
+            })));
+        assert_eq!(
+            result.consistent(),
+            Err(EvmError::RpcError(
+                "JSON-RPC error -32000: nonce too low".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_suggested_fees() {
+        let history = FeeHistory {
+            oldest_block: Nat::from(100u64),
+            base_fee_per_gas: vec![Nat::from(10u64), Nat::from(20u64)],
+            gas_used_ratio: vec![0.5],
+            reward: vec![
+                vec![Nat::from(3u64)],
+                vec![Nat::from(1u64)],
+                vec![Nat::from(2u64)],
+            ],
+        };
+
+        assert_eq!(history.suggested_fees(2), Ok((42, 2)));
+
+        let history = FeeHistory {
+            reward: vec![],
+            ..history
+        };
+
+        assert_eq!(
+            history.suggested_fees(2),
+            Ok((
+                40 + DEFAULT_PRIORITY_FEE_PER_GAS,
+                DEFAULT_PRIORITY_FEE_PER_GAS
+            ))
+        );
+    }
+}
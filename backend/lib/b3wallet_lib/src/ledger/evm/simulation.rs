@@ -0,0 +1,101 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use super::{error::EvmError, gas::EvmFeeRequest};
+use crate::{error::WalletError, ledger::types::ChainEnum, store::with_chain};
+
+/// How the transaction of an operation would end if it was mined at the latest block.
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub enum EvmSimulation {
+    /// The call succeeds, with its hex encoded output and the gas it would use.
+    Success { output: String, gas: u64 },
+    /// The call reverts, with the reason the contract gave if any.
+    Reverted(String),
+    /// The providers couldn't run the call, the outcome is unknown.
+    Unavailable(String),
+}
+
+impl EvmSimulation {
+    /// The outcome told by the error of `eth_call` or `eth_estimateGas`.
+    pub fn from_error(error: &EvmError) -> Self {
+        match error {
+            EvmError::ExecutionReverted(reason) => EvmSimulation::Reverted(reason.clone()),
+            EvmError::RpcError(message) if message.starts_with("execution reverted") => {
+                let reason = message
+                    .trim_start_matches("execution reverted")
+                    .trim_start_matches(':')
+                    .trim();
+
+                EvmSimulation::Reverted(reason.to_string())
+            }
+            error => EvmSimulation::Unavailable(error.to_string()),
+        }
+    }
+
+    pub fn is_reverted(&self) -> bool {
+        matches!(self, EvmSimulation::Reverted(_))
+    }
+}
+
+impl fmt::Display for EvmSimulation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvmSimulation::Success { gas, .. } => {
+                write!(f, "succeeds using about {} gas", gas)
+            }
+            EvmSimulation::Reverted(reason) if reason.is_empty() => {
+                write!(f, "would revert without a reason")
+            }
+            EvmSimulation::Reverted(reason) => write!(f, "would revert: {}", reason),
+            EvmSimulation::Unavailable(error) => write!(f, "could not be simulated: {}", error),
+        }
+    }
+}
+
+impl EvmFeeRequest {
+    /// Run the transaction against the latest block without sending it.
+    pub async fn simulate(&self) -> Result<EvmSimulation, WalletError> {
+        let evm = with_chain(&self.account_id, &ChainEnum::EVM(self.chain_id), |chain| {
+            chain.evm()
+        })??;
+
+        Ok(evm.simulate(&self.to, &self.value, &self.data).await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_error() {
+        assert_eq!(
+            EvmSimulation::from_error(&EvmError::ExecutionReverted(
+                "ERC20: insufficient allowance".to_string()
+            )),
+            EvmSimulation::Reverted("ERC20: insufficient allowance".to_string())
+        );
+
+        assert_eq!(
+            EvmSimulation::from_error(&EvmError::RpcError(
+                "execution reverted: Ownable: caller is not the owner".to_string()
+            )),
+            EvmSimulation::Reverted("Ownable: caller is not the owner".to_string())
+        );
+
+        let reverted =
+            EvmSimulation::from_error(&EvmError::RpcError("execution reverted".to_string()));
+
+        assert_eq!(reverted, EvmSimulation::Reverted("".to_string()));
+        assert_eq!(reverted.to_string(), "would revert without a reason");
+
+        let unavailable = EvmSimulation::from_error(&EvmError::UnsupportedChain(1337));
+
+        assert!(!unavailable.is_reverted());
+        assert_eq!(
+            unavailable.to_string(),
+            "could not be simulated: No RPC providers for chain 1337"
+        );
+    }
+}
@@ -0,0 +1,302 @@
+use super::berlin::EvmTransaction2930;
+use super::error::EvmError;
+use super::evm::{get_evm_transaction, EvmSignTrait};
+use super::legacy::EvmTransactionLegacy;
+use super::london::EvmTransaction1559;
+use super::u256::U256;
+use super::utils::vec_u8_to_string;
+
+use proptest::proptest;
+use proptest::{
+    array::{uniform20, uniform32},
+    collection::vec as pvec,
+    prelude::{any, Just, Strategy},
+};
+use proptest::{prop_assert, prop_assert_eq, prop_oneof};
+
+fn arb_u256() -> impl Strategy<Value = U256> {
+    uniform32(any::<u8>()).prop_map(|bytes| U256::from_be_bytes(&bytes).unwrap())
+}
+
+fn arb_address() -> impl Strategy<Value = String> {
+    prop_oneof![
+        Just("".to_string()),
+        uniform20(any::<u8>()).prop_map(|bytes| vec_u8_to_string(&bytes.to_vec())),
+    ]
+}
+
+fn arb_hex(max_length: usize) -> impl Strategy<Value = String> {
+    pvec(any::<u8>(), 0..max_length).prop_map(|bytes| vec_u8_to_string(&bytes))
+}
+
+fn arb_access_list() -> impl Strategy<Value = Vec<(String, Vec<String>)>> {
+    pvec(
+        (
+            uniform20(any::<u8>()).prop_map(|bytes| vec_u8_to_string(&bytes.to_vec())),
+            pvec(
+                uniform32(any::<u8>()).prop_map(|bytes| vec_u8_to_string(&bytes.to_vec())),
+                0..3,
+            ),
+        ),
+        0..3,
+    )
+}
+
+fn arb_tx_1559() -> impl Strategy<Value = EvmTransaction1559> {
+    (
+        (
+            any::<u64>(),
+            any::<u64>(),
+            arb_u256(),
+            arb_u256(),
+            any::<u64>(),
+        ),
+        (arb_address(), arb_u256(), arb_hex(100), arb_access_list()),
+        (arb_hex(2), arb_hex(33), arb_hex(33)),
+    )
+        .prop_map(
+            |(
+                (chain_id, nonce, max_priority_fee_per_gas, max_fee_per_gas, gas_limit),
+                (to, value, data, access_list),
+                (v, r, s),
+            )| EvmTransaction1559 {
+                chain_id,
+                nonce,
+                max_priority_fee_per_gas,
+                max_fee_per_gas,
+                gas_limit,
+                to,
+                value,
+                data,
+                access_list,
+                v,
+                r,
+                s,
+            },
+        )
+}
+
+fn arb_tx_2930() -> impl Strategy<Value = EvmTransaction2930> {
+    (
+        (any::<u64>(), any::<u64>(), arb_u256(), any::<u64>()),
+        (arb_address(), arb_u256(), arb_hex(100), arb_access_list()),
+        (arb_hex(2), arb_hex(33), arb_hex(33)),
+    )
+        .prop_map(
+            |(
+                (chain_id, nonce, gas_price, gas_limit),
+                (to, value, data, access_list),
+                (v, r, s),
+            )| EvmTransaction2930 {
+                chain_id,
+                nonce,
+                gas_price,
+                gas_limit,
+                to,
+                value,
+                data,
+                access_list,
+                v,
+                r,
+                s,
+            },
+        )
+}
+
+fn arb_tx_legacy() -> impl Strategy<Value = EvmTransactionLegacy> {
+    (
+        (1..u32::MAX as u64, any::<u64>(), arb_u256(), any::<u64>()),
+        (arb_address(), arb_u256(), arb_hex(100)),
+        0..2u64,
+    )
+        .prop_map(
+            |((chain_id, nonce, gas_price, gas_limit), (to, value, data), recovery_id)| {
+                let v = chain_id * 2 + 35 + recovery_id;
+
+                EvmTransactionLegacy {
+                    chain_id,
+                    nonce,
+                    gas_price,
+                    gas_limit,
+                    to,
+                    value,
+                    data,
+                    v: vec_u8_to_string(&U256::from(v).to_be_bytes()),
+                    r: "0a".to_string(),
+                    s: "0b".to_string(),
+                }
+            },
+        )
+}
+
+#[test]
+fn test_decode_empty() {
+    assert_eq!(
+        get_evm_transaction(&vec![], 1),
+        Err(EvmError::InvalidLength("transaction".to_string(), 0))
+    );
+    assert_eq!(
+        EvmTransaction1559::try_from(vec![0x02]),
+        Err(EvmError::InvalidRlp("expected a list".to_string()))
+    );
+    assert_eq!(
+        get_evm_transaction(&vec![0x03, 0xc0], 1),
+        Err(EvmError::InvalidTransactionType)
+    );
+}
+
+#[test]
+fn test_decode_wrong_field_count() {
+    // an empty list after the type
+    assert_eq!(
+        EvmTransaction1559::try_from(vec![0x02, 0xc0]),
+        Err(EvmError::InvalidFieldCount(12, 0))
+    );
+    assert_eq!(
+        EvmTransaction2930::try_from(vec![0x01, 0xc1, 0x80]),
+        Err(EvmError::InvalidFieldCount(11, 1))
+    );
+    assert_eq!(
+        EvmTransactionLegacy::try_from((vec![0xc2, 0x80, 0x80], 1)),
+        Err(EvmError::InvalidFieldCount(9, 2))
+    );
+}
+
+#[test]
+fn test_decode_wrong_chain_id() {
+    let tx = EvmTransaction1559 {
+        chain_id: 1,
+        nonce: 0,
+        max_priority_fee_per_gas: U256::zero(),
+        max_fee_per_gas: U256::zero(),
+        gas_limit: 21_000,
+        to: "7a9d2f53fea15e31f0a89d7f5d9e0e82b0b88ad6".to_string(),
+        value: U256::zero(),
+        data: "".to_string(),
+        access_list: vec![],
+        v: "".to_string(),
+        r: "".to_string(),
+        s: "".to_string(),
+    };
+
+    assert!(get_evm_transaction(&tx.serialized(), 1).is_ok());
+    assert_eq!(
+        get_evm_transaction(&tx.serialized(), 5),
+        Err(EvmError::InvalidChainId(5, 1))
+    );
+
+    let tx = EvmTransactionLegacy {
+        chain_id: 1,
+        nonce: 0,
+        gas_price: U256::zero(),
+        gas_limit: 21_000,
+        to: "7a9d2f53fea15e31f0a89d7f5d9e0e82b0b88ad6".to_string(),
+        value: U256::zero(),
+        data: "".to_string(),
+        // unsigned as of EIP-155, v is the chain id
+        v: "01".to_string(),
+        r: "".to_string(),
+        s: "".to_string(),
+    };
+
+    assert!(get_evm_transaction(&tx.serialized(), 1).is_ok());
+    assert_eq!(
+        get_evm_transaction(&tx.serialized(), 5),
+        Err(EvmError::InvalidChainId(5, 1))
+    );
+}
+
+#[test]
+fn test_decode_wrong_field_length() {
+    let tx = EvmTransaction1559 {
+        chain_id: 1,
+        nonce: 0,
+        max_priority_fee_per_gas: U256::zero(),
+        max_fee_per_gas: U256::zero(),
+        gas_limit: 21_000,
+        // one byte short of an address
+        to: "7a9d2f53fea15e31f0a89d7f5d9e0e82b0b88a".to_string(),
+        value: U256::zero(),
+        data: "".to_string(),
+        access_list: vec![],
+        v: "".to_string(),
+        r: "".to_string(),
+        s: "".to_string(),
+    };
+
+    assert_eq!(
+        EvmTransaction1559::try_from(tx.serialized()),
+        Err(EvmError::InvalidLength("to".to_string(), 19))
+    );
+}
+
+proptest! {
+    #[test]
+    fn decode_random_bytes(bytes in pvec(any::<u8>(), 0..300), chain_id in any::<u64>()) {
+        // only checks that nothing panics
+        let _ = get_evm_transaction(&bytes, chain_id);
+    }
+
+    #[test]
+    fn decode_random_typed_payload(
+        tx_type in prop_oneof![Just(0x01u8), Just(0x02u8)],
+        payload in pvec(any::<u8>(), 0..300),
+    ) {
+        let bytes = [vec![tx_type], payload].concat();
+
+        let _ = EvmTransaction1559::try_from(bytes.clone());
+        let _ = EvmTransaction2930::try_from(bytes);
+    }
+
+    #[test]
+    fn decode_random_list(items in pvec(pvec(any::<u8>(), 0..40), 0..14)) {
+        // well formed RLP of any number of strings
+        let mut stream = rlp::RlpStream::new_list(items.len());
+        for item in items.iter() {
+            stream.append(item);
+        }
+        let payload = stream.out().to_vec();
+
+        let _ = EvmTransaction1559::try_from([vec![0x02], payload.clone()].concat());
+        let _ = EvmTransaction2930::try_from([vec![0x01], payload.clone()].concat());
+        let _ = EvmTransactionLegacy::try_from((payload, 1));
+    }
+
+    #[test]
+    fn round_trip_1559(tx in arb_tx_1559()) {
+        let serialized = tx.serialized();
+
+        prop_assert_eq!(EvmTransaction1559::try_from(serialized.clone()), Ok(tx.clone()));
+        prop_assert_eq!(get_evm_transaction(&serialized, tx.chain_id), Ok(tx.into()));
+
+        // any cut of the transaction is refused
+        for length in 0..serialized.len() {
+            prop_assert!(EvmTransaction1559::try_from(serialized[..length].to_vec()).is_err());
+        }
+    }
+
+    #[test]
+    fn round_trip_2930(tx in arb_tx_2930()) {
+        let serialized = tx.serialized();
+
+        prop_assert_eq!(EvmTransaction2930::try_from(serialized.clone()), Ok(tx.clone()));
+
+        for length in 0..serialized.len() {
+            prop_assert!(EvmTransaction2930::try_from(serialized[..length].to_vec()).is_err());
+        }
+    }
+
+    #[test]
+    fn round_trip_legacy(tx in arb_tx_legacy()) {
+        let serialized = tx.serialized();
+
+        prop_assert_eq!(
+            EvmTransactionLegacy::try_from((serialized.clone(), tx.chain_id)),
+            Ok(tx.clone())
+        );
+        prop_assert_eq!(
+            EvmTransactionLegacy::try_from((serialized, tx.chain_id + 1)),
+            Err(EvmError::InvalidChainId(tx.chain_id + 1, tx.chain_id))
+        );
+    }
+}
@@ -1,7 +1,147 @@
 use b3_utils::vec_to_hex_string_with_0x;
+use candid::CandidType;
 use libsecp256k1::PublicKey;
+use serde::{Deserialize, Serialize};
 use tiny_keccak::{Hasher, Keccak};
 
+use super::{
+    abi::{AbiFunction, AbiValue},
+    error::EvmError,
+    u256::U256,
+    utils::validate_address,
+};
+use crate::ledger::token::TokenDescriptor;
+use std::fmt;
+
+/// An ERC-20 token whose balance is tracked on an EVM chain.
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct Erc20Token {
+    pub address: String,
+    pub symbol: String,
+    pub decimals: u8,
+}
+
+impl Erc20Token {
+    pub fn validate(&self) -> Result<(), EvmError> {
+        validate_address(&self.address)
+    }
+
+    pub fn is(&self, address: &str) -> bool {
+        self.address.eq_ignore_ascii_case(address)
+    }
+
+    pub fn descriptor(&self) -> TokenDescriptor {
+        TokenDescriptor::new(&self.symbol, self.decimals, None)
+    }
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub enum NftStandard {
+    Erc721,
+    Erc1155,
+}
+
+impl fmt::Display for NftStandard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NftStandard::Erc721 => write!(f, "ERC-721"),
+            NftStandard::Erc1155 => write!(f, "ERC-1155"),
+        }
+    }
+}
+
+/// An NFT collection whose tokens can be transferred from an EVM chain.
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct NftCollection {
+    pub address: String,
+    pub name: String,
+    pub standard: NftStandard,
+}
+
+impl NftCollection {
+    pub fn validate(&self) -> Result<(), EvmError> {
+        validate_address(&self.address)
+    }
+
+    pub fn is(&self, address: &str) -> bool {
+        self.address.eq_ignore_ascii_case(address)
+    }
+
+    /// Fails unless the collection follows the given standard.
+    pub fn expect_standard(&self, standard: NftStandard) -> Result<(), EvmError> {
+        if self.standard != standard {
+            return Err(EvmError::WrongNftStandard(
+                self.address.clone(),
+                self.standard.to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// The `safeTransferFrom` call moving `amount` of the token, an ERC-721 token
+    /// being unique the amount is ignored for those.
+    pub fn transfer_data(
+        &self,
+        from: &str,
+        to: &str,
+        token_id: &U256,
+        amount: &U256,
+    ) -> Result<Vec<u8>, EvmError> {
+        validate_address(from)?;
+        validate_address(to)?;
+
+        let from = AbiValue::Address(from.to_string());
+        let to = AbiValue::Address(to.to_string());
+        let token_id = AbiValue::Uint(token_id.clone());
+
+        match self.standard {
+            NftStandard::Erc721 => AbiFunction::parse("safeTransferFrom(address,address,uint256)")?
+                .encode_call(&[from, to, token_id]),
+            NftStandard::Erc1155 => {
+                AbiFunction::parse("safeTransferFrom(address,address,uint256,uint256,bytes)")?
+                    .encode_call(&[
+                        from,
+                        to,
+                        token_id,
+                        AbiValue::Uint(amount.clone()),
+                        AbiValue::Bytes(vec![]),
+                    ])
+            }
+        }
+    }
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub enum EvmReceiptStatus {
+    Success,
+    Reverted,
+    /// Never mined, another transaction with the same nonce was.
+    Dropped,
+}
+
+/// The outcome of a broadcast transaction, the gas is only known once it is mined.
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct EvmReceipt {
+    pub tx_hash: String,
+    pub status: EvmReceiptStatus,
+    pub block_number: Option<u64>,
+    pub gas_used: Option<u64>,
+    pub effective_gas_price: Option<U256>,
+}
+
+impl EvmReceipt {
+    pub fn dropped(tx_hash: &str) -> Self {
+        EvmReceipt {
+            tx_hash: tx_hash.to_string(),
+            status: EvmReceiptStatus::Dropped,
+            block_number: None,
+            gas_used: None,
+            effective_gas_price: None,
+        }
+    }
+}
+
 pub trait PublicKeyTrait {
     fn to_address(&self) -> String;
     fn to_evm_key(&self) -> Vec<u8>; // Return owned Vec<u8> instead of reference
@@ -34,3 +174,61 @@ impl PublicKeyTrait for PublicKey {
         output[12..].to_vec()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FROM: &str = "0x7a9d2f53fea15e31f0a89d7f5d9e0e82b0b88ad6";
+    const TO: &str = "0x1f9840a85d5af5bf1d1762f925bdaddc4201f984";
+
+    fn collection(standard: NftStandard) -> NftCollection {
+        NftCollection {
+            address: "0xbc4ca0eda7647a8ab7c2061c2e118a18a936f13d".to_string(),
+            name: "Apes".to_string(),
+            standard,
+        }
+    }
+
+    #[test]
+    fn test_erc721_transfer_data() {
+        let data = collection(NftStandard::Erc721)
+            .transfer_data(FROM, TO, &U256::from(42u64), &U256::from(1u64))
+            .unwrap();
+
+        assert_eq!(hex::encode(&data[..4]), "42842e0e");
+        assert_eq!(data.len(), 4 + 3 * 32);
+        assert_eq!(hex::encode(&data[4 + 12..4 + 32]), &FROM[2..]);
+        assert_eq!(hex::encode(&data[4 + 32 + 12..4 + 64]), &TO[2..]);
+        assert_eq!(data[4 + 95], 42);
+    }
+
+    #[test]
+    fn test_erc1155_transfer_data() {
+        let data = collection(NftStandard::Erc1155)
+            .transfer_data(FROM, TO, &U256::from(7u64), &U256::from(3u64))
+            .unwrap();
+
+        assert_eq!(hex::encode(&data[..4]), "f242432a");
+        // five heads and the length of the empty bytes
+        assert_eq!(data.len(), 4 + 6 * 32);
+        assert_eq!(data[4 + 95], 7);
+        assert_eq!(data[4 + 127], 3);
+        // offset of the bytes, right after the heads
+        assert_eq!(data[4 + 159], 160);
+    }
+
+    #[test]
+    fn test_wrong_standard() {
+        let collection = collection(NftStandard::Erc1155);
+
+        assert!(collection.expect_standard(NftStandard::Erc1155).is_ok());
+        assert_eq!(
+            collection.expect_standard(NftStandard::Erc721),
+            Err(EvmError::WrongNftStandard(
+                collection.address.clone(),
+                "ERC-1155".to_string()
+            ))
+        );
+    }
+}
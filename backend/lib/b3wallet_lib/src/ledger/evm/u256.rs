@@ -0,0 +1,282 @@
+use candid::{
+    types::{Serializer, Type},
+    CandidType, Nat,
+};
+use num_traits::ToPrimitive;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::{fmt, str::FromStr};
+
+use super::error::EvmError;
+
+/// An unsigned 256-bit integer, the size of the EVM amounts and fees.
+/// It is a `nat` on the Candid interface and can be read from a hex string.
+#[derive(Serialize, Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Default)]
+pub struct U256(Nat);
+
+impl U256 {
+    pub const BITS: u64 = 256;
+
+    pub fn zero() -> Self {
+        U256(Nat::from(0u8))
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0 .0.bits() == 0
+    }
+
+    /// Read a big endian number of at most 32 bytes, leading zeros included.
+    pub fn from_be_bytes(bytes: &[u8]) -> Result<Self, EvmError> {
+        let nat = bytes.iter().fold(Nat::from(0u8), |acc, byte| {
+            Nat(acc.0 * 256u32 + u32::from(*byte))
+        });
+
+        U256::try_from(nat)
+    }
+
+    /// The big endian bytes without leading zeros, empty for zero, as RLP encodes integers.
+    pub fn to_be_bytes(&self) -> Vec<u8> {
+        if self.is_zero() {
+            return vec![];
+        }
+
+        self.0 .0.to_bytes_be()
+    }
+
+    /// The big endian bytes padded to 32, as the ABI encodes a `uint256`.
+    pub fn to_be_bytes32(&self) -> [u8; 32] {
+        let bytes = self.to_be_bytes();
+
+        let mut padded = [0u8; 32];
+        padded[32 - bytes.len()..].copy_from_slice(&bytes);
+
+        padded
+    }
+
+    /// The big endian bytes of `-self` on 256 bits, in two's complement,
+    /// as the ABI encodes a negative `int256`.
+    pub fn to_negative_bytes32(&self) -> [u8; 32] {
+        let mut negated = self.to_be_bytes32().map(|byte| !byte);
+
+        for byte in negated.iter_mut().rev() {
+            let (sum, carry) = byte.overflowing_add(1);
+            *byte = sum;
+
+            if !carry {
+                break;
+            }
+        }
+
+        negated
+    }
+
+    /// Read back the magnitude of a negative number written by `to_negative_bytes32`.
+    pub fn from_negative_bytes32(word: &[u8; 32]) -> U256 {
+        // 32 bytes always fit, and negating twice gives back the magnitude
+        let word = U256::from_be_bytes(word).unwrap_or_default();
+
+        U256::from_be_bytes(&word.to_negative_bytes32()).unwrap_or_default()
+    }
+
+    /// Parse a hex number, with or without `0x`.
+    pub fn from_hex(hex: &str) -> Result<Self, EvmError> {
+        let digits = hex.strip_prefix("0x").unwrap_or(hex);
+
+        let nat = digits.chars().try_fold(Nat::from(0u8), |acc, c| {
+            let digit = c
+                .to_digit(16)
+                .ok_or_else(|| EvmError::InvalidHex(hex.to_string()))?;
+
+            Ok(Nat(acc.0 * 16u32 + digit))
+        })?;
+
+        U256::try_from(nat)
+    }
+
+    /// Parse a decimal number, or a hex one when it starts with `0x`.
+    pub fn from_dec_or_hex(number: &str) -> Result<Self, EvmError> {
+        if number.starts_with("0x") {
+            return U256::from_hex(number);
+        }
+
+        let nat = Nat::from_str(number).map_err(|_| EvmError::InvalidNumber(number.to_string()))?;
+
+        U256::try_from(nat)
+    }
+
+    /// The `0x` prefixed hex of the number, as JSON-RPC quantities are written.
+    pub fn to_hex(&self) -> String {
+        format!("0x{}", self.0 .0.to_str_radix(16))
+    }
+
+    /// How many bits the number takes, 0 for zero.
+    pub fn bits(&self) -> u64 {
+        self.0 .0.bits()
+    }
+
+    pub fn to_u64(&self) -> Option<u64> {
+        self.0 .0.to_u64()
+    }
+
+    pub fn checked_add(&self, other: &U256) -> Option<U256> {
+        U256::try_from(Nat(&self.0 .0 + &other.0 .0)).ok()
+    }
+
+    pub fn checked_mul(&self, other: &U256) -> Option<U256> {
+        U256::try_from(Nat(&self.0 .0 * &other.0 .0)).ok()
+    }
+
+    pub fn as_nat(&self) -> &Nat {
+        &self.0
+    }
+}
+
+impl TryFrom<Nat> for U256 {
+    type Error = EvmError;
+
+    fn try_from(nat: Nat) -> Result<Self, Self::Error> {
+        if nat.0.bits() > U256::BITS {
+            return Err(EvmError::ValueOverflow(nat.0.to_string()));
+        }
+
+        Ok(U256(nat))
+    }
+}
+
+impl From<U256> for Nat {
+    fn from(value: U256) -> Self {
+        value.0
+    }
+}
+
+impl From<u64> for U256 {
+    fn from(value: u64) -> Self {
+        U256(Nat::from(value))
+    }
+}
+
+impl From<u128> for U256 {
+    fn from(value: u128) -> Self {
+        U256(Nat::from(value))
+    }
+}
+
+impl fmt::Display for U256 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0 .0)
+    }
+}
+
+impl CandidType for U256 {
+    fn _ty() -> Type {
+        Nat::_ty()
+    }
+
+    fn idl_serialize<S>(&self, serializer: S) -> Result<(), S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.idl_serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for U256 {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let nat = Nat::deserialize(deserializer)?;
+
+        U256::try_from(nat).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use candid::{Decode, Encode};
+
+    /// 2^64 + 1, just past what a u64 holds.
+    const ABOVE_U64: &str = "0x10000000000000001";
+
+    #[test]
+    fn test_bytes_round_trip() {
+        let value = U256::from_hex(ABOVE_U64).unwrap();
+
+        assert_eq!(value.to_be_bytes(), vec![1, 0, 0, 0, 0, 0, 0, 0, 1]);
+        assert_eq!(U256::from_be_bytes(&value.to_be_bytes()).unwrap(), value);
+        assert_eq!(value.to_hex(), ABOVE_U64);
+        assert_eq!(value.to_u64(), None);
+        assert_eq!(value.to_string(), "18446744073709551617");
+
+        assert_eq!(U256::zero().to_be_bytes(), Vec::<u8>::new());
+        assert_eq!(U256::from_be_bytes(&[]).unwrap(), U256::zero());
+        assert_eq!(U256::from_be_bytes(&[0, 0, 5]).unwrap(), U256::from(5u64));
+    }
+
+    #[test]
+    fn test_from_dec_or_hex() {
+        assert_eq!(
+            U256::from_dec_or_hex("18446744073709551617").unwrap(),
+            U256::from_hex(ABOVE_U64).unwrap()
+        );
+        assert_eq!(
+            U256::from_dec_or_hex("0x3039").unwrap(),
+            U256::from(12345u64)
+        );
+        assert_eq!(
+            U256::from_dec_or_hex("12a"),
+            Err(EvmError::InvalidNumber("12a".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_max() {
+        let max = U256::from_be_bytes(&[0xff; 32]).unwrap();
+
+        assert_eq!(max.to_be_bytes32(), [0xff; 32]);
+        assert_eq!(max.checked_add(&U256::from(1u64)), None);
+        assert_eq!(max.checked_add(&U256::zero()), Some(max.clone()));
+
+        assert!(U256::from_be_bytes(&[1; 33]).is_err());
+        assert!(U256::from_hex(&format!("0x1{}", "0".repeat(64))).is_err());
+    }
+
+    #[test]
+    fn test_negative_bytes32() {
+        assert_eq!(U256::from(1u64).to_negative_bytes32(), [0xff; 32]);
+        assert_eq!(U256::zero().to_negative_bytes32(), [0; 32]);
+
+        let mut expected = [0xff; 32];
+        expected[31] = 0x80;
+        assert_eq!(U256::from(128u64).to_negative_bytes32(), expected);
+
+        assert_eq!(U256::from_negative_bytes32(&expected), U256::from(128u64));
+        assert_eq!(U256::from_negative_bytes32(&[0xff; 32]), U256::from(1u64));
+    }
+
+    #[test]
+    fn test_be_bytes32() {
+        let mut expected = [0u8; 32];
+        expected[31] = 0x39;
+        expected[30] = 0x30;
+
+        assert_eq!(U256::from(12345u64).to_be_bytes32(), expected);
+    }
+
+    #[test]
+    fn test_candid_round_trip() {
+        let value = U256::from(u128::MAX)
+            .checked_mul(&U256::from(1_000u64))
+            .unwrap();
+
+        let bytes = Encode!(&value).unwrap();
+
+        // the same as a plain nat
+        assert_eq!(Decode!(&bytes, Nat).unwrap(), value.as_nat().clone());
+        assert_eq!(Decode!(&bytes, U256).unwrap(), value);
+
+        let too_large = Nat(Nat::from(u128::MAX).0 * Nat::from(u128::MAX).0 * 2u32);
+
+        assert!(Decode!(&Encode!(&too_large).unwrap(), U256).is_err());
+    }
+}
@@ -0,0 +1,253 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::{
+    abi::{encode, AbiFunction, AbiType, AbiValue},
+    error::EvmError,
+    rpc::RpcApi,
+    u256::U256,
+    utils::{keccak256, validate_address},
+};
+use crate::ledger::types::ChainId;
+
+/// The EntryPoint v0.6 contract, at the same address on every network.
+pub const ENTRY_POINT_V06: &str = "0x5FF137D4b0FDCD49DcA30c7CF57E578a026d2789";
+
+/// The `SimpleAccountFactory` deployed next to the v0.6 EntryPoint.
+pub const SIMPLE_ACCOUNT_FACTORY_V06: &str = "0x9406Cc6185a346906296840746125a0E44976454";
+
+/// The ERC-4337 contracts the accounts own smart accounts through on a network.
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct Erc4337Config {
+    pub chain_id: ChainId,
+    pub entry_point: String,
+    /// Deploys the smart accounts with `createAccount(owner, salt)`.
+    pub factory: String,
+    /// The bundler the signed operations are sent to, a local stand-in works too.
+    /// Without one they are only signed, for the caller to submit.
+    pub bundler: Option<RpcApi>,
+}
+
+impl Erc4337Config {
+    /// The v0.6 EntryPoint and `SimpleAccountFactory`, without a bundler.
+    pub fn new(chain_id: ChainId) -> Self {
+        Erc4337Config {
+            chain_id,
+            entry_point: ENTRY_POINT_V06.to_string(),
+            factory: SIMPLE_ACCOUNT_FACTORY_V06.to_string(),
+            bundler: None,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), EvmError> {
+        if self.chain_id == 0 {
+            return Err(EvmError::InvalidNetwork("chain id 0".to_string()));
+        }
+
+        validate_address(&self.entry_point)?;
+        validate_address(&self.factory)?;
+
+        Ok(())
+    }
+
+    /// The code the EntryPoint runs to deploy the smart account of the owner,
+    /// the factory address followed by the `createAccount` call.
+    pub fn init_code(&self, owner: &str, salt: &U256) -> Result<Vec<u8>, EvmError> {
+        validate_address(&self.factory)?;
+
+        let factory = hex::decode(&self.factory[2..])
+            .map_err(|_| EvmError::InvalidAddress(self.factory.clone()))?;
+
+        let call = AbiFunction::parse("createAccount(address,uint256)")?.encode_call(&[
+            AbiValue::Address(owner.to_string()),
+            AbiValue::Uint(salt.clone()),
+        ])?;
+
+        Ok([factory, call].concat())
+    }
+}
+
+/// An ERC-4337 `UserOperation` of the v0.6 EntryPoint.
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct UserOperation {
+    pub sender: String,
+    pub nonce: U256,
+    /// Empty once the smart account is deployed.
+    pub init_code: Vec<u8>,
+    pub call_data: Vec<u8>,
+    pub call_gas_limit: U256,
+    pub verification_gas_limit: U256,
+    pub pre_verification_gas: U256,
+    pub max_fee_per_gas: U256,
+    pub max_priority_fee_per_gas: U256,
+    pub paymaster_and_data: Vec<u8>,
+    pub signature: Vec<u8>,
+}
+
+impl UserOperation {
+    /// The hash the owner signs, of every field but the signature, the
+    /// EntryPoint and the chain, as `EntryPoint.getUserOpHash` computes it.
+    pub fn hash(&self, entry_point: &str, chain_id: ChainId) -> Result<[u8; 32], EvmError> {
+        let bytes32 = |bytes: &[u8]| AbiValue::FixedBytes(keccak256(bytes).to_vec());
+
+        let packed = encode(
+            &[
+                AbiType::Address,
+                AbiType::Uint(256),
+                AbiType::FixedBytes(32),
+                AbiType::FixedBytes(32),
+                AbiType::Uint(256),
+                AbiType::Uint(256),
+                AbiType::Uint(256),
+                AbiType::Uint(256),
+                AbiType::Uint(256),
+                AbiType::FixedBytes(32),
+            ],
+            &[
+                AbiValue::Address(self.sender.clone()),
+                AbiValue::Uint(self.nonce.clone()),
+                bytes32(&self.init_code),
+                bytes32(&self.call_data),
+                AbiValue::Uint(self.call_gas_limit.clone()),
+                AbiValue::Uint(self.verification_gas_limit.clone()),
+                AbiValue::Uint(self.pre_verification_gas.clone()),
+                AbiValue::Uint(self.max_fee_per_gas.clone()),
+                AbiValue::Uint(self.max_priority_fee_per_gas.clone()),
+                bytes32(&self.paymaster_and_data),
+            ],
+        )?;
+
+        let encoded = encode(
+            &[
+                AbiType::FixedBytes(32),
+                AbiType::Address,
+                AbiType::Uint(256),
+            ],
+            &[
+                bytes32(&packed),
+                AbiValue::Address(entry_point.to_string()),
+                AbiValue::Uint(U256::from(chain_id)),
+            ],
+        )?;
+
+        Ok(keccak256(&encoded))
+    }
+
+    /// The operation as the bundler JSON-RPC methods take it.
+    pub fn to_json(&self) -> Value {
+        let bytes = |bytes: &[u8]| format!("0x{}", hex::encode(bytes));
+
+        json!({
+            "sender": self.sender,
+            "nonce": self.nonce.to_hex(),
+            "initCode": bytes(&self.init_code),
+            "callData": bytes(&self.call_data),
+            "callGasLimit": self.call_gas_limit.to_hex(),
+            "verificationGasLimit": self.verification_gas_limit.to_hex(),
+            "preVerificationGas": self.pre_verification_gas.to_hex(),
+            "maxFeePerGas": self.max_fee_per_gas.to_hex(),
+            "maxPriorityFeePerGas": self.max_priority_fee_per_gas.to_hex(),
+            "paymasterAndData": bytes(&self.paymaster_and_data),
+            "signature": bytes(&self.signature),
+        })
+    }
+}
+
+/// A signed operation, with its hash and the bundler it was sent to if any.
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct SignedUserOperation {
+    pub user_operation: UserOperation,
+    pub hash: String,
+    pub bundler: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OWNER: &str = "0x7a9d2f53fea15e31f0a89d7f5d9e0e82b0b88ad6";
+
+    fn user_operation() -> UserOperation {
+        UserOperation {
+            sender: "0x0000000000000000000000000000000000000001".to_string(),
+            nonce: U256::zero(),
+            init_code: vec![],
+            call_data: vec![0xb6, 0x1d, 0x27, 0xf6],
+            call_gas_limit: U256::from(100_000u64),
+            verification_gas_limit: U256::from(150_000u64),
+            pre_verification_gas: U256::from(50_000u64),
+            max_fee_per_gas: U256::from(30_000_000_000u64),
+            max_priority_fee_per_gas: U256::from(1_500_000_000u64),
+            paymaster_and_data: vec![],
+            signature: vec![],
+        }
+    }
+
+    #[test]
+    fn test_hash() {
+        let user_operation = user_operation();
+
+        let hash = user_operation.hash(ENTRY_POINT_V06, 1).unwrap();
+
+        // the signature is not part of what is signed
+        let signed = UserOperation {
+            signature: vec![1; 65],
+            ..user_operation.clone()
+        };
+        assert_eq!(signed.hash(ENTRY_POINT_V06, 1).unwrap(), hash);
+
+        // but the chain, the EntryPoint and every other field are
+        assert_ne!(user_operation.hash(ENTRY_POINT_V06, 10).unwrap(), hash);
+        assert_ne!(
+            user_operation.hash(SIMPLE_ACCOUNT_FACTORY_V06, 1).unwrap(),
+            hash
+        );
+
+        let other = UserOperation {
+            nonce: U256::from(1u64),
+            ..user_operation.clone()
+        };
+        assert_ne!(other.hash(ENTRY_POINT_V06, 1).unwrap(), hash);
+
+        let invalid = UserOperation {
+            sender: "0x01".to_string(),
+            ..user_operation
+        };
+        assert!(invalid.hash(ENTRY_POINT_V06, 1).is_err());
+    }
+
+    #[test]
+    fn test_init_code() {
+        let config = Erc4337Config::new(1);
+
+        assert!(config.validate().is_ok());
+
+        let init_code = config.init_code(OWNER, &U256::from(7u64)).unwrap();
+
+        // the factory address, the selector and two words
+        assert_eq!(init_code.len(), 20 + 4 + 64);
+        assert_eq!(
+            format!("0x{}", hex::encode(&init_code[..20])),
+            SIMPLE_ACCOUNT_FACTORY_V06.to_lowercase()
+        );
+        assert_eq!(init_code[20..24], [0x5f, 0xbf, 0xb9, 0xcf]);
+        assert_eq!(init_code[init_code.len() - 1], 7);
+
+        let invalid = Erc4337Config {
+            factory: "0x".to_string(),
+            ..config
+        };
+        assert!(invalid.validate().is_err());
+    }
+
+    #[test]
+    fn test_to_json() {
+        let json = user_operation().to_json();
+
+        assert_eq!(json["nonce"], "0x0");
+        assert_eq!(json["callData"], "0xb61d27f6");
+        assert_eq!(json["callGasLimit"], "0x186a0");
+        assert_eq!(json["initCode"], "0x");
+    }
+}
@@ -1,8 +1,10 @@
 use b3_utils::vec_to_hex_string_with_0x;
+use candid::Nat;
 use libsecp256k1::PublicKey;
+use rlp::UntrustedRlp;
 use tiny_keccak::{Hasher, Keccak};
 
-use super::{error::EvmError, types::PublicKeyTrait};
+use super::{error::EvmError, types::PublicKeyTrait, u256::U256};
 
 pub fn get_method_id(method_sig: &str) -> String {
     // Keccak-256 hashing using tiny-keccak
@@ -17,7 +19,24 @@ pub fn get_method_id(method_sig: &str) -> String {
     hex_string
 }
 
-pub fn get_transfer_data(address: &str, amount: u64) -> Result<String, EvmError> {
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut keccak = Keccak::v256();
+    keccak.update(data);
+    let mut output = [0u8; 32];
+    keccak.finalize(&mut output);
+
+    output
+}
+
+/// The EIP-191 hash of a message signed with `personal_sign`,
+/// prefixed so that it can never be a valid transaction.
+pub fn hash_personal_message(message: &[u8]) -> [u8; 32] {
+    let prefix = format!("\x19Ethereum Signed Message:\n{}", message.len());
+
+    keccak256(&[prefix.as_bytes(), message].concat())
+}
+
+pub fn get_transfer_data(address: &str, amount: &U256) -> Result<String, EvmError> {
     if address.len() != 42 {
         return Err(EvmError::InvalidAddress(address.to_string()));
     }
@@ -27,12 +46,47 @@ pub fn get_transfer_data(address: &str, amount: u64) -> Result<String, EvmError>
 
     let address_64 = format!("{:0>64}", &address[2..]);
 
-    let amount_hex = format!("{:02x}", amount);
-    let amount_64 = format!("{:0>64}", amount_hex);
+    let amount_64 = hex::encode(amount.to_be_bytes32());
 
     Ok(method_id + &address_64 + &amount_64)
 }
 
+pub fn get_balance_of_data(address: &str) -> Result<String, EvmError> {
+    validate_address(address)?;
+
+    let method_id = get_method_id("balanceOf(address)");
+
+    let address_64 = format!("{:0>64}", &address[2..]);
+
+    Ok(method_id + &address_64)
+}
+
+/// Check that the address is a `0x` prefixed 20 bytes hex string.
+pub fn validate_address(address: &str) -> Result<(), EvmError> {
+    let is_valid = address.len() == 42
+        && address.starts_with("0x")
+        && address[2..].chars().all(|c| c.is_ascii_hexdigit());
+
+    if !is_valid {
+        return Err(EvmError::InvalidAddress(address.to_string()));
+    }
+
+    Ok(())
+}
+
+/// Parse a hex number of any size, with or without `0x`, e.g. a 256-bit balance.
+pub fn hex_to_nat(hex: &str) -> Result<Nat, EvmError> {
+    let digits = hex.strip_prefix("0x").unwrap_or(hex);
+
+    digits.chars().try_fold(Nat::from(0u8), |acc, c| {
+        let digit = c
+            .to_digit(16)
+            .ok_or_else(|| EvmError::InvalidHex(hex.to_string()))?;
+
+        Ok(Nat(acc.0 * 16u32 + digit))
+    })
+}
+
 pub fn create_address_from(public_key: &PublicKey, nonce: u64) -> String {
     let sender = public_key.to_evm_key();
 
@@ -90,10 +144,76 @@ pub fn vec_u8_to_string(vec: &Vec<u8>) -> String {
         .to_string()
 }
 
-pub fn vec_u8_to_u64(vec: &Vec<u8>) -> u64 {
+pub fn vec_u8_to_u64(vec: &Vec<u8>) -> Result<u64, EvmError> {
+    if vec.len() > 8 {
+        return Err(EvmError::InvalidLength("u64".to_string(), vec.len()));
+    }
+
     let mut _vec = [0; 8];
     _vec[8 - vec.len()..].copy_from_slice(&vec);
-    u64::from_be_bytes(_vec).try_into().unwrap()
+
+    Ok(u64::from_be_bytes(_vec))
+}
+
+/// The items of an RLP list, refusing a list of any other size than `expected`.
+pub fn rlp_items(data: &[u8], expected: usize) -> Result<Vec<UntrustedRlp>, EvmError> {
+    let rlp = UntrustedRlp::new(data);
+
+    if !rlp.is_list() {
+        return Err(EvmError::InvalidRlp("expected a list".to_string()));
+    }
+
+    // the iteration stops at the first malformed item, so it shows in the count
+    let items = rlp.iter().collect::<Vec<UntrustedRlp>>();
+
+    if items.len() != expected {
+        return Err(EvmError::InvalidFieldCount(expected, items.len()));
+    }
+
+    Ok(items)
+}
+
+pub fn rlp_bytes(item: &UntrustedRlp) -> Result<Vec<u8>, EvmError> {
+    item.as_val::<Vec<u8>>()
+        .map_err(|err| EvmError::InvalidRlp(format!("{:?}", err)))
+}
+
+/// The bytes of the `field` as hex, refusing more than `max_length` bytes.
+pub fn rlp_hex(item: &UntrustedRlp, field: &str, max_length: usize) -> Result<String, EvmError> {
+    let bytes = rlp_bytes(item)?;
+
+    if bytes.len() > max_length {
+        return Err(EvmError::InvalidLength(field.to_string(), bytes.len()));
+    }
+
+    Ok(vec_u8_to_string(&bytes))
+}
+
+pub fn rlp_u64(item: &UntrustedRlp, field: &str) -> Result<u64, EvmError> {
+    let bytes = rlp_bytes(item)?;
+
+    vec_u8_to_u64(&bytes).map_err(|_| EvmError::InvalidLength(field.to_string(), bytes.len()))
+}
+
+pub fn rlp_u256(item: &UntrustedRlp, field: &str) -> Result<U256, EvmError> {
+    let bytes = rlp_bytes(item)?;
+
+    if bytes.len() > 32 {
+        return Err(EvmError::InvalidLength(field.to_string(), bytes.len()));
+    }
+
+    U256::from_be_bytes(&bytes)
+}
+
+/// The recipient of a transaction, empty when it deploys a contract.
+pub fn rlp_address(item: &UntrustedRlp) -> Result<String, EvmError> {
+    let bytes = rlp_bytes(item)?;
+
+    if !bytes.is_empty() && bytes.len() != 20 {
+        return Err(EvmError::InvalidLength("to".to_string(), bytes.len()));
+    }
+
+    Ok(vec_u8_to_string(&bytes))
 }
 
 #[cfg(test)]
@@ -113,18 +233,76 @@ mod tests {
         assert_eq!(result, expected_result);
     }
 
+    #[test]
+    fn test_hash_personal_message() {
+        let hash = hash_personal_message(b"Hello World");
+
+        assert_eq!(
+            hex::encode(hash),
+            "a1de988600a42c4b4ab089b619297c17d53cffae5d5120d82d8a92d0bb3b78f2"
+        );
+    }
+
     #[test]
     fn test_get_transfer_data() {
         let address = "0x7a9d2f53fea15e31f0a89d7f5d9e0e82b0b88ad6";
-        let amount = 12345;
+        let amount = U256::from(12345u64);
 
         let expected_result = "a9059cbb0000000000000000000000007a9d2f53fea15e31f0a89d7f5d9e0e82b0b88ad60000000000000000000000000000000000000000000000000000000000003039";
 
-        let result = get_transfer_data(address, amount).unwrap();
+        let result = get_transfer_data(address, &amount).unwrap();
+
+        assert_eq!(result, expected_result);
+
+        // 1 million tokens of 18 decimals, past 2^64
+        let amount = U256::from(1_000_000_000_000_000_000_000_000u128);
+
+        let expected_result = "a9059cbb0000000000000000000000007a9d2f53fea15e31f0a89d7f5d9e0e82b0b88ad600000000000000000000000000000000000000000000d3c21bcecceda1000000";
+
+        let result = get_transfer_data(address, &amount).unwrap();
 
         assert_eq!(result, expected_result);
     }
 
+    #[test]
+    fn test_get_balance_of_data() {
+        let address = "0x7a9d2f53fea15e31f0a89d7f5d9e0e82b0b88ad6";
+
+        let expected_result =
+            "70a082310000000000000000000000007a9d2f53fea15e31f0a89d7f5d9e0e82b0b88ad6";
+
+        assert_eq!(get_balance_of_data(address).unwrap(), expected_result);
+
+        assert_eq!(
+            get_balance_of_data("0x7a9d"),
+            Err(EvmError::InvalidAddress("0x7a9d".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_validate_address() {
+        assert!(validate_address("0x7a9d2f53fea15e31f0a89d7f5d9e0e82b0b88ad6").is_ok());
+        assert!(validate_address("7a9d2f53fea15e31f0a89d7f5d9e0e82b0b88ad600").is_err());
+        assert!(validate_address("0x7a9d2f53fea15e31f0a89d7f5d9e0e82b0b88adz").is_err());
+    }
+
+    #[test]
+    fn test_hex_to_nat() {
+        assert_eq!(hex_to_nat("0x").unwrap(), Nat::from(0u8));
+        assert_eq!(hex_to_nat("0x3039").unwrap(), Nat::from(12345u64));
+
+        // 2^64 doesn't fit in a u64
+        assert_eq!(
+            hex_to_nat("0x10000000000000000").unwrap(),
+            Nat::from(u64::MAX) + Nat::from(1u8)
+        );
+
+        assert_eq!(
+            hex_to_nat("0xzz"),
+            Err(EvmError::InvalidHex("0xzz".to_string()))
+        );
+    }
+
     #[test]
     fn test_create_address_from() {
         let pub_key =
@@ -1,62 +1,126 @@
 use async_trait::async_trait;
 use b3_utils::ledger::{
-    currency::TokenAmount, AccountIdentifier, ICPTransferResult, NotifyTopUpResult,
+    currency::{ICPToken, TokenAmount},
+    ICPTransferArgs, ICPTransferResult, ICPTransferTimestamp, NotifyTopUpResult, TransferMemo,
 };
 use candid::Principal;
 use std::str::FromStr;
 
-#[cfg(test)]
-use b3_utils::mocks::id_mock as ic_cdk_id;
-#[cfg(not(test))]
-use ic_cdk::api::id as ic_cdk_id;
-
-use super::{error::IcpError, icp::IcpChain};
+use super::{
+    error::IcpError,
+    icp::{block_index_to_u64, IcpChain},
+    types::IcpRecipient,
+};
 use crate::ledger::{
     chain::ChainTrait,
     error::LedgerError,
-    types::{Balance, IcpPending, PendingEnum, SendResult},
+    icrc::types::ICRC1TransferArgs,
+    options::{SendOptions, MAX_MEMO_LENGTH},
+    token::TokenDescriptor,
+    types::{IcpPending, PendingEnum, SendResult},
 };
 
 #[async_trait]
 impl ChainTrait for IcpChain {
     fn address(&self) -> String {
-        let canister_id = ic_cdk_id();
-
-        let account = AccountIdentifier::new(canister_id, Some(self.subaccount.clone()));
-
-        account.to_string()
+        self.account_identifier().to_string()
     }
 
-    async fn balance(&self) -> Result<Balance, LedgerError> {
-        let canister_id = ic_cdk_id();
-
-        let account = AccountIdentifier::new(canister_id, Some(self.subaccount.clone()));
+    fn token(&self) -> TokenDescriptor {
+        TokenDescriptor::new("ICP", 8, Some(self.fee.e8s().into()))
+    }
 
+    async fn balance(&self) -> Result<TokenAmount, LedgerError> {
         let res = self
-            .account_balance(account)
+            .account_balance(self.account_identifier())
             .await
             .map_err(|e| LedgerError::CallError(e.to_string()))?;
 
-        Ok(res.e8s().into())
+        Ok(self.token().amount(res.e8s().into()))
     }
 
-    async fn send(&self, to: String, amount: TokenAmount) -> Result<SendResult, LedgerError> {
-        let to =
-            AccountIdentifier::from_str(&to).map_err(|e| LedgerError::CallError(e.to_string()))?;
-
-        let amount = amount
-            .to_tokens()
-            .map_err(|e| LedgerError::InvalidAmountError(e.to_string()))?;
-
-        let result = self
-            .transfer(to, amount, None, None)
-            .await
-            .map_err(|e| LedgerError::CallError(e.to_string()))?;
-
-        match result {
-            ICPTransferResult::Ok(block_index) => Ok(SendResult::ICP(block_index)),
-            ICPTransferResult::Err(err) => {
-                return Err(LedgerError::IcpError(IcpError::ICPTransferError(err)))
+    async fn send(
+        &self,
+        to: String,
+        amount: TokenAmount,
+        options: SendOptions,
+    ) -> Result<SendResult, LedgerError> {
+        let to = IcpRecipient::from_str(&to).map_err(LedgerError::IcpError)?;
+
+        let token = self.token();
+
+        let amount = ICPToken::from_e8s(token.to_base_units_u64(&amount)?);
+
+        let fee = match options.fee {
+            Some(ref fee) => ICPToken::from_e8s(token.to_base_units_u64(fee)?),
+            None => self.fee.clone(),
+        };
+
+        let from_subaccount = options.from_subaccount(Some(self.subaccount.clone()))?;
+
+        match to {
+            IcpRecipient::AccountIdentifier(to) => {
+                let memo = options
+                    .memo_u64()?
+                    .map(TransferMemo)
+                    .unwrap_or(self.memo.clone());
+
+                let created_at_time = options
+                    .created_at_time
+                    .map(|timestamp_nanos| ICPTransferTimestamp { timestamp_nanos })
+                    .or(self.created_at_time.clone());
+
+                let args = ICPTransferArgs {
+                    memo,
+                    fee,
+                    amount,
+                    to,
+                    from_subaccount,
+                    created_at_time,
+                };
+
+                let result = self
+                    .transfer_with_args(args)
+                    .await
+                    .map_err(|e| LedgerError::CallError(e.to_string()))?;
+
+                match result {
+                    ICPTransferResult::Ok(block_index) => Ok(SendResult::ICP(block_index)),
+                    ICPTransferResult::Err(err) => {
+                        Err(LedgerError::IcpError(IcpError::ICPTransferError(err)))
+                    }
+                }
+            }
+            IcpRecipient::ICRCAccount(to) => {
+                options.check_memo(MAX_MEMO_LENGTH)?;
+
+                let memo = options
+                    .memo
+                    .unwrap_or_else(|| self.memo.0.to_be_bytes().to_vec());
+
+                let created_at_time = options.created_at_time.or(self
+                    .created_at_time
+                    .as_ref()
+                    .map(|timestamp| timestamp.timestamp_nanos));
+
+                let args = ICRC1TransferArgs {
+                    to,
+                    amount: amount.e8s().into(),
+                    fee: Some(fee.e8s().into()),
+                    memo: Some(memo),
+                    created_at_time,
+                    from_subaccount,
+                };
+
+                let block_index = self
+                    .icrc1_transfer(args)
+                    .await
+                    .map_err(LedgerError::IcpError)?
+                    .map_err(|err| LedgerError::IcpError(IcpError::ICRC1TransferError(err)))?;
+
+                let block_index = block_index_to_u64(block_index).map_err(LedgerError::IcpError)?;
+
+                Ok(SendResult::ICP(block_index))
             }
         }
     }
@@ -0,0 +1,75 @@
+use b3_utils::constants::CYCLES_MINTING_CANISTER_ID;
+use candid::CandidType;
+use ic_cdk::api::call::call;
+use serde::Deserialize;
+
+use super::error::IcpError;
+
+#[derive(CandidType, Clone, Deserialize, PartialEq, Eq, Debug)]
+pub struct IcpXdrConversionRate {
+    pub timestamp_seconds: u64,
+    pub xdr_permyriad_per_icp: u64,
+}
+
+#[derive(CandidType, Clone, Deserialize, Debug)]
+pub struct IcpXdrConversionRateResponse {
+    pub data: IcpXdrConversionRate,
+    pub hash_tree: Vec<u8>,
+    pub certificate: Vec<u8>,
+}
+
+/// The current ICP/XDR rate the cycles minting canister converts ICP with.
+pub async fn icp_xdr_conversion_rate() -> Result<IcpXdrConversionRate, IcpError> {
+    let (res,): (IcpXdrConversionRateResponse,) =
+        call(CYCLES_MINTING_CANISTER_ID, "get_icp_xdr_conversion_rate", ())
+            .await
+            .map_err(|e| IcpError::CallError(e.1))?;
+
+    Ok(res.data)
+}
+
+impl IcpXdrConversionRate {
+    /// The e8s to send to the cycles minting canister to get at least `cycles`.
+    /// One XDR is worth a trillion cycles, so one ICP mints
+    /// `xdr_permyriad_per_icp * 10^8` cycles and each e8s is worth
+    /// `xdr_permyriad_per_icp` cycles.
+    pub fn cycles_to_e8s(&self, cycles: u128) -> Result<u64, IcpError> {
+        if self.xdr_permyriad_per_icp == 0 {
+            return Err(IcpError::CallError("Conversion rate is zero".to_string()));
+        }
+
+        let rate = self.xdr_permyriad_per_icp as u128;
+
+        u64::try_from(cycles.div_ceil(rate))
+            .map_err(|_| IcpError::CallError(format!("{} cycles is too many", cycles)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rate(xdr_permyriad_per_icp: u64) -> IcpXdrConversionRate {
+        IcpXdrConversionRate {
+            timestamp_seconds: 0,
+            xdr_permyriad_per_icp,
+        }
+    }
+
+    #[test]
+    fn test_cycles_to_e8s() {
+        // 1 ICP = 5 XDR = 5T cycles
+        let rate = rate(50_000);
+
+        assert_eq!(rate.cycles_to_e8s(5_000_000_000_000).unwrap(), 100_000_000);
+        assert_eq!(rate.cycles_to_e8s(1_000_000_000_000).unwrap(), 20_000_000);
+        // rounded up so the canister gets at least the cycles asked for
+        assert_eq!(rate.cycles_to_e8s(50_001).unwrap(), 2);
+        assert_eq!(rate.cycles_to_e8s(0).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_zero_rate() {
+        assert!(rate(0).cycles_to_e8s(1).is_err());
+    }
+}
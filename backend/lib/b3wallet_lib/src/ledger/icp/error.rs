@@ -3,13 +3,19 @@ use candid::{CandidType, Deserialize};
 use enum_dispatch::enum_dispatch;
 use std::fmt;
 
+use crate::ledger::icrc::error::ICRC1TransferError;
+
 #[rustfmt::skip]
 #[enum_dispatch]
 #[derive(CandidType, Clone, Debug, Deserialize, PartialEq)]
 pub enum IcpError {
     CallError(String),
     TopUpPending(String),
+    IndexError(String),
+    InvalidRecipient(String),
+    GovernanceError(String),
     ICPTransferError,
+    ICRC1TransferError,
     NotifyError
 }
 
@@ -18,9 +24,13 @@ impl fmt::Display for IcpError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             IcpError::ICPTransferError(ref err) => write!(f, "ICP Transfer error: {}", err),
+            IcpError::ICRC1TransferError(ref err) => write!(f, "ICRC1 Transfer error: {}", err),
+            IcpError::InvalidRecipient(ref msg) => write!(f, "Invalid recipient: {}", msg),
+            IcpError::GovernanceError(ref msg) => write!(f, "Governance error: {}", msg),
             IcpError::NotifyError(ref err) => write!(f, "Notify error: {}", err),
             IcpError::CallError(ref msg) => write!(f, "Call error: {}", msg),
             IcpError::TopUpPending(ref msg) => write!(f, "Top up pending: {}", msg),
+            IcpError::IndexError(ref msg) => write!(f, "Index error: {}", msg),
         }
     }
 }
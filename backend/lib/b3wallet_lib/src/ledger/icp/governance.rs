@@ -0,0 +1,333 @@
+use b3_utils::{
+    ledger::{currency::ICPToken, AccountIdentifier},
+    sha2::Sha256,
+    types::CanisterId,
+    Subaccount,
+};
+use candid::{CandidType, Principal};
+use ic_cdk::api::call::call;
+use serde::{Deserialize, Serialize};
+
+use super::error::IcpError;
+
+pub const GOVERNANCE_CANISTER_ID: &str = "rrkah-fqaaa-aaaaa-aaaaq-cai";
+
+pub type NeuronIdValue = u64;
+
+#[derive(CandidType, Clone, Deserialize, Serialize, PartialEq, Eq, Debug)]
+pub struct NeuronId {
+    pub id: NeuronIdValue,
+}
+
+#[derive(CandidType, Clone, Deserialize, Serialize, PartialEq, Eq, Debug)]
+pub struct ProposalId {
+    pub id: u64,
+}
+
+#[derive(CandidType, Clone, Deserialize, Serialize, PartialEq, Eq, Debug)]
+pub struct GovernanceAccountIdentifier {
+    pub hash: Vec<u8>,
+}
+
+#[derive(CandidType, Clone, Deserialize, Serialize, PartialEq, Eq, Debug)]
+pub struct Amount {
+    pub e8s: u64,
+}
+
+#[derive(CandidType, Clone, Deserialize, Serialize, PartialEq, Eq, Debug)]
+pub struct IncreaseDissolveDelay {
+    pub additional_dissolve_delay_seconds: u32,
+}
+
+#[derive(CandidType, Clone, Deserialize, Serialize, PartialEq, Eq, Debug)]
+pub struct StartDissolving {}
+
+#[derive(CandidType, Clone, Deserialize, Serialize, PartialEq, Eq, Debug)]
+pub struct StopDissolving {}
+
+#[derive(CandidType, Clone, Deserialize, Serialize, PartialEq, Eq, Debug)]
+pub enum NeuronOperation {
+    IncreaseDissolveDelay(IncreaseDissolveDelay),
+    StartDissolving(StartDissolving),
+    StopDissolving(StopDissolving),
+}
+
+#[derive(CandidType, Clone, Deserialize, Serialize, PartialEq, Eq, Debug)]
+pub struct Configure {
+    pub operation: Option<NeuronOperation>,
+}
+
+#[derive(CandidType, Clone, Deserialize, Serialize, PartialEq, Eq, Debug)]
+pub struct Disburse {
+    pub to_account: Option<GovernanceAccountIdentifier>,
+    pub amount: Option<Amount>,
+}
+
+#[derive(CandidType, Clone, Deserialize, Serialize, PartialEq, Eq, Debug)]
+pub struct Follow {
+    pub topic: i32,
+    pub followees: Vec<NeuronId>,
+}
+
+#[derive(CandidType, Clone, Deserialize, Serialize, PartialEq, Eq, Debug)]
+pub struct RegisterVote {
+    pub vote: i32,
+    pub proposal: Option<ProposalId>,
+}
+
+#[derive(CandidType, Clone, Deserialize, Serialize, PartialEq, Eq, Debug)]
+pub struct ClaimOrRefreshNeuronFromAccount {
+    pub controller: Option<Principal>,
+    pub memo: u64,
+}
+
+#[derive(CandidType, Clone, Deserialize, Serialize, PartialEq, Eq, Debug)]
+pub enum By {
+    MemoAndController(ClaimOrRefreshNeuronFromAccount),
+}
+
+#[derive(CandidType, Clone, Deserialize, Serialize, PartialEq, Eq, Debug)]
+pub struct ClaimOrRefresh {
+    pub by: Option<By>,
+}
+
+#[derive(CandidType, Clone, Deserialize, Serialize, PartialEq, Eq, Debug)]
+pub enum Command {
+    Configure(Configure),
+    Disburse(Disburse),
+    Follow(Follow),
+    RegisterVote(RegisterVote),
+    ClaimOrRefresh(ClaimOrRefresh),
+}
+
+#[derive(CandidType, Clone, Deserialize, Serialize, PartialEq, Eq, Debug)]
+pub struct ManageNeuron {
+    pub id: Option<NeuronId>,
+    pub command: Option<Command>,
+}
+
+#[derive(CandidType, Clone, Deserialize, PartialEq, Eq, Debug)]
+pub struct GovernanceError {
+    pub error_message: String,
+    pub error_type: i32,
+}
+
+#[derive(CandidType, Clone, Deserialize, PartialEq, Eq, Debug)]
+pub struct DisburseResponse {
+    pub transfer_block_height: u64,
+}
+
+#[derive(CandidType, Clone, Deserialize, PartialEq, Eq, Debug)]
+pub struct ClaimOrRefreshResponse {
+    pub refreshed_neuron_id: Option<NeuronId>,
+}
+
+#[derive(CandidType, Clone, Deserialize, PartialEq, Eq, Debug)]
+pub struct EmptyResponse {}
+
+#[derive(CandidType, Clone, Deserialize, PartialEq, Eq, Debug)]
+pub enum CommandResponse {
+    Error(GovernanceError),
+    Configure(EmptyResponse),
+    Disburse(DisburseResponse),
+    Follow(EmptyResponse),
+    RegisterVote(EmptyResponse),
+    ClaimOrRefresh(ClaimOrRefreshResponse),
+}
+
+#[derive(CandidType, Clone, Deserialize, PartialEq, Eq, Debug)]
+pub struct ManageNeuronResponse {
+    pub command: Option<CommandResponse>,
+}
+
+/// NNS proposal votes, as the governance canister numbers them.
+#[derive(CandidType, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, Debug)]
+pub enum Vote {
+    Yes,
+    No,
+}
+
+impl From<Vote> for i32 {
+    fn from(vote: Vote) -> Self {
+        match vote {
+            Vote::Yes => 1,
+            Vote::No => 2,
+        }
+    }
+}
+
+/// The subaccount of the governance canister where the stake of a neuron lives,
+/// derived from the controller and the memo like the NNS does it.
+pub fn neuron_subaccount(controller: &Principal, memo: u64) -> Subaccount {
+    let mut buf = Vec::new();
+
+    buf.push(0x0c);
+    buf.extend_from_slice(b"neuron-stake");
+    buf.extend_from_slice(controller.as_slice());
+    buf.extend_from_slice(&memo.to_be_bytes());
+
+    Subaccount(Sha256::hash(&buf))
+}
+
+#[derive(CandidType, Clone, Deserialize, Serialize, PartialEq, Debug)]
+pub struct Governance(pub CanisterId);
+
+impl Default for Governance {
+    fn default() -> Self {
+        Governance(Principal::from_text(GOVERNANCE_CANISTER_ID).unwrap())
+    }
+}
+
+impl Governance {
+    pub fn new(canister_id: CanisterId) -> Self {
+        Governance(canister_id)
+    }
+
+    /// The ledger account to transfer the stake of a neuron to.
+    pub fn neuron_account(&self, controller: &Principal, memo: u64) -> AccountIdentifier {
+        AccountIdentifier::new(self.0, Some(neuron_subaccount(controller, memo)))
+    }
+
+    pub async fn manage_neuron(&self, args: ManageNeuron) -> Result<CommandResponse, IcpError> {
+        let (res,): (ManageNeuronResponse,) = call(self.0, "manage_neuron", (args,))
+            .await
+            .map_err(|e| IcpError::CallError(e.1))?;
+
+        match res.command {
+            Some(CommandResponse::Error(err)) => Err(IcpError::GovernanceError(err.error_message)),
+            Some(command) => Ok(command),
+            None => Err(IcpError::GovernanceError("Empty response".to_string())),
+        }
+    }
+
+    async fn command(
+        &self,
+        neuron_id: NeuronIdValue,
+        command: Command,
+    ) -> Result<CommandResponse, IcpError> {
+        self.manage_neuron(ManageNeuron {
+            id: Some(NeuronId { id: neuron_id }),
+            command: Some(command),
+        })
+        .await
+    }
+
+    /// Claim a newly staked neuron, or refresh the stake of an existing one.
+    pub async fn claim_or_refresh(
+        &self,
+        controller: Principal,
+        memo: u64,
+    ) -> Result<NeuronIdValue, IcpError> {
+        let args = ManageNeuron {
+            id: None,
+            command: Some(Command::ClaimOrRefresh(ClaimOrRefresh {
+                by: Some(By::MemoAndController(ClaimOrRefreshNeuronFromAccount {
+                    controller: Some(controller),
+                    memo,
+                })),
+            })),
+        };
+
+        match self.manage_neuron(args).await? {
+            CommandResponse::ClaimOrRefresh(ClaimOrRefreshResponse {
+                refreshed_neuron_id: Some(neuron_id),
+            }) => Ok(neuron_id.id),
+            response => Err(IcpError::GovernanceError(format!(
+                "Unexpected response: {:?}",
+                response
+            ))),
+        }
+    }
+
+    pub async fn configure(
+        &self,
+        neuron_id: NeuronIdValue,
+        operation: NeuronOperation,
+    ) -> Result<(), IcpError> {
+        let command = Command::Configure(Configure {
+            operation: Some(operation),
+        });
+
+        self.command(neuron_id, command).await.map(|_| ())
+    }
+
+    /// Disburse a dissolved neuron, returns the block height of the transfer.
+    pub async fn disburse(
+        &self,
+        neuron_id: NeuronIdValue,
+        to: Option<AccountIdentifier>,
+        amount: Option<ICPToken>,
+    ) -> Result<u64, IcpError> {
+        let to_account = to
+            .map(|to| hex::decode(to.to_string()))
+            .transpose()
+            .map_err(|e| IcpError::InvalidRecipient(e.to_string()))?
+            .map(|hash| GovernanceAccountIdentifier { hash });
+
+        let command = Command::Disburse(Disburse {
+            to_account,
+            amount: amount.map(|amount| Amount { e8s: amount.e8s() }),
+        });
+
+        match self.command(neuron_id, command).await? {
+            CommandResponse::Disburse(DisburseResponse {
+                transfer_block_height,
+            }) => Ok(transfer_block_height),
+            response => Err(IcpError::GovernanceError(format!(
+                "Unexpected response: {:?}",
+                response
+            ))),
+        }
+    }
+
+    pub async fn follow(
+        &self,
+        neuron_id: NeuronIdValue,
+        topic: i32,
+        followees: Vec<NeuronIdValue>,
+    ) -> Result<(), IcpError> {
+        let command = Command::Follow(Follow {
+            topic,
+            followees: followees.into_iter().map(|id| NeuronId { id }).collect(),
+        });
+
+        self.command(neuron_id, command).await.map(|_| ())
+    }
+
+    pub async fn register_vote(
+        &self,
+        neuron_id: NeuronIdValue,
+        proposal_id: u64,
+        vote: Vote,
+    ) -> Result<(), IcpError> {
+        let command = Command::RegisterVote(RegisterVote {
+            vote: vote.into(),
+            proposal: Some(ProposalId { id: proposal_id }),
+        });
+
+        self.command(neuron_id, command).await.map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_neuron_subaccount_depends_on_memo_and_controller() {
+        let controller = Principal::from_text("bkyz2-fmaaa-aaaaa-qaaaq-cai").unwrap();
+        let other = Principal::from_text("rrkah-fqaaa-aaaaa-aaaaq-cai").unwrap();
+
+        let subaccount = neuron_subaccount(&controller, 1);
+
+        assert_eq!(subaccount, neuron_subaccount(&controller, 1));
+        assert_ne!(subaccount, neuron_subaccount(&controller, 2));
+        assert_ne!(subaccount, neuron_subaccount(&other, 1));
+    }
+
+    #[test]
+    fn test_vote() {
+        assert_eq!(i32::from(Vote::Yes), 1);
+        assert_eq!(i32::from(Vote::No), 2);
+    }
+}
@@ -1,20 +1,35 @@
-use crate::ledger::types::IcpPending;
+use crate::ledger::{
+    chain::ChainTrait,
+    icrc::types::{ICRC1TransferArgs, ICRC1TransferResult},
+    types::{IcpPending, TransactionPage},
+};
 
-use super::error::IcpError;
+use super::{
+    error::IcpError,
+    governance::{Governance, NeuronIdValue},
+    index::{GetAccountIdentifierTransactionsArgs, IcpIndex},
+    types::IcpRecipient,
+};
 use b3_utils::{
     constants::{CYCLES_MINTING_CANISTER_ID, LEDGER_CANISTER_ID},
     ledger::{
         constants::{CANISTER_TOP_UP_MEMO, CANISTER_TRANSFER_MEMO, IC_TRANSACTION_FEE_ICP},
         currency::ICPToken,
-        AccountIdentifier, ICPAccountBalanceArgs, ICPTransferArgs, ICPTransferResult,
+        AccountIdentifier, ICPAccountBalanceArgs, ICRCAccount, ICPTransferArgs, ICPTransferResult,
         ICPTransferTimestamp, NotifyTopUpResult, NotifyTopupArgs, TransferBlockIndex, TransferMemo,
     },
     types::CanisterId,
     Subaccount,
 };
-use candid::CandidType;
+use candid::{CandidType, Nat};
+use num_traits::ToPrimitive;
 use serde::{Deserialize, Serialize};
 
+#[cfg(test)]
+use b3_utils::mocks::id_mock as ic_cdk_id;
+#[cfg(not(test))]
+use ic_cdk::api::id as ic_cdk_id;
+
 #[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Debug)]
 pub struct IcpChain {
     pub subaccount: Subaccount,
@@ -34,6 +49,20 @@ impl IcpChain {
             pendings: Vec::new(),
         }
     }
+
+    /// The legacy account identifier of the chain.
+    pub fn account_identifier(&self) -> AccountIdentifier {
+        AccountIdentifier::new(ic_cdk_id(), Some(self.subaccount.clone()))
+    }
+
+    /// The ICRC-1 account of the chain, the same funds as the account identifier.
+    pub fn account(&self) -> ICRCAccount {
+        ICRCAccount::new(ic_cdk_id(), Some(self.subaccount.clone()))
+    }
+
+    pub fn icrc_address(&self) -> String {
+        self.account().to_string()
+    }
 }
 
 impl IcpChain {
@@ -63,6 +92,13 @@ impl IcpChain {
             created_at_time: None,
         };
 
+        self.transfer_with_args(args).await
+    }
+
+    pub async fn transfer_with_args(
+        &self,
+        args: ICPTransferArgs,
+    ) -> Result<ICPTransferResult, IcpError> {
         let (res,): (ICPTransferResult,) = ic_cdk::call(LEDGER_CANISTER_ID, "transfer", (args,))
             .await
             .map_err(|e| IcpError::CallError(e.1))?;
@@ -70,6 +106,93 @@ impl IcpChain {
         Ok(res)
     }
 
+    pub async fn icrc1_transfer(
+        &self,
+        args: ICRC1TransferArgs,
+    ) -> Result<ICRC1TransferResult, IcpError> {
+        let (res,): (ICRC1TransferResult,) =
+            ic_cdk::call(LEDGER_CANISTER_ID, "icrc1_transfer", (args,))
+                .await
+                .map_err(|e| IcpError::CallError(e.1))?;
+
+        Ok(res)
+    }
+
+    /// Transfer to either form of recipient, legacy account identifiers go through
+    /// `transfer` and ICRC-1 accounts through `icrc1_transfer`.
+    pub async fn transfer_to(
+        &self,
+        to: IcpRecipient,
+        amount: ICPToken,
+        fee: Option<ICPToken>,
+        memo: Option<TransferMemo>,
+    ) -> Result<TransferBlockIndex, IcpError> {
+        match to {
+            IcpRecipient::AccountIdentifier(to) => self
+                .transfer(to, amount, fee, memo)
+                .await?
+                .map_err(IcpError::ICPTransferError),
+            IcpRecipient::ICRCAccount(to) => {
+                let memo = memo.unwrap_or(self.memo.clone());
+
+                let args = ICRC1TransferArgs {
+                    to,
+                    amount: amount.e8s().into(),
+                    fee: Some(fee.unwrap_or(self.fee.clone()).e8s().into()),
+                    memo: Some(memo.0.to_be_bytes().to_vec()),
+                    created_at_time: None,
+                    from_subaccount: Some(self.subaccount.clone()),
+                };
+
+                self.icrc1_transfer(args)
+                    .await?
+                    .map_err(IcpError::ICRC1TransferError)
+                    .and_then(block_index_to_u64)
+            }
+        }
+    }
+
+    /// Stake ICP into the neuron identified by the memo, claiming it if it's new
+    /// or refreshing its stake otherwise. The wallet canister is the controller.
+    pub async fn stake_neuron(
+        &self,
+        governance: &Governance,
+        amount: ICPToken,
+        memo: u64,
+    ) -> Result<NeuronIdValue, IcpError> {
+        let controller = ic_cdk_id();
+
+        let to = governance.neuron_account(&controller, memo);
+
+        self.transfer(to, amount, None, Some(TransferMemo(memo)))
+            .await?
+            .map_err(IcpError::ICPTransferError)?;
+
+        governance.claim_or_refresh(controller, memo).await
+    }
+
+    /// Get a page of the account transactions from the ICP index canister,
+    /// newest first. Pass the `next_start` of the previous page to go further back.
+    pub async fn transactions(
+        &self,
+        start: Option<u64>,
+        max_results: u64,
+    ) -> Result<TransactionPage, IcpError> {
+        let account_identifier = self.address();
+
+        let args = GetAccountIdentifierTransactionsArgs {
+            account_identifier: account_identifier.clone(),
+            start,
+            max_results,
+        };
+
+        let response = IcpIndex::default()
+            .get_account_identifier_transactions(args)
+            .await?;
+
+        Ok(response.to_page(&account_identifier, &self.token(), max_results))
+    }
+
     pub async fn top_up(
         &self,
         canister_id: CanisterId,
@@ -105,3 +228,10 @@ impl IcpChain {
         Ok(res)
     }
 }
+
+pub fn block_index_to_u64(block_index: Nat) -> Result<TransferBlockIndex, IcpError> {
+    block_index
+        .0
+        .to_u64()
+        .ok_or_else(|| IcpError::CallError(format!("Invalid block index: {}", block_index)))
+}
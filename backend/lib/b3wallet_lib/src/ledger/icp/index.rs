@@ -0,0 +1,298 @@
+use b3_utils::{
+    ledger::{currency::ICPToken, ICPTransferTimestamp},
+    types::CanisterId,
+};
+use candid::{CandidType, Principal};
+use ic_cdk::api::call::call;
+use serde::{Deserialize, Serialize};
+
+use super::error::IcpError;
+use crate::ledger::{
+    token::TokenDescriptor,
+    types::{TransactionDirection, TransactionPage, TransactionView},
+};
+
+pub const ICP_INDEX_CANISTER_ID: &str = "qhbym-qaaaa-aaaaa-aaafq-cai";
+
+#[derive(CandidType, Clone, Deserialize, Serialize, Debug)]
+pub struct GetAccountIdentifierTransactionsArgs {
+    pub account_identifier: String,
+    pub start: Option<u64>,
+    pub max_results: u64,
+}
+
+#[derive(CandidType, Clone, Deserialize, Debug)]
+pub struct GetAccountIdentifierTransactionsResponse {
+    pub balance: u64,
+    pub transactions: Vec<IndexTransactionWithId>,
+    pub oldest_tx_id: Option<u64>,
+}
+
+#[derive(CandidType, Clone, Deserialize, Debug)]
+pub struct GetAccountIdentifierTransactionsError {
+    pub message: String,
+}
+
+pub type GetAccountIdentifierTransactionsResult =
+    Result<GetAccountIdentifierTransactionsResponse, GetAccountIdentifierTransactionsError>;
+
+#[derive(CandidType, Clone, Deserialize, Debug)]
+pub struct IndexTransactionWithId {
+    pub id: u64,
+    pub transaction: IndexTransaction,
+}
+
+#[derive(CandidType, Clone, Deserialize, Debug)]
+pub struct IndexTransaction {
+    pub memo: u64,
+    pub icrc1_memo: Option<Vec<u8>>,
+    pub operation: IndexOperation,
+    pub created_at_time: Option<ICPTransferTimestamp>,
+    pub timestamp: Option<ICPTransferTimestamp>,
+}
+
+#[derive(CandidType, Clone, Deserialize, Debug)]
+pub enum IndexOperation {
+    Approve {
+        fee: ICPToken,
+        from: String,
+        allowance: ICPToken,
+        expected_allowance: Option<ICPToken>,
+        expires_at: Option<ICPTransferTimestamp>,
+        spender: String,
+    },
+    Burn {
+        from: String,
+        amount: ICPToken,
+        spender: Option<String>,
+    },
+    Mint {
+        to: String,
+        amount: ICPToken,
+    },
+    Transfer {
+        to: String,
+        fee: ICPToken,
+        from: String,
+        amount: ICPToken,
+        spender: Option<String>,
+    },
+}
+
+#[derive(CandidType, Clone, Deserialize, Serialize, PartialEq, Debug)]
+pub struct IcpIndex(pub CanisterId);
+
+impl Default for IcpIndex {
+    fn default() -> Self {
+        IcpIndex(Principal::from_text(ICP_INDEX_CANISTER_ID).unwrap())
+    }
+}
+
+impl IcpIndex {
+    pub async fn get_account_identifier_transactions(
+        &self,
+        args: GetAccountIdentifierTransactionsArgs,
+    ) -> Result<GetAccountIdentifierTransactionsResponse, IcpError> {
+        let (res,): (GetAccountIdentifierTransactionsResult,) =
+            call(self.0, "get_account_identifier_transactions", (args,))
+                .await
+                .map_err(|e| IcpError::CallError(e.1))?;
+
+        res.map_err(|err| IcpError::IndexError(err.message))
+    }
+}
+
+impl IndexTransactionWithId {
+    /// Normalize the index transaction as seen from the given account identifier.
+    pub fn to_view(&self, account_identifier: &str, token: &TokenDescriptor) -> TransactionView {
+        let IndexTransaction {
+            memo,
+            icrc1_memo,
+            operation,
+            created_at_time,
+            timestamp,
+        } = &self.transaction;
+
+        let (direction, counterparty, amount, fee) = match operation {
+            IndexOperation::Transfer {
+                to,
+                fee,
+                from,
+                amount,
+                ..
+            } => {
+                let (direction, counterparty) = if from == to {
+                    (TransactionDirection::SelfTransfer, to)
+                } else if from == account_identifier {
+                    (TransactionDirection::Outgoing, to)
+                } else {
+                    (TransactionDirection::Incoming, from)
+                };
+
+                (direction, Some(counterparty.clone()), amount, Some(fee))
+            }
+            IndexOperation::Mint { amount, .. } => {
+                (TransactionDirection::Incoming, None, amount, None)
+            }
+            IndexOperation::Burn { amount, .. } => {
+                (TransactionDirection::Outgoing, None, amount, None)
+            }
+            IndexOperation::Approve {
+                fee,
+                allowance,
+                spender,
+                ..
+            } => (
+                TransactionDirection::Approve,
+                Some(spender.clone()),
+                allowance,
+                Some(fee),
+            ),
+        };
+
+        let memo = match icrc1_memo {
+            Some(icrc1_memo) => Some(icrc1_memo.clone()),
+            None if *memo != 0 => Some(memo.to_be_bytes().to_vec()),
+            None => None,
+        };
+
+        TransactionView {
+            id: self.id,
+            direction,
+            counterparty,
+            amount: token.amount(amount.e8s().into()),
+            fee: fee.map(|fee| token.amount(fee.e8s().into())),
+            memo,
+            timestamp: timestamp
+                .as_ref()
+                .or(created_at_time.as_ref())
+                .map(|timestamp| timestamp.timestamp_nanos),
+        }
+    }
+}
+
+impl GetAccountIdentifierTransactionsResponse {
+    /// Normalize the response into a page of the account transactions.
+    pub fn to_page(
+        &self,
+        account_identifier: &str,
+        token: &TokenDescriptor,
+        max_results: u64,
+    ) -> TransactionPage {
+        let transactions = self
+            .transactions
+            .iter()
+            .map(|tx| tx.to_view(account_identifier, token))
+            .collect();
+
+        let next_start = match self.transactions.last() {
+            Some(last) if (self.transactions.len() as u64) == max_results => {
+                match self.oldest_tx_id {
+                    Some(oldest_tx_id) if oldest_tx_id == last.id => None,
+                    _ => Some(last.id),
+                }
+            }
+            _ => None,
+        };
+
+        TransactionPage {
+            transactions,
+            balance: token.amount(self.balance.into()),
+            next_start,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ME: &str = "me";
+    const OTHER: &str = "other";
+
+    fn transfer(id: u64, from: &str, to: &str) -> IndexTransactionWithId {
+        IndexTransactionWithId {
+            id,
+            transaction: IndexTransaction {
+                memo: 0,
+                icrc1_memo: None,
+                operation: IndexOperation::Transfer {
+                    to: to.to_string(),
+                    fee: ICPToken::from_e8s(10_000),
+                    from: from.to_string(),
+                    amount: ICPToken::from_e8s(150_000_000),
+                    spender: None,
+                },
+                created_at_time: None,
+                timestamp: Some(ICPTransferTimestamp {
+                    timestamp_nanos: 42,
+                }),
+            },
+        }
+    }
+
+    fn token() -> TokenDescriptor {
+        TokenDescriptor::new("ICP", 8, None)
+    }
+
+    #[test]
+    fn test_transfer_direction() {
+        let view = transfer(1, ME, OTHER).to_view(ME, &token());
+
+        assert_eq!(view.direction, TransactionDirection::Outgoing);
+        assert_eq!(view.counterparty, Some(OTHER.to_string()));
+        assert_eq!(token().format(&view.amount), "1.5 ICP");
+        assert_eq!(view.fee, Some(token().amount(10_000u64.into())));
+        assert_eq!(view.timestamp, Some(42));
+        assert_eq!(view.memo, None);
+
+        let view = transfer(2, OTHER, ME).to_view(ME, &token());
+
+        assert_eq!(view.direction, TransactionDirection::Incoming);
+        assert_eq!(view.counterparty, Some(OTHER.to_string()));
+
+        let view = transfer(3, ME, ME).to_view(ME, &token());
+
+        assert_eq!(view.direction, TransactionDirection::SelfTransfer);
+    }
+
+    #[test]
+    fn test_memo() {
+        let mut tx = transfer(1, ME, OTHER);
+        tx.transaction.memo = 0x0102;
+
+        assert_eq!(
+            tx.to_view(ME, &token()).memo,
+            Some(vec![0, 0, 0, 0, 0, 0, 1, 2])
+        );
+
+        tx.transaction.icrc1_memo = Some(vec![7]);
+
+        assert_eq!(tx.to_view(ME, &token()).memo, Some(vec![7]));
+    }
+
+    #[test]
+    fn test_pagination() {
+        let response = GetAccountIdentifierTransactionsResponse {
+            balance: 100,
+            transactions: vec![transfer(9, ME, OTHER), transfer(5, OTHER, ME)],
+            oldest_tx_id: Some(1),
+        };
+
+        let page = response.to_page(ME, &token(), 2);
+        assert_eq!(page.next_start, Some(5));
+        assert_eq!(page.transactions.len(), 2);
+
+        // less than asked, this is the last page
+        let page = response.to_page(ME, &token(), 10);
+        assert_eq!(page.next_start, None);
+
+        // reached the oldest transaction
+        let response = GetAccountIdentifierTransactionsResponse {
+            oldest_tx_id: Some(5),
+            ..response
+        };
+        let page = response.to_page(ME, &token(), 2);
+        assert_eq!(page.next_start, None);
+    }
+}
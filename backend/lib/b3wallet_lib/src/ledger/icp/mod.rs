@@ -1,3 +1,7 @@
 pub mod api;
+pub mod cmc;
 pub mod error;
+pub mod governance;
 pub mod icp;
+pub mod index;
+pub mod types;
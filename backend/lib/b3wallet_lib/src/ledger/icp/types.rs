@@ -0,0 +1,77 @@
+use b3_utils::ledger::{AccountIdentifier, ICRCAccount};
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+use super::error::IcpError;
+
+/// Where ICP can be sent: a legacy account identifier (64 hex characters)
+/// or an ICRC-1 account (principal with an optional subaccount).
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub enum IcpRecipient {
+    AccountIdentifier(AccountIdentifier),
+    ICRCAccount(ICRCAccount),
+}
+
+impl FromStr for IcpRecipient {
+    type Err = IcpError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() == 64 && s.chars().all(|c| c.is_ascii_hexdigit()) {
+            return AccountIdentifier::from_str(s)
+                .map(IcpRecipient::AccountIdentifier)
+                .map_err(|e| IcpError::InvalidRecipient(e.to_string()));
+        }
+
+        ICRCAccount::from_str(s)
+            .map(IcpRecipient::ICRCAccount)
+            .map_err(|e| IcpError::InvalidRecipient(e.to_string()))
+    }
+}
+
+impl fmt::Display for IcpRecipient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IcpRecipient::AccountIdentifier(account) => write!(f, "{}", account),
+            IcpRecipient::ICRCAccount(account) => write!(f, "{}", account),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use b3_utils::{types::CanisterId, Subaccount};
+
+    #[test]
+    fn test_parse_account_identifier() {
+        let owner = CanisterId::from_text("bkyz2-fmaaa-aaaaa-qaaaq-cai").unwrap();
+        let identifier = AccountIdentifier::new(owner, None);
+
+        let recipient = IcpRecipient::from_str(&identifier.to_string()).unwrap();
+
+        assert_eq!(recipient, IcpRecipient::AccountIdentifier(identifier));
+    }
+
+    #[test]
+    fn test_parse_icrc_account() {
+        let owner = CanisterId::from_text("bkyz2-fmaaa-aaaaa-qaaaq-cai").unwrap();
+
+        let recipient = IcpRecipient::from_str("bkyz2-fmaaa-aaaaa-qaaaq-cai").unwrap();
+        assert_eq!(
+            recipient,
+            IcpRecipient::ICRCAccount(ICRCAccount::new(owner, None))
+        );
+
+        let account = ICRCAccount::new(owner, Some(Subaccount([1; 32])));
+
+        let recipient = IcpRecipient::from_str(&account.to_string()).unwrap();
+        assert_eq!(recipient, IcpRecipient::ICRCAccount(account));
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(IcpRecipient::from_str("not an account").is_err());
+        assert!(IcpRecipient::from_str(&"0".repeat(64)).is_err());
+    }
+}
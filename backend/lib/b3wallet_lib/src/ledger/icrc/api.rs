@@ -5,6 +5,8 @@ use super::{
 use crate::ledger::{
     chain::ChainTrait,
     error::LedgerError,
+    options::{SendOptions, MAX_MEMO_LENGTH},
+    token::TokenDescriptor,
     types::{Balance, PendingEnum, SendResult},
 };
 use async_trait::async_trait;
@@ -24,7 +26,11 @@ impl ChainTrait for IcrcChain {
         ICRCAccount::new(owner, Some(self.subaccount.clone())).to_string()
     }
 
-    async fn balance(&self) -> Result<Balance, LedgerError> {
+    fn token(&self) -> TokenDescriptor {
+        self.token_descriptor()
+    }
+
+    async fn balance(&self) -> Result<TokenAmount, LedgerError> {
         let canister_id = ic_cdk_id();
 
         let account = ICRCAccount::new(canister_id, Some(self.subaccount.clone()));
@@ -33,19 +39,28 @@ impl ChainTrait for IcrcChain {
             .await
             .map_err(|e| LedgerError::CallError(e.1))?;
 
-        Ok(res)
+        Ok(self.token().amount(res))
     }
 
-    async fn send(&self, to: String, amount: TokenAmount) -> Result<SendResult, LedgerError> {
+    async fn send(
+        &self,
+        to: String,
+        amount: TokenAmount,
+        options: SendOptions,
+    ) -> Result<SendResult, LedgerError> {
         let to = ICRCAccount::from_str(&to).map_err(|e| LedgerError::CallError(e.to_string()))?;
 
+        let token = self.token();
+
+        options.check_memo(MAX_MEMO_LENGTH)?;
+
         let transfer_args = ICRC1TransferArgs {
             to,
-            amount: amount.to_nat(),
-            from_subaccount: Some(self.subaccount.clone()),
-            fee: self.fee.clone(),
-            memo: self.memo.clone(),
-            created_at_time: self.created_at_time,
+            amount: token.to_base_units(&amount)?,
+            from_subaccount: options.from_subaccount(Some(self.subaccount.clone()))?,
+            fee: options.fee_base_units(&token)?.or(self.fee.clone()),
+            memo: options.memo.or(self.memo.clone()),
+            created_at_time: options.created_at_time.or(self.created_at_time),
         };
 
         let (res,): (TxIndex,) = ic_cdk::call(self.canister_id, "icrc1_transfer", (transfer_args,))
@@ -1,12 +1,13 @@
-use crate::ledger::types::IcrcPending;
+use crate::ledger::{token::TokenDescriptor, types::IcrcPending};
 use b3_utils::{types::CanisterId, Subaccount};
-use candid::CandidType;
+use candid::{CandidType, Nat};
+use num_traits::ToPrimitive;
 use serde::{Deserialize, Serialize};
 
 use super::{
     error::IcrcError,
     icrc1::ICRC1,
-    types::{ICRCMemo, ICRCMetadata, ICRCTimestamp, ICRCTokens},
+    types::{ICRC1MetadataValue, ICRCMemo, ICRCMetadata, ICRCTimestamp, ICRCTokens},
 };
 
 #[derive(CandidType, Serialize, Clone, Deserialize, PartialEq, Debug)]
@@ -39,3 +40,45 @@ impl IcrcChain {
         })
     }
 }
+
+impl IcrcChain {
+    fn metadata_value(&self, key: &str) -> Option<&ICRC1MetadataValue> {
+        self.metadata
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value)
+    }
+
+    /// The token symbol from the `icrc1:symbol` metadata entry.
+    pub fn symbol(&self) -> String {
+        match self.metadata_value("icrc1:symbol") {
+            Some(ICRC1MetadataValue::Text(symbol)) => symbol.clone(),
+            _ => self.canister_id.to_text(),
+        }
+    }
+
+    /// The token decimals from the `icrc1:decimals` metadata entry.
+    /// ICRC-1 ledgers default to 8 decimals.
+    pub fn decimals(&self) -> u8 {
+        match self.metadata_value("icrc1:decimals") {
+            Some(ICRC1MetadataValue::Nat(decimals)) => decimals.0.to_u8().unwrap_or(8),
+            _ => 8,
+        }
+    }
+
+    /// The transfer fee, preferring the fee fetched from the ledger.
+    pub fn transfer_fee(&self) -> Option<Nat> {
+        if self.fee.is_some() {
+            return self.fee.clone();
+        }
+
+        match self.metadata_value("icrc1:fee") {
+            Some(ICRC1MetadataValue::Nat(fee)) => Some(fee.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn token_descriptor(&self) -> TokenDescriptor {
+        TokenDescriptor::new(&self.symbol(), self.decimals(), self.transfer_fee())
+    }
+}
@@ -0,0 +1,60 @@
+use super::{error::Icrc7Error, icrc7::Icrc7Chain};
+use crate::ledger::{
+    chain::ChainTrait,
+    error::LedgerError,
+    options::SendOptions,
+    token::TokenDescriptor,
+    types::{PendingEnum, SendResult},
+};
+use async_trait::async_trait;
+use b3_utils::ledger::currency::TokenAmount;
+
+#[async_trait]
+impl ChainTrait for Icrc7Chain {
+    fn address(&self) -> String {
+        self.account().to_string()
+    }
+
+    fn token(&self) -> TokenDescriptor {
+        TokenDescriptor::new(&self.symbol, 0, None)
+    }
+
+    /// The number of tokens the account holds in the collection.
+    async fn balance(&self) -> Result<TokenAmount, LedgerError> {
+        let balances = self
+            .collection
+            .balance_of(vec![self.account()])
+            .await
+            .map_err(LedgerError::Icrc7Error)?;
+
+        let balance = balances.into_iter().next().unwrap_or_else(|| 0u8.into());
+
+        Ok(self.token().amount(balance))
+    }
+
+    async fn send(
+        &self,
+        _to: String,
+        _amount: TokenAmount,
+        _options: SendOptions,
+    ) -> Result<SendResult, LedgerError> {
+        Err(LedgerError::Icrc7Error(
+            Icrc7Error::FungibleSendNotSupported,
+        ))
+    }
+
+    // transfers are final once `icrc7_transfer` returns, nothing is ever pending
+    async fn check_pending(&self, _pending_index: usize) -> Result<(), LedgerError> {
+        Ok(())
+    }
+
+    fn pendings(&self) -> Vec<PendingEnum> {
+        Vec::new()
+    }
+
+    fn add_pending(&mut self, _pending: PendingEnum) {}
+
+    fn remove_pending(&mut self, _pending_index: usize) {}
+
+    fn clear_pending(&mut self) {}
+}
@@ -0,0 +1,52 @@
+use candid::{CandidType, Deserialize, Nat};
+use std::fmt;
+
+#[rustfmt::skip]
+#[derive(CandidType, Clone, Debug, Deserialize, PartialEq, Eq)]
+pub enum Icrc7Error {
+    CallError(String),
+    Icrc7TransferError(Icrc7TransferError),
+    MissingTransferResult(Nat),
+    FungibleSendNotSupported,
+}
+
+#[rustfmt::skip]
+impl fmt::Display for Icrc7Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Icrc7Error::CallError(ref msg) => write!(f, "Call Error::{}", msg),
+            Icrc7Error::Icrc7TransferError(ref err) => write!(f, "ICRC7 transfer Error::{}", err),
+            Icrc7Error::MissingTransferResult(ref token_id) => write!(f, "No transfer result for token {}", token_id),
+            Icrc7Error::FungibleSendNotSupported => write!(f, "NFTs are transferred by token id, use the ICRC7 transfer instead"),
+        }
+    }
+}
+
+#[rustfmt::skip]
+#[derive(CandidType, Clone, Debug, Deserialize, PartialEq, Eq)]
+pub enum Icrc7TransferError {
+    NonExistingTokenId,
+    InvalidRecipient,
+    Unauthorized,
+    TooOld,
+    CreatedInFuture { ledger_time: u64 },
+    Duplicate { duplicate_of: Nat },
+    GenericError { error_code: Nat, message: String },
+    GenericBatchError { error_code: Nat, message: String },
+}
+
+#[rustfmt::skip]
+impl fmt::Display for Icrc7TransferError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Icrc7TransferError::NonExistingTokenId => write!(f, "Token does not exist"),
+            Icrc7TransferError::InvalidRecipient => write!(f, "Invalid recipient"),
+            Icrc7TransferError::Unauthorized => write!(f, "Unauthorized: the account does not own the token"),
+            Icrc7TransferError::TooOld => write!(f, "Transaction is too old"),
+            Icrc7TransferError::CreatedInFuture { ledger_time } => write!(f, "Transaction created in the future: {}", ledger_time),
+            Icrc7TransferError::Duplicate { duplicate_of } => write!(f, "Duplicate transaction: duplicate of {}", duplicate_of),
+            Icrc7TransferError::GenericError { error_code, message } => write!(f, "Generic error: {} - {}", error_code, message),
+            Icrc7TransferError::GenericBatchError { error_code, message } => write!(f, "Generic batch error: {} - {}", error_code, message),
+        }
+    }
+}
@@ -0,0 +1,308 @@
+use crate::ledger::icrc::types::TxIndex;
+use b3_utils::{ledger::ICRCAccount, types::CanisterId, Subaccount};
+use candid::{CandidType, Nat};
+use ic_cdk::api::call::call;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    error::Icrc7Error,
+    types::{Icrc7Metadata, Icrc7Token, Icrc7TransferArgs, Icrc7TransferResult, TokenId},
+};
+
+#[cfg(test)]
+use b3_utils::mocks::id_mock as ic_cdk_id;
+#[cfg(not(test))]
+use ic_cdk::api::id as ic_cdk_id;
+
+/// Number of token ids requested per `icrc7_tokens_of` page.
+const TOKENS_PAGE_SIZE: u64 = 100;
+
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct ICRC7(pub CanisterId);
+
+impl ICRC7 {
+    pub async fn name(&self) -> Result<String, Icrc7Error> {
+        let (res,): (String,) = call(self.0, "icrc7_name", ())
+            .await
+            .map_err(|e| Icrc7Error::CallError(e.1))?;
+
+        Ok(res)
+    }
+
+    pub async fn symbol(&self) -> Result<String, Icrc7Error> {
+        let (res,): (String,) = call(self.0, "icrc7_symbol", ())
+            .await
+            .map_err(|e| Icrc7Error::CallError(e.1))?;
+
+        Ok(res)
+    }
+
+    pub async fn balance_of(&self, accounts: Vec<ICRCAccount>) -> Result<Vec<Nat>, Icrc7Error> {
+        let (res,): (Vec<Nat>,) = call(self.0, "icrc7_balance_of", (accounts,))
+            .await
+            .map_err(|e| Icrc7Error::CallError(e.1))?;
+
+        Ok(res)
+    }
+
+    pub async fn tokens_of(
+        &self,
+        account: ICRCAccount,
+        prev: Option<TokenId>,
+        take: Option<Nat>,
+    ) -> Result<Vec<TokenId>, Icrc7Error> {
+        let (res,): (Vec<TokenId>,) = call(self.0, "icrc7_tokens_of", (account, prev, take))
+            .await
+            .map_err(|e| Icrc7Error::CallError(e.1))?;
+
+        Ok(res)
+    }
+
+    pub async fn token_metadata(
+        &self,
+        token_ids: Vec<TokenId>,
+    ) -> Result<Vec<Option<Icrc7Metadata>>, Icrc7Error> {
+        let (res,): (Vec<Option<Icrc7Metadata>>,) =
+            call(self.0, "icrc7_token_metadata", (token_ids,))
+                .await
+                .map_err(|e| Icrc7Error::CallError(e.1))?;
+
+        Ok(res)
+    }
+
+    pub async fn transfer(
+        &self,
+        args: Vec<Icrc7TransferArgs>,
+    ) -> Result<Vec<Option<Icrc7TransferResult>>, Icrc7Error> {
+        let (res,): (Vec<Option<Icrc7TransferResult>>,) = call(self.0, "icrc7_transfer", (args,))
+            .await
+            .map_err(|e| Icrc7Error::CallError(e.1))?;
+
+        Ok(res)
+    }
+}
+
+/// The token ids of `icrc7_tokens_of`, gathered page after page.
+#[derive(Default)]
+struct TokenPages {
+    token_ids: Vec<TokenId>,
+    done: bool,
+}
+
+impl TokenPages {
+    /// The page after the last token id gathered.
+    fn prev(&self) -> Option<TokenId> {
+        self.token_ids.last().cloned()
+    }
+
+    /// A short page is the last one. So is a page that doesn't go past the
+    /// previous one, a collection answering that way would be paged forever.
+    fn push(&mut self, page: Vec<TokenId>) {
+        let prev = self.prev();
+
+        self.done = (page.len() as u64) < TOKENS_PAGE_SIZE;
+
+        for token_id in page {
+            if prev.as_ref().map_or(false, |prev| &token_id <= prev) {
+                self.done = true;
+                break;
+            }
+
+            self.token_ids.push(token_id);
+        }
+    }
+}
+
+/// Pair the token ids with their metadata, a collection can answer with fewer entries.
+fn with_metadata(token_ids: Vec<TokenId>, metadata: Vec<Option<Icrc7Metadata>>) -> Vec<Icrc7Token> {
+    token_ids
+        .into_iter()
+        .zip(metadata.into_iter().chain(std::iter::repeat(None)))
+        .map(|(token_id, metadata)| Icrc7Token { token_id, metadata })
+        .collect()
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct Icrc7Chain {
+    pub collection: ICRC7,
+    pub subaccount: Subaccount,
+    pub name: String,
+    pub symbol: String,
+}
+
+impl Icrc7Chain {
+    pub async fn new(canister_id: CanisterId, subaccount: Subaccount) -> Result<Self, Icrc7Error> {
+        let collection = ICRC7(canister_id);
+
+        let name = collection.name().await?;
+
+        let symbol = collection.symbol().await?;
+
+        Ok(Icrc7Chain {
+            collection,
+            subaccount,
+            name,
+            symbol,
+        })
+    }
+
+    pub fn canister_id(&self) -> CanisterId {
+        self.collection.0
+    }
+
+    pub fn account(&self) -> ICRCAccount {
+        ICRCAccount::new(ic_cdk_id(), Some(self.subaccount.clone()))
+    }
+
+    /// List every token the account holds in the collection, with its metadata.
+    pub async fn tokens(&self) -> Result<Vec<Icrc7Token>, Icrc7Error> {
+        let account = self.account();
+
+        let mut pages = TokenPages::default();
+
+        while !pages.done {
+            let page = self
+                .collection
+                .tokens_of(account.clone(), pages.prev(), Some(TOKENS_PAGE_SIZE.into()))
+                .await?;
+
+            pages.push(page);
+        }
+
+        let token_ids = pages.token_ids;
+
+        if token_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let metadata = self.collection.token_metadata(token_ids.clone()).await?;
+
+        Ok(with_metadata(token_ids, metadata))
+    }
+
+    /// Transfer a single token from the account's subaccount.
+    pub async fn transfer(
+        &self,
+        to: ICRCAccount,
+        token_id: TokenId,
+        memo: Option<Vec<u8>>,
+    ) -> Result<TxIndex, Icrc7Error> {
+        let args = Icrc7TransferArgs {
+            from_subaccount: Some(self.subaccount.clone()),
+            to,
+            token_id: token_id.clone(),
+            memo,
+            created_at_time: None,
+        };
+
+        let result = self.collection.transfer(vec![args]).await?;
+
+        match result.into_iter().next().flatten() {
+            Some(Ok(tx_index)) => Ok(tx_index),
+            Some(Err(err)) => Err(Icrc7Error::Icrc7TransferError(err)),
+            None => Err(Icrc7Error::MissingTransferResult(token_id)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ledger::{
+        chain::ChainTrait,
+        icrc7::types::Icrc7Value,
+        types::{ChainEnum, PendingEnum},
+    };
+    use b3_utils::mocks::id_mock;
+
+    fn chain() -> Icrc7Chain {
+        Icrc7Chain {
+            collection: ICRC7(CanisterId::from_text("bkyz2-fmaaa-aaaaa-qaaaq-cai").unwrap()),
+            subaccount: Subaccount([1; 32]),
+            name: "Punks".to_string(),
+            symbol: "PNK".to_string(),
+        }
+    }
+
+    fn page(range: std::ops::Range<u64>) -> Vec<TokenId> {
+        range.map(TokenId::from).collect()
+    }
+
+    #[test]
+    fn test_icrc7_chain() {
+        let chain = chain();
+
+        assert_eq!(
+            chain.address(),
+            ICRCAccount::new(id_mock(), Some(Subaccount([1; 32]))).to_string()
+        );
+        assert_eq!(chain.canister_id(), chain.collection.0);
+
+        // NFTs are counted, not divided
+        assert_eq!(chain.token().decimals, 0);
+        assert_eq!(chain.token().symbol, "PNK");
+
+        assert!(ChainEnum::ICRC7(chain.canister_id()).is_icrc7());
+    }
+
+    #[test]
+    fn test_icrc7_no_pendings() {
+        let mut chain = chain();
+
+        chain.add_pending(PendingEnum::new_icrc(1, 1u8.into()));
+
+        assert!(chain.pendings().is_empty());
+    }
+
+    #[test]
+    fn test_token_pages() {
+        let mut pages = TokenPages::default();
+
+        assert_eq!(pages.prev(), None);
+
+        pages.push(page(0..TOKENS_PAGE_SIZE));
+
+        assert!(!pages.done);
+        assert_eq!(pages.prev(), Some(TokenId::from(TOKENS_PAGE_SIZE - 1)));
+
+        pages.push(page(TOKENS_PAGE_SIZE..TOKENS_PAGE_SIZE + 10));
+
+        assert!(pages.done);
+        assert_eq!(pages.token_ids, page(0..TOKENS_PAGE_SIZE + 10));
+    }
+
+    #[test]
+    fn test_token_pages_exact_multiple() {
+        let mut pages = TokenPages::default();
+
+        pages.push(page(0..TOKENS_PAGE_SIZE));
+        pages.push(vec![]);
+
+        assert!(pages.done);
+        assert_eq!(pages.token_ids.len() as u64, TOKENS_PAGE_SIZE);
+    }
+
+    #[test]
+    fn test_token_pages_not_advancing() {
+        let mut pages = TokenPages::default();
+
+        pages.push(page(0..TOKENS_PAGE_SIZE));
+
+        // the same full page again ends the listing instead of looping
+        pages.push(page(0..TOKENS_PAGE_SIZE));
+
+        assert!(pages.done);
+        assert_eq!(pages.token_ids, page(0..TOKENS_PAGE_SIZE));
+    }
+
+    #[test]
+    fn test_with_metadata() {
+        let metadata = vec![("name".to_string(), Icrc7Value::Text("#0".to_string()))];
+
+        let tokens = with_metadata(page(0..2), vec![Some(metadata.clone())]);
+
+        assert_eq!(tokens.len(), 2);
+        assert_eq!(tokens[0].metadata, Some(metadata));
+        assert_eq!(tokens[1].metadata, None);
+    }
+}
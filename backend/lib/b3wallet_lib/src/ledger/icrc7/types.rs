@@ -0,0 +1,39 @@
+use super::error::Icrc7TransferError;
+use crate::ledger::icrc::types::{ICRCMemo, ICRCTimestamp, TxIndex};
+use b3_utils::{ledger::ICRCAccount, Subaccount};
+use candid::{CandidType, Int, Nat};
+use serde::{Deserialize, Serialize};
+use serde_bytes::ByteBuf;
+
+pub type TokenId = Nat;
+
+pub type Icrc7Metadata = Vec<(String, Icrc7Value)>;
+
+pub type Icrc7TransferResult = Result<TxIndex, Icrc7TransferError>;
+
+/// ICRC-3 style value used by the `icrc7_token_metadata` endpoint.
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub enum Icrc7Value {
+    Blob(ByteBuf),
+    Text(String),
+    Nat(Nat),
+    Int(Int),
+    Array(Vec<Icrc7Value>),
+    Map(Vec<(String, Icrc7Value)>),
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug)]
+pub struct Icrc7TransferArgs {
+    pub from_subaccount: Option<Subaccount>,
+    pub to: ICRCAccount,
+    pub token_id: TokenId,
+    pub memo: Option<ICRCMemo>,
+    pub created_at_time: Option<ICRCTimestamp>,
+}
+
+/// A token held by the account, with the metadata the collection reports for it.
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct Icrc7Token {
+    pub token_id: TokenId,
+    pub metadata: Option<Icrc7Metadata>,
+}
@@ -1,10 +1,13 @@
 use super::{
     chain::Chain,
     error::LedgerError,
-    types::{AddressMap, Balance, ChainEnum, ChainMap, PendingEnum, SendResult},
+    options::SendOptions,
+    token::TokenMap,
+    types::{AddressMap, ChainEnum, ChainMap, PendingEnum, SendResult},
 };
 use crate::ledger::chain::ChainTrait;
 use crate::ledger::ecdsa::ChainAddress;
+use crate::store::with_setting;
 use b3_utils::{ledger::currency::TokenAmount, Environment, Subaccount};
 use libsecp256k1::{PublicKey, PublicKeyFormat};
 use serde::{Deserialize, Serialize};
@@ -52,13 +55,14 @@ impl Ledger {
         chain_type: &ChainEnum,
         to: String,
         amount: TokenAmount,
+        options: SendOptions,
     ) -> Result<SendResult, LedgerError> {
         let chain = self.chain(chain_type)?;
 
-        chain.send(to, amount).await
+        chain.send(to, amount, options).await
     }
 
-    pub async fn balance(&self, chain_type: ChainEnum) -> Result<Balance, LedgerError> {
+    pub async fn balance(&self, chain_type: ChainEnum) -> Result<TokenAmount, LedgerError> {
         match self.chains.get(&chain_type) {
             Some(chain) => chain.balance().await,
             None => Err(LedgerError::MissingAddress),
@@ -77,6 +81,23 @@ impl Ledger {
         addresses
     }
 
+    pub fn icp_icrc_address(&self) -> Option<String> {
+        self.chains
+            .get(&ChainEnum::ICP)
+            .and_then(|chain| chain.icp().ok())
+            .map(|icp| icp.icrc_address())
+    }
+
+    pub fn token_map(&self) -> TokenMap {
+        let mut tokens = TokenMap::new();
+
+        for (chain_type, chain) in &self.chains {
+            tokens.insert(chain_type.clone(), chain.token());
+        }
+
+        tokens
+    }
+
     pub fn pendings(&self) -> Vec<PendingEnum> {
         self.chains
             .iter()
@@ -124,6 +145,11 @@ impl Ledger {
 
                 Ok(chain)
             }
+            ChainEnum::ICRC7(canister_id) => {
+                let chain = Chain::new_icrc7_chain(canister_id, subaccount).await?;
+
+                Ok(chain)
+            }
             ChainEnum::BTC(btc_network) => {
                 let ecdsa = self.public_key()?;
 
@@ -132,9 +158,9 @@ impl Ledger {
                 Ok(btc_chain)
             }
             ChainEnum::EVM(chain_id) => {
-                let eth_address = self.eth_address()?;
+                let ecdsa = self.public_key()?;
 
-                let eth_chain = Chain::new_evm_chain(chain_id, eth_address);
+                let eth_chain = Chain::new_evm_chain(chain_id, subaccount, ecdsa.clone())?;
 
                 Ok(eth_chain)
             }
@@ -143,6 +169,13 @@ impl Ledger {
 
                 Ok(icp_chain)
             }
+            ChainEnum::CYCLES => {
+                let cycles_ledger = with_setting(|s| s.cycles_ledger());
+
+                let cycles_chain = Chain::new_cycles_chain(cycles_ledger, subaccount).await?;
+
+                Ok(cycles_chain)
+            }
         }
     }
 
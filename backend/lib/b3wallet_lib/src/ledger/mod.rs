@@ -2,12 +2,16 @@ pub mod btc;
 pub mod chain;
 pub mod ckbtc;
 pub mod config;
+pub mod cycles;
 pub mod error;
 pub mod evm;
 pub mod icp;
 pub mod icrc;
+pub mod icrc7;
 pub mod ledger;
+pub mod options;
 pub mod subaccount;
+pub mod token;
 pub mod types;
 
 pub mod ecdsa;
@@ -0,0 +1,202 @@
+use super::{error::LedgerError, token::TokenDescriptor};
+use b3_utils::{ledger::currency::TokenAmount, Subaccount};
+use candid::{CandidType, Nat};
+use serde::{Deserialize, Serialize};
+
+/// The longest memo ICRC-1 ledgers accept.
+pub const MAX_MEMO_LENGTH: usize = 32;
+
+/// Per-send options, each chain applies the ones it supports and rejects the rest.
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub struct SendOptions {
+    pub memo: Option<Vec<u8>>,
+    pub fee: Option<TokenAmount>,
+    pub created_at_time: Option<u64>,
+    pub from_subaccount: Option<Subaccount>,
+}
+
+impl SendOptions {
+    pub fn is_empty(&self) -> bool {
+        self.memo.is_none()
+            && self.fee.is_none()
+            && self.created_at_time.is_none()
+            && self.from_subaccount.is_none()
+    }
+
+    /// For chains that don't support any option.
+    pub fn reject_all(&self, chain: &str) -> Result<(), LedgerError> {
+        if let Some(option) = self.first_set() {
+            return Err(LedgerError::UnsupportedSendOption(format!(
+                "{} is not supported on {}",
+                option, chain
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// The subaccount to send from, only the one of the account itself is allowed.
+    pub fn from_subaccount(
+        &self,
+        own: Option<Subaccount>,
+    ) -> Result<Option<Subaccount>, LedgerError> {
+        match self.from_subaccount {
+            Some(ref subaccount) if Some(subaccount) != own.as_ref() => {
+                Err(LedgerError::UnsupportedSendOption(
+                    "from_subaccount must be the subaccount of the account".to_string(),
+                ))
+            }
+            _ => Ok(own),
+        }
+    }
+
+    /// For ledgers with byte memos, the memo can be at most `max_length` bytes.
+    pub fn check_memo(&self, max_length: usize) -> Result<(), LedgerError> {
+        match self.memo {
+            Some(ref memo) if memo.len() > max_length => {
+                Err(LedgerError::UnsupportedSendOption(format!(
+                    "memo must be at most {} bytes, got {}",
+                    max_length,
+                    memo.len()
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// The fee in the base units of the token, if one was given.
+    pub fn fee_base_units(&self, token: &TokenDescriptor) -> Result<Option<Nat>, LedgerError> {
+        self.fee
+            .as_ref()
+            .map(|fee| token.to_base_units(fee))
+            .transpose()
+    }
+
+    /// The memo as a big-endian `u64`, for ledgers with numeric memos (e.g. ICP).
+    pub fn memo_u64(&self) -> Result<Option<u64>, LedgerError> {
+        match self.memo {
+            None => Ok(None),
+            Some(ref memo) if memo.len() > 8 => Err(LedgerError::UnsupportedSendOption(format!(
+                "memo must be at most 8 bytes, got {}",
+                memo.len()
+            ))),
+            Some(ref memo) => {
+                let mut bytes = [0u8; 8];
+                bytes[8 - memo.len()..].copy_from_slice(memo);
+
+                Ok(Some(u64::from_be_bytes(bytes)))
+            }
+        }
+    }
+
+    fn first_set(&self) -> Option<&'static str> {
+        if self.memo.is_some() {
+            Some("memo")
+        } else if self.fee.is_some() {
+            Some("fee")
+        } else if self.created_at_time.is_some() {
+            Some("created_at_time")
+        } else if self.from_subaccount.is_some() {
+            Some("from_subaccount")
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use b3_utils::Environment;
+
+    #[test]
+    fn test_memo_u64() {
+        let options = SendOptions::default();
+        assert_eq!(options.memo_u64().unwrap(), None);
+
+        let options = SendOptions {
+            memo: Some(vec![1, 2]),
+            ..Default::default()
+        };
+        assert_eq!(options.memo_u64().unwrap(), Some(0x0102));
+
+        let options = SendOptions {
+            memo: Some(vec![0; 9]),
+            ..Default::default()
+        };
+        assert!(options.memo_u64().is_err());
+    }
+
+    #[test]
+    fn test_from_subaccount() {
+        let own = Subaccount::new(Environment::Production, 0);
+        let other = Subaccount::new(Environment::Production, 1);
+
+        assert_eq!(
+            SendOptions::default().from_subaccount(Some(own.clone())),
+            Ok(Some(own.clone()))
+        );
+
+        let options = SendOptions {
+            from_subaccount: Some(own.clone()),
+            ..Default::default()
+        };
+        assert_eq!(
+            options.from_subaccount(Some(own.clone())),
+            Ok(Some(own.clone()))
+        );
+
+        let options = SendOptions {
+            from_subaccount: Some(other),
+            ..Default::default()
+        };
+        assert!(options.from_subaccount(Some(own)).is_err());
+    }
+
+    #[test]
+    fn test_check_memo() {
+        let options = SendOptions {
+            memo: Some(vec![0; MAX_MEMO_LENGTH]),
+            ..Default::default()
+        };
+        assert!(options.check_memo(MAX_MEMO_LENGTH).is_ok());
+
+        let options = SendOptions {
+            memo: Some(vec![0; MAX_MEMO_LENGTH + 1]),
+            ..Default::default()
+        };
+        assert!(options.check_memo(MAX_MEMO_LENGTH).is_err());
+    }
+
+    #[test]
+    fn test_reject_all() {
+        assert!(SendOptions::default().reject_all("BTC").is_ok());
+
+        let options = SendOptions {
+            created_at_time: Some(1),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            options.reject_all("BTC"),
+            Err(LedgerError::UnsupportedSendOption(
+                "created_at_time is not supported on BTC".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_fee_base_units() {
+        let token = TokenDescriptor::new("ICP", 8, None);
+
+        let options = SendOptions {
+            fee: Some(TokenAmount::new(Nat::from(1u64), 4)),
+            ..Default::default()
+        };
+
+        assert_eq!(
+            options.fee_base_units(&token).unwrap(),
+            Some(Nat::from(10_000u64))
+        );
+    }
+}
@@ -9,7 +9,7 @@ mod tests {
         },
         chain::{Chain, ChainTrait},
         ledger::Ledger,
-        types::{ChainEnum, ChainMap},
+        types::{ChainEnum, ChainMap, EvmPending, PendingEnum},
     };
     use b3_utils::{ledger::AccountIdentifier, mocks::id_mock, types::CanisterId, Subaccount};
     use libsecp256k1::PublicKey;
@@ -51,9 +51,9 @@ mod tests {
 
         println!("identifier: {}", identifier);
 
-        let address = ledger.eth_address().unwrap();
+        let public_key = ledger.public_key().unwrap().clone();
 
-        let eth = Chain::new_evm_chain(1, address);
+        let eth = Chain::new_evm_chain(1, subaccount.clone(), public_key).unwrap();
 
         ledger.insert_chain(ChainEnum::EVM(1), eth);
 
@@ -333,4 +333,68 @@ mod tests {
         assert_eq!(btc_p2pkh_add.len(), 34);
         assert_eq!(btc_address.len(), 42);
     }
+
+    #[test]
+    fn test_evm_nonce_counter() {
+        let subaccount = Subaccount([0; 32]);
+
+        let ecdsa = [
+            3, 94, 114, 171, 76, 217, 209, 126, 120, 169, 209, 205, 226, 55, 21, 238, 204, 199,
+            153, 192, 65, 30, 59, 177, 153, 39, 80, 76, 185, 200, 51, 255, 218,
+        ];
+
+        let public_key = PublicKey::parse_compressed(&ecdsa).unwrap();
+
+        let mut chain = Chain::new_evm_chain(1, subaccount, public_key).unwrap();
+
+        let evm = chain.evm_mut().unwrap();
+
+        // the chain count is used until the counter is set
+        assert_eq!(evm.reserve_nonce(5), 5);
+        assert_eq!(evm.reserve_nonce(5), 6);
+
+        // the chain moved ahead, e.g. a transaction sent from elsewhere
+        assert_eq!(evm.reserve_nonce(10), 10);
+
+        // only the last nonce can be given back
+        evm.release_nonce(6);
+        assert_eq!(evm.next_nonce(0), 11);
+        evm.release_nonce(10);
+        assert_eq!(evm.next_nonce(0), 10);
+
+        evm.observe_nonce(12);
+        assert_eq!(evm.next_nonce(0), 13);
+
+        evm.reset_nonce();
+        assert_eq!(evm.next_nonce(7), 7);
+    }
+
+    #[test]
+    fn test_remove_pending_entry() {
+        let ecdsa = [
+            3, 94, 114, 171, 76, 217, 209, 126, 120, 169, 209, 205, 226, 55, 21, 238, 204, 199,
+            153, 192, 65, 30, 59, 177, 153, 39, 80, 76, 185, 200, 51, 255, 218,
+        ];
+
+        let public_key = PublicKey::parse_compressed(&ecdsa).unwrap();
+
+        let mut chain = Chain::new_evm_chain(1, Subaccount([0; 32]), public_key).unwrap();
+
+        let pending = |tx_hash: &str, nonce| EvmPending {
+            tx_hash: tx_hash.to_string(),
+            nonce,
+        };
+
+        chain.add_pending(PendingEnum::EVM(pending("0x01", 1)));
+        chain.add_pending(PendingEnum::EVM(pending("0x02", 2)));
+
+        // the stuck transaction is replaced while it is checked, which reorders the list
+        chain.evm_mut().unwrap().replace_pending(pending("0x03", 1));
+
+        assert!(!chain.remove_pending_entry(&PendingEnum::EVM(pending("0x01", 1))));
+        assert_eq!(chain.pendings().len(), 2);
+
+        assert!(chain.remove_pending_entry(&PendingEnum::EVM(pending("0x02", 2))));
+        assert_eq!(chain.pendings(), vec![PendingEnum::EVM(pending("0x03", 1))]);
+    }
 }
@@ -0,0 +1,144 @@
+use super::error::LedgerError;
+use b3_utils::ledger::currency::TokenAmount;
+use candid::{CandidType, Nat};
+use num_traits::{ToPrimitive, Zero};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::types::ChainEnum;
+
+pub type TokenMap = BTreeMap<ChainEnum, TokenDescriptor>;
+
+/// Describes the token a chain holds, so amounts can be normalized and displayed.
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct TokenDescriptor {
+    pub symbol: String,
+    pub decimals: u8,
+    pub fee: Option<Nat>,
+}
+
+impl TokenDescriptor {
+    pub fn new(symbol: &str, decimals: u8, fee: Option<Nat>) -> Self {
+        TokenDescriptor {
+            symbol: symbol.to_string(),
+            decimals,
+            fee,
+        }
+    }
+
+    /// Wrap a raw ledger amount (in base units) into a `TokenAmount`.
+    pub fn amount(&self, base_units: Nat) -> TokenAmount {
+        TokenAmount::new(base_units, self.decimals)
+    }
+
+    /// The fee of the token as a `TokenAmount`, if the chain knows it.
+    pub fn fee_amount(&self) -> Option<TokenAmount> {
+        self.fee.clone().map(|fee| self.amount(fee))
+    }
+
+    /// Convert the given amount into the base units of this token.
+    /// The amount can be expressed with any number of decimals, but it can't
+    /// be more precise than the token itself.
+    pub fn to_base_units(&self, amount: &TokenAmount) -> Result<Nat, LedgerError> {
+        if amount.decimals <= self.decimals {
+            let factor = ten_pow(self.decimals - amount.decimals);
+
+            return Ok(Nat(&amount.amount.0 * &factor.0));
+        }
+
+        let factor = ten_pow(amount.decimals - self.decimals);
+
+        if !(&amount.amount.0 % &factor.0).is_zero() {
+            return Err(LedgerError::InvalidAmountError(format!(
+                "{} has only {} decimals",
+                self.symbol, self.decimals
+            )));
+        }
+
+        Ok(Nat(&amount.amount.0 / &factor.0))
+    }
+
+    /// Same as `to_base_units`, for chains that count in `u64`.
+    pub fn to_base_units_u64(&self, amount: &TokenAmount) -> Result<u64, LedgerError> {
+        let base_units = self.to_base_units(amount)?;
+
+        base_units.0.to_u64().ok_or_else(|| {
+            LedgerError::InvalidAmountError(format!("{} is too large", base_units))
+        })
+    }
+
+    /// Format the amount with the token symbol, e.g. `1.5 ckBTC`.
+    pub fn format(&self, amount: &TokenAmount) -> String {
+        format!("{} {}", format_units(&amount.amount, amount.decimals), self.symbol)
+    }
+
+    /// Format a raw ledger amount (in base units) with the token symbol.
+    pub fn format_base_units(&self, base_units: &Nat) -> String {
+        format!("{} {}", format_units(base_units, self.decimals), self.symbol)
+    }
+}
+
+fn ten_pow(exp: u8) -> Nat {
+    (0..exp).fold(Nat::from(1u8), |acc, _| Nat(acc.0 * 10u8))
+}
+
+/// Render `amount / 10^decimals` as a decimal string without trailing zeros.
+pub fn format_units(amount: &Nat, decimals: u8) -> String {
+    let factor = ten_pow(decimals);
+
+    let whole = &amount.0 / &factor.0;
+    let fraction = &amount.0 % &factor.0;
+
+    if fraction.is_zero() {
+        return whole.to_string();
+    }
+
+    let fraction = format!("{:0>width$}", fraction.to_string(), width = decimals as usize);
+
+    format!("{}.{}", whole, fraction.trim_end_matches('0'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_units() {
+        assert_eq!(format_units(&Nat::from(150_000_000u64), 8), "1.5");
+        assert_eq!(format_units(&Nat::from(100_000_000u64), 8), "1");
+        assert_eq!(format_units(&Nat::from(1u64), 8), "0.00000001");
+        assert_eq!(format_units(&Nat::from(0u64), 8), "0");
+        assert_eq!(format_units(&Nat::from(42u64), 0), "42");
+    }
+
+    #[test]
+    fn test_format() {
+        let token = TokenDescriptor::new("ckBTC", 8, Some(Nat::from(10u64)));
+
+        let amount = token.amount(Nat::from(150_000_000u64));
+
+        assert_eq!(token.format(&amount), "1.5 ckBTC");
+        assert_eq!(token.format_base_units(&Nat::from(10u64)), "0.0000001 ckBTC");
+    }
+
+    #[test]
+    fn test_to_base_units() {
+        let token = TokenDescriptor::new("ICP", 8, None);
+
+        // 1.5 expressed with one decimal
+        let amount = TokenAmount::new(Nat::from(15u64), 1);
+        assert_eq!(token.to_base_units(&amount).unwrap(), Nat::from(150_000_000u64));
+
+        // already in base units
+        let amount = TokenAmount::new(Nat::from(12345u64), 8);
+        assert_eq!(token.to_base_units_u64(&amount).unwrap(), 12345);
+
+        // more decimals than the token, but no precision lost
+        let amount = TokenAmount::new(Nat::from(1_000_000_000u64), 10);
+        assert_eq!(token.to_base_units(&amount).unwrap(), Nat::from(10_000_000u64));
+
+        // more precise than the token
+        let amount = TokenAmount::new(Nat::from(1u64), 10);
+        assert!(token.to_base_units(&amount).is_err());
+    }
+}
@@ -1,7 +1,7 @@
 use super::{
     btc::network::BitcoinNetwork, btc::types::BtcTxId, chain::Chain, icrc::types::TxIndex,
 };
-use b3_utils::types::CanisterId;
+use b3_utils::{ledger::currency::TokenAmount, types::CanisterId};
 use candid::{CandidType, Nat};
 use enum_dispatch::enum_dispatch;
 use serde::{Deserialize, Serialize};
@@ -30,7 +30,8 @@ pub struct IcpPending {
 
 #[derive(CandidType, PartialEq, Serialize, Eq, PartialOrd, Ord, Deserialize, Clone, Debug)]
 pub struct EvmPending {
-    pub block_index: u64,
+    pub tx_hash: String,
+    pub nonce: u64,
 }
 
 #[derive(CandidType, PartialEq, Serialize, Eq, PartialOrd, Ord, Deserialize, Clone, Debug)]
@@ -73,8 +74,8 @@ impl PendingEnum {
         PendingEnum::BTC(BtcPending { txid, account })
     }
 
-    pub fn new_evm(block_index: u64) -> Self {
-        PendingEnum::EVM(EvmPending { block_index })
+    pub fn new_evm(tx_hash: String, nonce: u64) -> Self {
+        PendingEnum::EVM(EvmPending { tx_hash, nonce })
     }
 
     pub fn new_icp(block_index: u64, canister_id: String) -> Self {
@@ -92,9 +93,11 @@ pub trait PendingTrait {}
 pub enum ChainEnum {
     CKBTC(BitcoinNetwork),
     ICRC(CanisterId),
+    ICRC7(CanisterId),
     BTC(BitcoinNetwork),
     EVM(ChainId),
     ICP,
+    CYCLES,
 }
 
 impl fmt::Display for ChainEnum {
@@ -102,9 +105,11 @@ impl fmt::Display for ChainEnum {
         match self {
             ChainEnum::CKBTC(network) => write!(f, "CKBTC({})", network),
             ChainEnum::ICRC(canister_id) => write!(f, "ICRC({})", canister_id),
+            ChainEnum::ICRC7(canister_id) => write!(f, "ICRC7({})", canister_id),
             ChainEnum::BTC(network) => write!(f, "BTC({})", network),
             ChainEnum::EVM(chain_id) => write!(f, "EVM({})", chain_id),
             ChainEnum::ICP => write!(f, "ICP"),
+            ChainEnum::CYCLES => write!(f, "CYCLES"),
         }
     }
 }
@@ -118,6 +123,10 @@ impl ChainEnum {
         matches!(self, ChainEnum::ICRC(_))
     }
 
+    pub fn is_icrc7(&self) -> bool {
+        matches!(self, ChainEnum::ICRC7(_))
+    }
+
     pub fn is_btc(&self) -> bool {
         matches!(self, ChainEnum::BTC(_))
     }
@@ -129,6 +138,10 @@ impl ChainEnum {
     pub fn is_icp(&self) -> bool {
         matches!(self, ChainEnum::ICP)
     }
+
+    pub fn is_cycles(&self) -> bool {
+        matches!(self, ChainEnum::CYCLES)
+    }
 }
 
 #[derive(CandidType, Clone, Deserialize, PartialEq, Debug)]
@@ -136,8 +149,20 @@ pub enum SendResult {
     ICP(u64),
     CKBTC(TxIndex),
     ICRC(TxIndex),
+    ICRC7(TxIndex),
     BTC(BtcTxId),
-    EVM,
+    EVM(EvmPending),
+    CYCLES(TxIndex),
+}
+
+impl SendResult {
+    /// What to keep track of on the chain after sending, for chains that confirm later.
+    pub fn pending(&self) -> Option<PendingEnum> {
+        match self {
+            SendResult::EVM(pending) => Some(PendingEnum::EVM(pending.clone())),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for SendResult {
@@ -146,12 +171,42 @@ impl fmt::Display for SendResult {
             SendResult::ICP(result) => write!(f, "ICP({})", result),
             SendResult::CKBTC(tx_index) => write!(f, "CKBTC({})", tx_index),
             SendResult::ICRC(tx_index) => write!(f, "ICRC({})", tx_index),
+            SendResult::ICRC7(tx_index) => write!(f, "ICRC7({})", tx_index),
             SendResult::BTC(txid) => write!(f, "BTC({})", txid),
-            SendResult::EVM => write!(f, "EVM"),
+            SendResult::EVM(pending) => write!(f, "EVM({})", pending.tx_hash),
+            SendResult::CYCLES(tx_index) => write!(f, "CYCLES({})", tx_index),
         }
     }
 }
 
+#[derive(CandidType, Clone, Deserialize, PartialEq, Eq, Debug)]
+pub enum TransactionDirection {
+    Incoming,
+    Outgoing,
+    SelfTransfer,
+    Approve,
+}
+
+/// A ledger transaction as seen from the account, normalized across ledgers.
+#[derive(CandidType, Clone, Deserialize, PartialEq, Debug)]
+pub struct TransactionView {
+    pub id: u64,
+    pub direction: TransactionDirection,
+    pub counterparty: Option<String>,
+    pub amount: TokenAmount,
+    pub fee: Option<TokenAmount>,
+    pub memo: Option<Vec<u8>>,
+    pub timestamp: Option<u64>,
+}
+
+#[derive(CandidType, Clone, Deserialize, PartialEq, Debug)]
+pub struct TransactionPage {
+    pub transactions: Vec<TransactionView>,
+    pub balance: TokenAmount,
+    /// Pass it as `start` to get the next (older) page, `None` on the last page.
+    pub next_start: Option<u64>,
+}
+
 #[derive(CandidType, Serialize)]
 pub struct PublicKeyReply {
     pub public_key: Vec<u8>,
@@ -5,4 +5,5 @@ pub mod nonces;
 pub mod setting;
 pub mod state;
 pub mod store;
+pub mod top_up;
 pub mod types;
@@ -1,10 +1,28 @@
 use std::collections::HashMap;
 
-use crate::error::WalletError;
+use crate::{
+    error::WalletError,
+    ledger::{
+        cycles::cycles::CyclesLedger,
+        evm::{
+            error::EvmError,
+            gas::EvmGasPolicy,
+            network::{default_evm_networks, EvmNetwork},
+            rpc::EvmRpc,
+            user_operation::Erc4337Config,
+        },
+        icp::governance::Governance,
+        types::{ChainEnum, ChainId},
+    },
+    top_up::{
+        fleet::{FleetTopUpPolicy, WatchedCanister},
+        CyclesTopUpPolicy,
+    },
+};
 use b3_utils::{
     api::Management,
     ledger::{Metadata, Value},
-    types::{AppControllerMap, ControllerId, ControllerIds},
+    types::{AppControllerMap, CanisterId, ControllerId, ControllerIds},
 };
 use candid::{CandidType, Nat};
 use ic_cdk::api::management_canister::{
@@ -21,6 +39,14 @@ pub struct WalletSettings {
     pub freezing_threshold: Option<Nat>,
     pub reserved_cycles_limit: Option<Nat>,
     pub initialised: bool,
+    pub governance_canister_id: Option<CanisterId>,
+    pub cycles_ledger_canister_id: Option<CanisterId>,
+    pub cycles_top_up: Option<CyclesTopUpPolicy>,
+    pub fleet_top_up: Option<FleetTopUpPolicy>,
+    pub evm_rpc: Option<EvmRpc>,
+    pub evm_gas_policy: Option<EvmGasPolicy>,
+    pub evm_networks: Option<Vec<EvmNetwork>>,
+    pub erc4337: Option<Vec<Erc4337Config>>,
 }
 
 impl Default for WalletSettings {
@@ -33,6 +59,14 @@ impl Default for WalletSettings {
             freezing_threshold: None,
             reserved_cycles_limit: None,
             initialised: false,
+            governance_canister_id: None,
+            cycles_ledger_canister_id: None,
+            cycles_top_up: None,
+            fleet_top_up: None,
+            evm_rpc: None,
+            evm_gas_policy: None,
+            evm_networks: None,
+            erc4337: None,
         }
     }
 }
@@ -62,6 +96,186 @@ impl WalletSettings {
         &mut self.metadata
     }
 
+    /// The NNS governance canister, unless another one is configured (e.g. a local stand-in).
+    pub fn governance(&self) -> Governance {
+        match self.governance_canister_id {
+            Some(canister_id) => Governance::new(canister_id),
+            None => Governance::default(),
+        }
+    }
+
+    pub fn set_governance_canister_id(&mut self, canister_id: Option<CanisterId>) {
+        self.governance_canister_id = canister_id;
+    }
+
+    /// The cycles ledger new cycles chains use, unless another one is configured.
+    pub fn cycles_ledger(&self) -> CyclesLedger {
+        match self.cycles_ledger_canister_id {
+            Some(canister_id) => CyclesLedger(canister_id),
+            None => CyclesLedger::default(),
+        }
+    }
+
+    pub fn set_cycles_ledger_canister_id(&mut self, canister_id: Option<CanisterId>) {
+        self.cycles_ledger_canister_id = canister_id;
+    }
+
+    /// The EVM RPC canister and its default providers, unless others are configured.
+    pub fn evm_rpc(&self) -> EvmRpc {
+        self.evm_rpc.clone().unwrap_or_default()
+    }
+
+    pub fn set_evm_rpc(&mut self, evm_rpc: Option<EvmRpc>) {
+        self.evm_rpc = evm_rpc;
+    }
+
+    /// The fee policy of the EVM transactions, standard speed without a cap by default.
+    pub fn evm_gas_policy(&self) -> EvmGasPolicy {
+        self.evm_gas_policy.clone().unwrap_or_default()
+    }
+
+    pub fn set_evm_gas_policy(&mut self, policy: Option<EvmGasPolicy>) {
+        self.evm_gas_policy = policy;
+    }
+
+    /// The EVM networks accounts can hold addresses on, the ones of the RPC canister by default.
+    pub fn evm_networks(&self) -> Vec<EvmNetwork> {
+        self.evm_networks
+            .clone()
+            .unwrap_or_else(default_evm_networks)
+    }
+
+    pub fn evm_network(&self, chain_id: ChainId) -> Result<EvmNetwork, EvmError> {
+        self.evm_networks()
+            .into_iter()
+            .find(|network| network.chain_id == chain_id)
+            .ok_or(EvmError::UnknownNetwork(chain_id))
+    }
+
+    /// The name of the network, or `EVM(chain_id)` if it isn't registered.
+    pub fn evm_network_name(&self, chain_id: ChainId) -> String {
+        self.evm_network(chain_id)
+            .map(|network| network.name)
+            .unwrap_or_else(|_| format!("EVM({})", chain_id))
+    }
+
+    /// The name of the chain shown to the users, the network name for the EVM chains.
+    pub fn chain_name(&self, chain: &ChainEnum) -> String {
+        match chain {
+            ChainEnum::EVM(chain_id) => self.evm_network_name(*chain_id),
+            chain => chain.to_string(),
+        }
+    }
+
+    /// The RPC settings for the network, with its own providers if it has some.
+    pub fn evm_network_rpc(&self, chain_id: ChainId) -> EvmRpc {
+        let rpc = self.evm_rpc();
+
+        match self.evm_network(chain_id) {
+            Ok(EvmNetwork {
+                providers: Some(providers),
+                ..
+            }) => rpc.with_providers(chain_id, providers),
+            _ => rpc,
+        }
+    }
+
+    /// Add the network to the registry, or replace the one with the same chain id.
+    pub fn add_evm_network(&mut self, network: EvmNetwork) -> Result<(), EvmError> {
+        network.validate()?;
+
+        let mut networks = self.evm_networks();
+
+        networks.retain(|n| n.chain_id != network.chain_id);
+        networks.push(network);
+
+        self.evm_networks = Some(networks);
+
+        Ok(())
+    }
+
+    pub fn remove_evm_network(&mut self, chain_id: ChainId) -> Result<(), EvmError> {
+        self.evm_network(chain_id)?;
+
+        let mut networks = self.evm_networks();
+
+        networks.retain(|network| network.chain_id != chain_id);
+
+        self.evm_networks = Some(networks);
+
+        Ok(())
+    }
+
+    /// The ERC-4337 contracts of the networks smart accounts are used on.
+    pub fn erc4337_configs(&self) -> Vec<Erc4337Config> {
+        self.erc4337.clone().unwrap_or_default()
+    }
+
+    pub fn erc4337_config(&self, chain_id: ChainId) -> Result<Erc4337Config, EvmError> {
+        self.erc4337_configs()
+            .into_iter()
+            .find(|config| config.chain_id == chain_id)
+            .ok_or(EvmError::Erc4337NotConfigured(chain_id))
+    }
+
+    /// Set the contracts of the network, replacing the ones it had.
+    pub fn set_erc4337_config(&mut self, config: Erc4337Config) -> Result<(), EvmError> {
+        config.validate()?;
+
+        let mut configs = self.erc4337_configs();
+
+        configs.retain(|c| c.chain_id != config.chain_id);
+        configs.push(config);
+
+        self.erc4337 = Some(configs);
+
+        Ok(())
+    }
+
+    pub fn remove_erc4337_config(&mut self, chain_id: ChainId) -> Result<(), EvmError> {
+        self.erc4337_config(chain_id)?;
+
+        let mut configs = self.erc4337_configs();
+
+        configs.retain(|config| config.chain_id != chain_id);
+
+        self.erc4337 = Some(configs);
+
+        Ok(())
+    }
+
+    pub fn cycles_top_up(&self) -> Option<&CyclesTopUpPolicy> {
+        self.cycles_top_up.as_ref()
+    }
+
+    /// Use `configure_cycles_top_up`, the policy is validated against the accounts there.
+    pub fn set_cycles_top_up(&mut self, policy: Option<CyclesTopUpPolicy>) {
+        self.cycles_top_up = policy;
+    }
+
+    pub fn fleet_top_up(&self) -> Option<&FleetTopUpPolicy> {
+        self.fleet_top_up.as_ref()
+    }
+
+    /// Use `configure_fleet_top_up`, the policy is validated against the accounts there.
+    pub fn set_fleet_top_up(&mut self, policy: Option<FleetTopUpPolicy>) {
+        self.fleet_top_up = policy;
+    }
+
+    pub fn watch_canister(&mut self, canister: WatchedCanister) -> Result<(), WalletError> {
+        self.fleet_top_up
+            .as_mut()
+            .ok_or_else(|| WalletError::TopUpPolicyError("No fleet top up policy".to_string()))?
+            .watch(canister)
+    }
+
+    pub fn unwatch_canister(&mut self, canister_id: &CanisterId) -> Result<(), WalletError> {
+        self.fleet_top_up
+            .as_mut()
+            .ok_or_else(|| WalletError::TopUpPolicyError("No fleet top up policy".to_string()))?
+            .unwatch(canister_id)
+    }
+
     pub fn add_metadata(&mut self, key: String, value: Value) {
         self.metadata.insert(key, value);
     }
@@ -214,6 +428,7 @@ impl WalletSettings {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ledger::evm::rpc::RpcApi;
     use b3_utils::types::ControllerIds;
     use candid::Principal;
 
@@ -258,4 +473,87 @@ mod tests {
 
         println!("{:?}", controller_map); // prints: [1, 3, 5]
     }
+
+    #[test]
+    fn test_evm_networks() {
+        let mut settings = WalletSettings::default();
+
+        assert_eq!(settings.evm_network_name(42161), "Arbitrum One");
+        assert_eq!(settings.evm_network_name(56), "EVM(56)");
+        assert_eq!(settings.chain_name(&ChainEnum::EVM(42161)), "Arbitrum One");
+        assert_eq!(settings.chain_name(&ChainEnum::ICP), "ICP");
+        assert_eq!(settings.evm_network(56), Err(EvmError::UnknownNetwork(56)));
+
+        let provider = RpcApi {
+            url: "https://bsc-dataseed.bnbchain.org".to_string(),
+            headers: None,
+        };
+
+        let network = EvmNetwork {
+            chain_id: 56,
+            name: "BNB Smart Chain".to_string(),
+            symbol: "BNB".to_string(),
+            decimals: 18,
+            eip1559: false,
+            explorer_url: None,
+            providers: Some(vec![provider.clone()]),
+        };
+
+        settings.add_evm_network(network.clone()).unwrap();
+
+        assert_eq!(settings.evm_network(56), Ok(network));
+        assert_eq!(settings.evm_network_name(56), "BNB Smart Chain");
+        assert_eq!(
+            settings.evm_network_rpc(56).providers.get(&56),
+            Some(&vec![provider])
+        );
+        assert!(settings.evm_network_rpc(1).providers.is_empty());
+        // the defaults are kept next to the added network
+        assert_eq!(settings.evm_networks().len(), 6);
+
+        settings.remove_evm_network(42161).unwrap();
+
+        assert_eq!(settings.evm_network_name(42161), "EVM(42161)");
+        assert_eq!(
+            settings.remove_evm_network(42161),
+            Err(EvmError::UnknownNetwork(42161))
+        );
+    }
+
+    #[test]
+    fn test_erc4337_configs() {
+        let mut settings = WalletSettings::default();
+
+        assert_eq!(
+            settings.erc4337_config(8453),
+            Err(EvmError::Erc4337NotConfigured(8453))
+        );
+
+        let config = Erc4337Config {
+            bundler: Some(RpcApi {
+                url: "http://127.0.0.1:4337".to_string(),
+                headers: None,
+            }),
+            ..Erc4337Config::new(8453)
+        };
+
+        settings.set_erc4337_config(config.clone()).unwrap();
+        assert_eq!(settings.erc4337_config(8453), Ok(config.clone()));
+
+        // setting it again replaces it
+        settings
+            .set_erc4337_config(Erc4337Config::new(8453))
+            .unwrap();
+        assert_eq!(settings.erc4337_configs().len(), 1);
+        assert_eq!(settings.erc4337_config(8453).unwrap().bundler, None);
+
+        let invalid = Erc4337Config {
+            entry_point: "entry point".to_string(),
+            ..config
+        };
+        assert!(settings.set_erc4337_config(invalid).is_err());
+
+        settings.remove_erc4337_config(8453).unwrap();
+        assert!(settings.remove_erc4337_config(8453).is_err());
+    }
 }
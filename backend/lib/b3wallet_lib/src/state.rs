@@ -1,7 +1,11 @@
 use crate::error::WalletError;
+use crate::ledger::evm::error::EvmError;
 use crate::ledger::ledger::Ledger;
+use crate::ledger::types::{ChainEnum, ChainId};
 use crate::nonces::NonceTrait;
 use crate::setting::WalletSettings;
+use crate::top_up::fleet::{FleetTopUpReport, MAX_FLEET_REPORTS};
+use crate::top_up::{TopUpRecord, MAX_TOP_UP_RECORDS};
 use crate::types::{WalletAccountMap, WalletAccountView};
 use crate::{account::WalletAccount, types::AccountId};
 use b3_utils::api::AppAccountsNonce;
@@ -21,6 +25,10 @@ pub struct WalletState {
     pub nonces: AppAccountsNonce,
     pub settings: WalletSettings,
     pub accounts: WalletAccountMap,
+    #[serde(default)]
+    pub top_ups: Vec<TopUpRecord>,
+    #[serde(default)]
+    pub fleet_reports: Vec<FleetTopUpReport>,
 }
 
 impl Storable for WalletState {
@@ -51,6 +59,8 @@ impl WalletState {
             nonces: AppAccountsNonce::new(),
             settings: WalletSettings::default(),
             accounts,
+            top_ups: Vec::new(),
+            fleet_reports: Vec::new(),
         }
     }
 
@@ -151,6 +161,61 @@ impl WalletState {
         self.accounts.len()
     }
 
+    pub fn top_ups(&self) -> &Vec<TopUpRecord> {
+        &self.top_ups
+    }
+
+    /// Keep the last `MAX_TOP_UP_RECORDS` runs of the cycles top-up.
+    pub fn record_top_up(&mut self, record: TopUpRecord) {
+        self.top_ups.push(record);
+
+        if self.top_ups.len() > MAX_TOP_UP_RECORDS {
+            let excess = self.top_ups.len() - MAX_TOP_UP_RECORDS;
+
+            self.top_ups.drain(..excess);
+        }
+    }
+
+    pub fn fleet_reports(&self) -> &Vec<FleetTopUpReport> {
+        &self.fleet_reports
+    }
+
+    /// Keep the last `MAX_FLEET_REPORTS` reports of the fleet top-up.
+    pub fn record_fleet_report(&mut self, report: FleetTopUpReport) {
+        self.fleet_reports.push(report);
+
+        if self.fleet_reports.len() > MAX_FLEET_REPORTS {
+            let excess = self.fleet_reports.len() - MAX_FLEET_REPORTS;
+
+            self.fleet_reports.drain(..excess);
+        }
+    }
+
+    /// A network can leave the registry once no account has an address on it.
+    pub fn check_evm_network_removal(&self, chain_id: ChainId) -> Result<(), EvmError> {
+        self.settings.evm_network(chain_id)?;
+
+        let chain = ChainEnum::EVM(chain_id);
+
+        let in_use = self
+            .accounts
+            .values()
+            .any(|account| account.ledger().chain(&chain).is_ok());
+
+        if in_use {
+            return Err(EvmError::NetworkInUse(chain_id));
+        }
+
+        Ok(())
+    }
+
+    /// Remove the network from the registry, unless an account still has an address on it.
+    pub fn remove_evm_network(&mut self, chain_id: ChainId) -> Result<(), EvmError> {
+        self.check_evm_network_removal(chain_id)?;
+
+        self.settings.remove_evm_network(chain_id)
+    }
+
     pub fn account_status(&self) -> AppAccountsNonce {
         self.nonces.clone().into()
     }
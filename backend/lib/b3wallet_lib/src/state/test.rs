@@ -1,8 +1,15 @@
 #[cfg(test)]
 mod test {
-    use b3_utils::{nonce::Nonce, Environment};
+    use b3_utils::{nonce::Nonce, Environment, NanoTimeStamp, Subaccount};
+    use libsecp256k1::PublicKey;
 
-    use crate::{account::WalletAccount, nonces::NonceTrait, state::WalletState};
+    use crate::{
+        account::WalletAccount,
+        ledger::{chain::Chain, evm::error::EvmError, types::ChainEnum},
+        nonces::NonceTrait,
+        state::WalletState,
+        top_up::{TopUpOutcome, TopUpRecord, MAX_TOP_UP_RECORDS},
+    };
 
     #[test]
     fn test_init_wallet() {
@@ -162,4 +169,52 @@ mod test {
 
         assert_eq!(nonce, Nonce(1));
     }
+
+    #[test]
+    fn test_record_top_up() {
+        let mut state = WalletState::new();
+
+        for cycles_balance in 0..(MAX_TOP_UP_RECORDS as u128 + 5) {
+            state.record_top_up(TopUpRecord {
+                timestamp: NanoTimeStamp(0),
+                cycles_balance,
+                outcome: TopUpOutcome::Skipped,
+            });
+        }
+
+        assert_eq!(state.top_ups().len(), MAX_TOP_UP_RECORDS);
+
+        // the oldest runs are dropped first
+        assert_eq!(state.top_ups()[0].cycles_balance, 5);
+    }
+
+    #[test]
+    fn test_remove_evm_network() {
+        let mut state = WalletState::new();
+
+        let ecdsa = [
+            3, 94, 114, 171, 76, 217, 209, 126, 120, 169, 209, 205, 226, 55, 21, 238, 204, 199,
+            153, 192, 65, 30, 59, 177, 153, 39, 80, 76, 185, 200, 51, 255, 218,
+        ];
+
+        let public_key = PublicKey::parse_compressed(&ecdsa).unwrap();
+
+        let chain = Chain::new_evm_chain(42161, Subaccount([0; 32]), public_key).unwrap();
+
+        state
+            .account_mut(&"-default".to_string())
+            .unwrap()
+            .ledger_mut()
+            .insert_chain(ChainEnum::EVM(42161), chain);
+
+        // the account address on the network would lose its name and RPC providers
+        assert_eq!(
+            state.remove_evm_network(42161),
+            Err(EvmError::NetworkInUse(42161))
+        );
+        assert!(state.settings.evm_network(42161).is_ok());
+
+        assert_eq!(state.remove_evm_network(10), Ok(()));
+        assert!(state.settings.evm_network(10).is_err());
+    }
 }
@@ -0,0 +1,334 @@
+use crate::{
+    error::WalletError,
+    ledger::{
+        chain::ChainTrait,
+        icp::icp::IcpChain,
+        types::{ChainEnum, PendingEnum},
+    },
+    store::{with_chain, with_chain_mut, with_setting_mut},
+    types::AccountId,
+};
+use b3_utils::{
+    ledger::{currency::ICPToken, NotifyTopUpResult, TransferBlockIndex},
+    types::CanisterId,
+    NanoTimeStamp,
+};
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+use std::{cell::RefCell, collections::BTreeSet};
+
+pub mod fleet;
+
+thread_local! {
+    /// The canisters being topped up, by a timer or a manual trigger.
+    static TOPPING_UP: RefCell<BTreeSet<CanisterId>> = RefCell::new(BTreeSet::new());
+}
+
+/// The shortest interval between two checks of the cycles balance.
+pub const MIN_TOP_UP_INTERVAL_SECS: u64 = 60;
+
+/// How many top-up runs are kept in the history.
+pub const MAX_TOP_UP_RECORDS: usize = 100;
+
+/// Keep the wallet canister funded from the ICP of one of its own accounts.
+#[derive(CandidType, Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct CyclesTopUpPolicy {
+    /// The account whose ICP pays for the top-ups.
+    pub account_id: AccountId,
+    /// Top up when the cycles balance drops below this level.
+    pub min_cycles: u128,
+    /// The ICP converted to cycles on each top-up.
+    pub amount: ICPToken,
+    /// How often the cycles balance is checked.
+    pub interval_secs: u64,
+}
+
+/// Validate the policy and store it, `None` turns the top-ups off.
+/// The source account is looked up before the settings are borrowed mutably.
+pub fn configure_cycles_top_up(policy: Option<CyclesTopUpPolicy>) -> Result<(), WalletError> {
+    if let Some(ref policy) = policy {
+        policy.validate()?;
+    }
+
+    with_setting_mut(|s| s.set_cycles_top_up(policy));
+
+    Ok(())
+}
+
+impl CyclesTopUpPolicy {
+    /// Reads the accounts, so it can't run while the wallet state is borrowed.
+    pub fn validate(&self) -> Result<(), WalletError> {
+        if self.amount.is_zero() {
+            return Err(WalletError::TopUpPolicyError(
+                "Top up amount is zero".to_string(),
+            ));
+        }
+
+        if self.interval_secs < MIN_TOP_UP_INTERVAL_SECS {
+            return Err(WalletError::TopUpPolicyError(format!(
+                "Interval must be at least {} seconds",
+                MIN_TOP_UP_INTERVAL_SECS
+            )));
+        }
+
+        with_chain(&self.account_id, &ChainEnum::ICP, |_| ()).map_err(|err| {
+            WalletError::TopUpPolicyError(format!("Invalid source account: {}", err))
+        })
+    }
+
+    pub fn needs_top_up(&self, cycles_balance: u128) -> bool {
+        cycles_balance < self.min_cycles
+    }
+
+    /// Check the cycles balance of the canister and top it up if needed.
+    /// A top-up whose notification failed earlier is finished first, so no
+    /// more ICP is spent until the cycles of the previous one are minted.
+    pub async fn run(&self, canister_id: CanisterId, cycles_balance: u128) -> TopUpOutcome {
+        let _guard = match TopUpGuard::acquire(canister_id) {
+            Some(guard) => guard,
+            None => return TopUpOutcome::InProgress,
+        };
+
+        let icp = match with_chain(&self.account_id, &ChainEnum::ICP, |chain| chain.icp()) {
+            Ok(Ok(icp)) => icp,
+            Ok(Err(err)) => return TopUpOutcome::Failed(err.to_string()),
+            Err(err) => return TopUpOutcome::Failed(err.to_string()),
+        };
+
+        if let Some(block_index) = pending_top_up(&icp, canister_id) {
+            return self.notify(&icp, canister_id, block_index, true).await;
+        }
+
+        if !self.needs_top_up(cycles_balance) {
+            return TopUpOutcome::Skipped;
+        }
+
+        let block_index = match icp.top_up(canister_id, self.amount.clone()).await {
+            Ok(block_index) => block_index,
+            Err(err) => return TopUpOutcome::Failed(err.to_string()),
+        };
+
+        self.notify(&icp, canister_id, block_index, false).await
+    }
+
+    async fn notify(
+        &self,
+        icp: &IcpChain,
+        canister_id: CanisterId,
+        block_index: TransferBlockIndex,
+        resumed: bool,
+    ) -> TopUpOutcome {
+        match notify_top_up(&self.account_id, icp, canister_id, block_index, resumed).await {
+            Ok(cycles) => TopUpOutcome::ToppedUp {
+                block_index,
+                cycles,
+                resumed,
+            },
+            Err(error) => TopUpOutcome::NotifyFailed { block_index, error },
+        }
+    }
+}
+
+/// Held while a canister is topped up, so a second run can't top it up at the same time.
+pub(crate) struct TopUpGuard(CanisterId);
+
+impl TopUpGuard {
+    /// `None` while another run holds the canister.
+    pub(crate) fn acquire(canister_id: CanisterId) -> Option<Self> {
+        let acquired = TOPPING_UP.with(|topping_up| topping_up.borrow_mut().insert(canister_id));
+
+        acquired.then_some(TopUpGuard(canister_id))
+    }
+}
+
+impl Drop for TopUpGuard {
+    fn drop(&mut self) {
+        TOPPING_UP.with(|topping_up| topping_up.borrow_mut().remove(&self.0));
+    }
+}
+
+/// The ICP top-up of `canister_id` still waiting to be notified, if any.
+pub(crate) fn pending_top_up(
+    icp: &IcpChain,
+    canister_id: CanisterId,
+) -> Option<TransferBlockIndex> {
+    let canister_text = canister_id.to_text();
+
+    icp.pendings
+        .iter()
+        .find(|pending| pending.canister_id == canister_text)
+        .map(|pending| pending.block_index)
+}
+
+/// Ask the cycles minting canister to mint the cycles of a top-up transfer.
+/// A new top-up that fails is kept as an `IcpPending` of the account, and
+/// a resumed one is removed from there once the cycles are minted.
+pub(crate) async fn notify_top_up(
+    account_id: &AccountId,
+    icp: &IcpChain,
+    canister_id: CanisterId,
+    block_index: TransferBlockIndex,
+    resumed: bool,
+) -> Result<u128, String> {
+    let error = match icp.notify_top_up(canister_id, block_index).await {
+        Ok(NotifyTopUpResult::Ok(cycles)) => {
+            if resumed {
+                with_chain_mut(account_id, ChainEnum::ICP, |chain| {
+                    if let Ok(icp) = chain.icp_mut() {
+                        icp.pendings
+                            .retain(|pending| pending.block_index != block_index);
+                    }
+                })
+                .ok();
+            }
+
+            return Ok(cycles);
+        }
+        Ok(NotifyTopUpResult::Err(err)) => err.to_string(),
+        Err(err) => err.to_string(),
+    };
+
+    if !resumed {
+        let pending = PendingEnum::new_icp(block_index, canister_id.to_text());
+
+        // the account was there a moment ago, nothing to do if it was removed since
+        with_chain_mut(account_id, ChainEnum::ICP, |chain| {
+            chain.add_pending(pending)
+        })
+        .ok();
+    }
+
+    Err(error)
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub enum TopUpOutcome {
+    /// The balance was above the minimum.
+    Skipped,
+    /// The cycles were minted, `resumed` when finishing an earlier top-up.
+    ToppedUp {
+        block_index: TransferBlockIndex,
+        cycles: u128,
+        resumed: bool,
+    },
+    /// The ICP was sent but the cycles are not minted yet, retried on the next run.
+    NotifyFailed {
+        block_index: TransferBlockIndex,
+        error: String,
+    },
+    /// Another run, timer or manual, is topping up the canister.
+    InProgress,
+    /// Nothing was sent.
+    Failed(String),
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct TopUpRecord {
+    pub timestamp: NanoTimeStamp,
+    pub cycles_balance: u128,
+    pub outcome: TopUpOutcome,
+}
+
+impl TopUpRecord {
+    pub fn new(cycles_balance: u128, outcome: TopUpOutcome) -> Self {
+        TopUpRecord {
+            timestamp: NanoTimeStamp::now(),
+            cycles_balance,
+            outcome,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::with_setting;
+
+    fn default_policy() -> CyclesTopUpPolicy {
+        CyclesTopUpPolicy {
+            account_id: "-default".to_string(),
+            min_cycles: 1_000_000_000_000,
+            amount: ICPToken::from_e8s(100_000_000),
+            interval_secs: 3600,
+        }
+    }
+
+    #[test]
+    fn test_needs_top_up() {
+        let policy = default_policy();
+
+        assert!(policy.needs_top_up(999_999_999_999));
+        assert!(!policy.needs_top_up(1_000_000_000_000));
+    }
+
+    #[test]
+    fn test_validate() {
+        let policy = CyclesTopUpPolicy {
+            amount: ICPToken::from_e8s(0),
+            ..default_policy()
+        };
+
+        assert_eq!(
+            policy.validate(),
+            Err(WalletError::TopUpPolicyError(
+                "Top up amount is zero".to_string()
+            ))
+        );
+
+        let policy = CyclesTopUpPolicy {
+            interval_secs: 1,
+            ..default_policy()
+        };
+
+        assert_eq!(
+            policy.validate(),
+            Err(WalletError::TopUpPolicyError(
+                "Interval must be at least 60 seconds".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_top_up_guard() {
+        let canister_id = CanisterId::from_slice(&[7; 29]);
+
+        let guard = TopUpGuard::acquire(canister_id).unwrap();
+
+        assert!(TopUpGuard::acquire(canister_id).is_none());
+        assert!(TopUpGuard::acquire(CanisterId::from_slice(&[8; 29])).is_some());
+
+        drop(guard);
+
+        assert!(TopUpGuard::acquire(canister_id).is_some());
+    }
+
+    #[test]
+    fn test_configure_cycles_top_up() {
+        configure_cycles_top_up(Some(default_policy())).unwrap();
+
+        assert_eq!(
+            with_setting(|s| s.cycles_top_up().cloned()),
+            Some(default_policy())
+        );
+
+        let policy = CyclesTopUpPolicy {
+            account_id: "-missing".to_string(),
+            ..default_policy()
+        };
+
+        assert!(matches!(
+            configure_cycles_top_up(Some(policy)),
+            Err(WalletError::TopUpPolicyError(_))
+        ));
+
+        // the invalid policy didn't replace the previous one
+        assert_eq!(
+            with_setting(|s| s.cycles_top_up().cloned()),
+            Some(default_policy())
+        );
+
+        configure_cycles_top_up(None).unwrap();
+
+        assert_eq!(with_setting(|s| s.cycles_top_up().cloned()), None);
+    }
+}
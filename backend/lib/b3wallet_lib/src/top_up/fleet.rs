@@ -0,0 +1,689 @@
+use crate::{
+    error::WalletError,
+    ledger::{
+        chain::{Chain, ChainTrait},
+        cycles::cycles::CyclesChain,
+        icp::{
+            cmc::{icp_xdr_conversion_rate, IcpXdrConversionRate},
+            icp::IcpChain,
+        },
+        token::TokenDescriptor,
+        types::ChainEnum,
+    },
+    store::{with_chain, with_setting_mut},
+    types::AccountId,
+};
+use b3_utils::{
+    api::Management,
+    ledger::currency::{ICPToken, TokenAmount},
+    types::CanisterId,
+    NanoTimeStamp,
+};
+use candid::{CandidType, Nat};
+use ic_cdk::api::call::call_raw;
+use num_traits::ToPrimitive;
+use serde::{Deserialize, Serialize};
+
+use super::{notify_top_up, pending_top_up, TopUpGuard, MIN_TOP_UP_INTERVAL_SECS};
+
+/// How many fleet top-up reports are kept.
+pub const MAX_FLEET_REPORTS: usize = 50;
+
+/// A canister kept between a minimum and a target cycles level.
+#[derive(CandidType, Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct WatchedCanister {
+    pub canister_id: CanisterId,
+    /// Top up when the cycles balance drops below this level.
+    pub min_cycles: u128,
+    /// The cycles balance a top-up brings the canister back to.
+    pub target_cycles: u128,
+    /// A query method returning the cycles balance as a `nat` or `nat64`, for
+    /// canisters the wallet doesn't control. `canister_status` is used if not set.
+    pub balance_query: Option<String>,
+}
+
+impl WatchedCanister {
+    pub fn validate(&self) -> Result<(), WalletError> {
+        if self.target_cycles <= self.min_cycles {
+            return Err(WalletError::TopUpPolicyError(format!(
+                "Target cycles of {} must be above the minimum",
+                self.canister_id
+            )));
+        }
+
+        if let Some(ref method) = self.balance_query {
+            if method.is_empty() {
+                return Err(WalletError::TopUpPolicyError(format!(
+                    "Empty balance query for {}",
+                    self.canister_id
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The cycles to add to reach the target, `None` while above the minimum.
+    pub fn cycles_needed(&self, cycles_balance: u128) -> Option<u128> {
+        if cycles_balance >= self.min_cycles {
+            return None;
+        }
+
+        Some(self.target_cycles - cycles_balance)
+    }
+
+    pub async fn cycles_balance(&self) -> Result<u128, String> {
+        match self.balance_query {
+            Some(ref method) => {
+                let arg = candid::encode_args(()).unwrap();
+
+                let reply = call_raw(self.canister_id, method, arg, 0)
+                    .await
+                    .map_err(|err| err.1)?;
+
+                decode_cycles_balance(&reply)
+            }
+            None => {
+                let status = Management::canister_status(self.canister_id)
+                    .await
+                    .map_err(|err| err.to_string())?;
+
+                status
+                    .cycles
+                    .0
+                    .to_u128()
+                    .ok_or_else(|| format!("Invalid cycles balance: {}", status.cycles))
+            }
+        }
+    }
+}
+
+/// Decode a cycles balance replied as either a `nat` or a `nat64`.
+pub fn decode_cycles_balance(reply: &[u8]) -> Result<u128, String> {
+    if let Ok(balance) = candid::decode_one::<Nat>(reply) {
+        return balance
+            .0
+            .to_u128()
+            .ok_or_else(|| format!("Invalid cycles balance: {}", balance));
+    }
+
+    candid::decode_one::<u64>(reply)
+        .map(u128::from)
+        .map_err(|err| err.to_string())
+}
+
+/// Validate the policy and store it, `None` turns the fleet top-ups off.
+/// The source account is looked up before the settings are borrowed mutably.
+pub fn configure_fleet_top_up(policy: Option<FleetTopUpPolicy>) -> Result<(), WalletError> {
+    if let Some(ref policy) = policy {
+        policy.validate()?;
+    }
+
+    with_setting_mut(|s| s.set_fleet_top_up(policy));
+
+    Ok(())
+}
+
+/// Keep a fleet of canisters funded from the ICP or the cycles of one account.
+#[derive(CandidType, Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct FleetTopUpPolicy {
+    pub account_id: AccountId,
+    /// `ICP` tops up through the cycles minting canister,
+    /// `CYCLES` withdraws from the cycles ledger.
+    pub chain: ChainEnum,
+    /// How often the fleet is checked.
+    pub interval_secs: u64,
+    /// The most cycles a canister gets in a run. The balances a `balance_query`
+    /// replies with come from the canister itself and can't be trusted.
+    pub max_cycles_per_canister: u128,
+    /// The most cycles the whole fleet gets in a run.
+    pub max_cycles_per_run: u128,
+    pub canisters: Vec<WatchedCanister>,
+}
+
+impl FleetTopUpPolicy {
+    /// Reads the accounts, so it can't run while the wallet state is borrowed.
+    pub fn validate(&self) -> Result<(), WalletError> {
+        if !self.chain.is_icp() && !self.chain.is_cycles() {
+            return Err(WalletError::TopUpPolicyError(format!(
+                "Cannot top up from {}",
+                self.chain
+            )));
+        }
+
+        if self.interval_secs < MIN_TOP_UP_INTERVAL_SECS {
+            return Err(WalletError::TopUpPolicyError(format!(
+                "Interval must be at least {} seconds",
+                MIN_TOP_UP_INTERVAL_SECS
+            )));
+        }
+
+        if self.max_cycles_per_canister == 0 || self.max_cycles_per_run == 0 {
+            return Err(WalletError::TopUpPolicyError(
+                "Spend caps must be above zero".to_string(),
+            ));
+        }
+
+        for (index, canister) in self.canisters.iter().enumerate() {
+            canister.validate()?;
+
+            if self.canisters[..index]
+                .iter()
+                .any(|other| other.canister_id == canister.canister_id)
+            {
+                return Err(WalletError::TopUpPolicyError(format!(
+                    "{} is watched twice",
+                    canister.canister_id
+                )));
+            }
+        }
+
+        with_chain(&self.account_id, &self.chain, |_| ()).map_err(|err| {
+            WalletError::TopUpPolicyError(format!("Invalid source account: {}", err))
+        })
+    }
+
+    /// The cycles to send a canister needing `cycles_needed`, once `spent` were
+    /// sent to the others in the run.
+    pub fn capped_cycles(&self, cycles_needed: u128, spent: u128) -> u128 {
+        cycles_needed
+            .min(self.max_cycles_per_canister)
+            .min(self.max_cycles_per_run.saturating_sub(spent))
+    }
+
+    /// Add a canister to the fleet, or update it if it's already watched.
+    pub fn watch(&mut self, canister: WatchedCanister) -> Result<(), WalletError> {
+        canister.validate()?;
+
+        match self
+            .canisters
+            .iter_mut()
+            .find(|watched| watched.canister_id == canister.canister_id)
+        {
+            Some(watched) => *watched = canister,
+            None => self.canisters.push(canister),
+        }
+
+        Ok(())
+    }
+
+    pub fn unwatch(&mut self, canister_id: &CanisterId) -> Result<(), WalletError> {
+        let len = self.canisters.len();
+
+        self.canisters
+            .retain(|watched| &watched.canister_id != canister_id);
+
+        if self.canisters.len() == len {
+            return Err(WalletError::TopUpPolicyError(format!(
+                "{} is not watched",
+                canister_id
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Check every watched canister and top up the low ones, one after the other,
+    /// skipping the ones another run is topping up.
+    pub async fn run(&self) -> FleetTopUpReport {
+        let mut report = FleetTopUpReport::new(self.chain.clone());
+
+        let chain = match with_chain(&self.account_id, &self.chain, |chain| chain.clone()) {
+            Ok(chain) => chain,
+            Err(err) => {
+                for canister in self.canisters.iter() {
+                    report.add(
+                        canister.canister_id,
+                        None,
+                        FleetCanisterOutcome::Failed(err.to_string()),
+                    );
+                }
+
+                return report;
+            }
+        };
+
+        let mut funder = match chain {
+            Chain::IcpChain(icp) => Funder::Icp {
+                account_id: self.account_id.clone(),
+                icp,
+                rate: None,
+            },
+            Chain::CyclesChain(cycles) => Funder::Cycles(cycles),
+            _ => {
+                let error = format!("Cannot top up from {}", self.chain);
+
+                for canister in self.canisters.iter() {
+                    report.add(
+                        canister.canister_id,
+                        None,
+                        FleetCanisterOutcome::Failed(error.clone()),
+                    );
+                }
+
+                return report;
+            }
+        };
+
+        report.cost = funder.token().amount(0u8.into());
+
+        let mut spent = 0;
+
+        for canister in self.canisters.iter() {
+            let _guard = match TopUpGuard::acquire(canister.canister_id) {
+                Some(guard) => guard,
+                None => {
+                    report.add(canister.canister_id, None, FleetCanisterOutcome::InProgress);
+
+                    continue;
+                }
+            };
+
+            // finish the earlier top-up before sending more
+            if let Some(block_index) = funder.pending_top_up(canister.canister_id) {
+                let outcome = funder.resume(canister.canister_id, block_index).await;
+
+                report.add(canister.canister_id, None, outcome);
+
+                continue;
+            }
+
+            let cycles_balance = match canister.cycles_balance().await {
+                Ok(cycles_balance) => cycles_balance,
+                Err(err) => {
+                    report.add(
+                        canister.canister_id,
+                        None,
+                        FleetCanisterOutcome::Failed(err),
+                    );
+
+                    continue;
+                }
+            };
+
+            let outcome = match canister.cycles_needed(cycles_balance) {
+                Some(cycles_needed) => match self.capped_cycles(cycles_needed, spent) {
+                    0 => FleetCanisterOutcome::CapReached,
+                    cycles => {
+                        let outcome = funder.top_up(canister.canister_id, cycles).await;
+
+                        if outcome.cost().is_some() {
+                            spent += cycles;
+                        }
+
+                        outcome
+                    }
+                },
+                None => FleetCanisterOutcome::Healthy,
+            };
+
+            report.add(canister.canister_id, Some(cycles_balance), outcome);
+        }
+
+        report
+    }
+}
+
+enum Funder {
+    Icp {
+        account_id: AccountId,
+        icp: IcpChain,
+        rate: Option<IcpXdrConversionRate>,
+    },
+    Cycles(CyclesChain),
+}
+
+impl Funder {
+    fn token(&self) -> TokenDescriptor {
+        match self {
+            Funder::Icp { icp, .. } => icp.token(),
+            Funder::Cycles(cycles) => cycles.token(),
+        }
+    }
+
+    fn pending_top_up(&self, canister_id: CanisterId) -> Option<u64> {
+        match self {
+            Funder::Icp { icp, .. } => pending_top_up(icp, canister_id),
+            Funder::Cycles(_) => None,
+        }
+    }
+
+    async fn resume(&self, canister_id: CanisterId, block_index: u64) -> FleetCanisterOutcome {
+        let (account_id, icp) = match self {
+            Funder::Icp {
+                account_id, icp, ..
+            } => (account_id, icp),
+            Funder::Cycles(_) => return FleetCanisterOutcome::Healthy,
+        };
+
+        // paid for on the run that sent the ICP
+        let cost = self.token().amount(0u8.into());
+
+        match notify_top_up(account_id, icp, canister_id, block_index, true).await {
+            Ok(cycles) => FleetCanisterOutcome::ToppedUp {
+                block_index: block_index.into(),
+                cycles,
+                cost,
+            },
+            Err(error) => FleetCanisterOutcome::NotifyFailed {
+                block_index,
+                error,
+                cost,
+            },
+        }
+    }
+
+    async fn top_up(&mut self, canister_id: CanisterId, cycles: u128) -> FleetCanisterOutcome {
+        let token = self.token();
+
+        match self {
+            Funder::Icp {
+                account_id,
+                icp,
+                rate,
+            } => {
+                // fetched once per run, and only when a canister is low
+                if rate.is_none() {
+                    match icp_xdr_conversion_rate().await {
+                        Ok(fetched) => *rate = Some(fetched),
+                        Err(err) => return FleetCanisterOutcome::Failed(err.to_string()),
+                    }
+                }
+
+                let e8s = match rate.as_ref().map(|rate| rate.cycles_to_e8s(cycles)) {
+                    Some(Ok(e8s)) => e8s,
+                    Some(Err(err)) => return FleetCanisterOutcome::Failed(err.to_string()),
+                    None => return FleetCanisterOutcome::Failed("No conversion rate".to_string()),
+                };
+
+                let block_index = match icp.top_up(canister_id, ICPToken::from_e8s(e8s)).await {
+                    Ok(block_index) => block_index,
+                    Err(err) => return FleetCanisterOutcome::Failed(err.to_string()),
+                };
+
+                let cost = token.amount((e8s + icp.fee.e8s()).into());
+
+                match notify_top_up(account_id, icp, canister_id, block_index, false).await {
+                    Ok(cycles) => FleetCanisterOutcome::ToppedUp {
+                        block_index: block_index.into(),
+                        cycles,
+                        cost,
+                    },
+                    Err(error) => FleetCanisterOutcome::NotifyFailed {
+                        block_index,
+                        error,
+                        cost,
+                    },
+                }
+            }
+            Funder::Cycles(chain) => match chain.withdraw(canister_id, cycles).await {
+                Ok(block_index) => {
+                    let fee = chain.fee.clone().unwrap_or_else(|| 0u8.into());
+
+                    FleetCanisterOutcome::ToppedUp {
+                        block_index,
+                        cycles,
+                        cost: token.amount(Nat::from(cycles) + fee),
+                    }
+                }
+                Err(err) => FleetCanisterOutcome::Failed(err.to_string()),
+            },
+        }
+    }
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub enum FleetCanisterOutcome {
+    /// The balance was above the minimum.
+    Healthy,
+    ToppedUp {
+        block_index: Nat,
+        cycles: u128,
+        cost: TokenAmount,
+    },
+    /// The ICP was sent but the cycles are not minted yet, retried on the next run.
+    NotifyFailed {
+        block_index: u64,
+        error: String,
+        cost: TokenAmount,
+    },
+    /// Low, but the run already sent all the cycles it may.
+    CapReached,
+    /// Another run is topping up the canister.
+    InProgress,
+    /// Nothing was sent.
+    Failed(String),
+}
+
+impl FleetCanisterOutcome {
+    pub fn cost(&self) -> Option<&TokenAmount> {
+        match self {
+            FleetCanisterOutcome::ToppedUp { cost, .. } => Some(cost),
+            FleetCanisterOutcome::NotifyFailed { cost, .. } => Some(cost),
+            _ => None,
+        }
+    }
+
+    pub fn cycles(&self) -> u128 {
+        match self {
+            FleetCanisterOutcome::ToppedUp { cycles, .. } => *cycles,
+            _ => 0,
+        }
+    }
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct FleetCanisterReport {
+    pub canister_id: CanisterId,
+    /// Not set when the balance couldn't be read or wasn't needed.
+    pub cycles_balance: Option<u128>,
+    pub outcome: FleetCanisterOutcome,
+}
+
+/// What a run of the fleet top-up did, and what it cost.
+#[derive(CandidType, Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct FleetTopUpReport {
+    pub timestamp: NanoTimeStamp,
+    pub chain: ChainEnum,
+    pub canisters: Vec<FleetCanisterReport>,
+    /// The cycles added to the fleet.
+    pub cycles: u128,
+    /// The amount spent from the account, fees included.
+    pub cost: TokenAmount,
+}
+
+impl FleetTopUpReport {
+    pub fn new(chain: ChainEnum) -> Self {
+        FleetTopUpReport {
+            timestamp: NanoTimeStamp::now(),
+            chain,
+            canisters: Vec::new(),
+            cycles: 0,
+            cost: TokenAmount::new(0u8.into(), 0),
+        }
+    }
+
+    pub fn add(
+        &mut self,
+        canister_id: CanisterId,
+        cycles_balance: Option<u128>,
+        outcome: FleetCanisterOutcome,
+    ) {
+        self.cycles += outcome.cycles();
+
+        if let Some(cost) = outcome.cost() {
+            self.cost = TokenAmount::new(
+                self.cost.amount.clone() + cost.amount.clone(),
+                cost.decimals,
+            );
+        }
+
+        self.canisters.push(FleetCanisterReport {
+            canister_id,
+            cycles_balance,
+            outcome,
+        });
+    }
+
+    pub fn topped_up(&self) -> Vec<&FleetCanisterReport> {
+        self.canisters
+            .iter()
+            .filter(|canister| matches!(canister.outcome, FleetCanisterOutcome::ToppedUp { .. }))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::with_setting;
+    use candid::Principal;
+
+    fn canister() -> WatchedCanister {
+        WatchedCanister {
+            canister_id: Principal::from_text("rrkah-fqaaa-aaaaa-aaaaq-cai").unwrap(),
+            min_cycles: 1_000,
+            target_cycles: 5_000,
+            balance_query: None,
+        }
+    }
+
+    #[test]
+    fn test_cycles_needed() {
+        let canister = canister();
+
+        assert_eq!(canister.cycles_needed(1_000), None);
+        assert_eq!(canister.cycles_needed(999), Some(4_001));
+        assert_eq!(canister.cycles_needed(0), Some(5_000));
+    }
+
+    #[test]
+    fn test_validate_watched_canister() {
+        assert!(canister().validate().is_ok());
+
+        let canister = WatchedCanister {
+            target_cycles: 1_000,
+            ..canister()
+        };
+
+        assert!(canister.validate().is_err());
+    }
+
+    #[test]
+    fn test_decode_cycles_balance() {
+        let nat = candid::encode_one(Nat::from(42u64)).unwrap();
+        assert_eq!(decode_cycles_balance(&nat).unwrap(), 42);
+
+        let nat64 = candid::encode_one(42u64).unwrap();
+        assert_eq!(decode_cycles_balance(&nat64).unwrap(), 42);
+
+        let text = candid::encode_one("42").unwrap();
+        assert!(decode_cycles_balance(&text).is_err());
+    }
+
+    fn policy() -> FleetTopUpPolicy {
+        FleetTopUpPolicy {
+            account_id: "-default".to_string(),
+            chain: ChainEnum::ICP,
+            interval_secs: 3600,
+            max_cycles_per_canister: 4_000,
+            max_cycles_per_run: 10_000,
+            canisters: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_capped_cycles() {
+        let policy = policy();
+
+        assert_eq!(policy.capped_cycles(1_000, 0), 1_000);
+        // a canister claiming an empty balance gets the per canister cap at most
+        assert_eq!(policy.capped_cycles(1_000_000, 0), 4_000);
+        // and the fleet what is left of the per run cap
+        assert_eq!(policy.capped_cycles(4_000, 8_000), 2_000);
+        assert_eq!(policy.capped_cycles(4_000, 10_000), 0);
+        assert_eq!(policy.capped_cycles(4_000, 12_000), 0);
+    }
+
+    #[test]
+    fn test_configure_fleet_top_up() {
+        let valid = FleetTopUpPolicy {
+            canisters: vec![canister()],
+            ..policy()
+        };
+
+        configure_fleet_top_up(Some(valid.clone())).unwrap();
+
+        assert_eq!(with_setting(|s| s.fleet_top_up().cloned()), Some(valid));
+
+        let invalid = FleetTopUpPolicy {
+            max_cycles_per_run: 0,
+            ..policy()
+        };
+        assert!(configure_fleet_top_up(Some(invalid)).is_err());
+
+        let missing = FleetTopUpPolicy {
+            account_id: "-missing".to_string(),
+            ..policy()
+        };
+        assert!(configure_fleet_top_up(Some(missing)).is_err());
+
+        configure_fleet_top_up(None).unwrap();
+
+        assert_eq!(with_setting(|s| s.fleet_top_up().cloned()), None);
+    }
+
+    #[test]
+    fn test_watch_and_unwatch() {
+        let mut policy = policy();
+
+        policy.watch(canister()).unwrap();
+        policy
+            .watch(WatchedCanister {
+                target_cycles: 9_000,
+                ..canister()
+            })
+            .unwrap();
+
+        assert_eq!(policy.canisters.len(), 1);
+        assert_eq!(policy.canisters[0].target_cycles, 9_000);
+
+        policy.unwatch(&canister().canister_id).unwrap();
+        assert!(policy.canisters.is_empty());
+        assert!(policy.unwatch(&canister().canister_id).is_err());
+    }
+
+    #[test]
+    fn test_report_totals() {
+        let mut report = FleetTopUpReport {
+            timestamp: NanoTimeStamp(0),
+            chain: ChainEnum::CYCLES,
+            canisters: Vec::new(),
+            cycles: 0,
+            cost: TokenAmount::new(0u8.into(), 12),
+        };
+
+        let canister_id = canister().canister_id;
+
+        report.add(canister_id, Some(10_000), FleetCanisterOutcome::Healthy);
+        report.add(
+            canister_id,
+            Some(500),
+            FleetCanisterOutcome::ToppedUp {
+                block_index: 1u8.into(),
+                cycles: 4_500,
+                cost: TokenAmount::new(4_600u64.into(), 12),
+            },
+        );
+        report.add(
+            canister_id,
+            None,
+            FleetCanisterOutcome::Failed("down".to_string()),
+        );
+
+        assert_eq!(report.cycles, 4_500);
+        assert_eq!(report.cost, TokenAmount::new(4_600u64.into(), 12));
+        assert_eq!(report.topped_up().len(), 1);
+        assert_eq!(report.canisters.len(), 3);
+    }
+}
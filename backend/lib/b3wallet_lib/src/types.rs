@@ -1,6 +1,9 @@
 use crate::{
     account::WalletAccount,
-    ledger::types::{AddressMap, Pendings},
+    ledger::{
+        token::TokenMap,
+        types::{AddressMap, ChainEnum, Pendings},
+    },
 };
 use b3_utils::{ledger::Metadata, Environment};
 use candid::CandidType;
@@ -19,6 +22,11 @@ pub struct WalletAccountView {
     pub hidden: bool,
     pub metadata: Metadata,
     pub addresses: AddressMap,
+    /// The name of each chain of the account, e.g. `Arbitrum One` for `EVM(42161)`.
+    pub chain_names: BTreeMap<ChainEnum, String>,
+    /// The ICRC-1 textual form of the ICP address, if the account has ICP.
+    pub icp_icrc_address: Option<String>,
+    pub tokens: TokenMap,
     pub environment: Environment,
     pub pendings: Pendings,
 }
@@ -39,6 +39,10 @@ pub enum OperationError {
     InvalidWasmHash,
     InvalidController,
     InvalidTransaction,
+    InvalidAddress(String),
+    CannotCallSelf,
+    CannotCallManagementCanister,
+    RecipientMismatch(String, String),
     SneakyMessage,
     AccountNotFound,
     ChainNotFound(String, String),
@@ -79,6 +83,10 @@ impl fmt::Display for OperationError {
             OperationError::InvalidWasmHash => write!(f, "Invalid wasm hash!"),
             OperationError::InvalidController => write!(f, "Invalid controller!"),
             OperationError::InvalidTransaction => write!(f, "Invalid transaction!"),
+            OperationError::InvalidAddress(ref msg) => write!(f, "Invalid address: {}", msg),
+            OperationError::CannotCallSelf => write!(f, "Cannot call the wallet itself, use the wallet operations instead!"),
+            OperationError::CannotCallManagementCanister => write!(f, "Cannot call the management canister, use the wallet operations instead!"),
+            OperationError::RecipientMismatch(ref to, ref account) => write!(f, "Account identifier {} is not the one of {}!", to, account),
             OperationError::SneakyMessage => write!(f, "Sneaky message, if you want to send transaction use 'send_transaction' method!"),
             OperationError::AccountNotFound => write!(f, "Account not found!"),
             OperationError::ChainNotFound(ref chain_name, ref chain_id) => write!(f, "Chain {} with id {} not found!", chain_name, chain_id),
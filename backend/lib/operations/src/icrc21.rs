@@ -0,0 +1,136 @@
+use b3_utils::types::CanisterId;
+use candid::{CandidType, Deserialize, Nat};
+use ic_cdk::api::call::call;
+use std::fmt;
+
+use crate::operation::{Operation, OperationTrait};
+
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
+pub struct Icrc21ConsentMessageMetadata {
+    pub language: String,
+    pub utc_offset_minutes: Option<i16>,
+}
+
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
+pub enum Icrc21DeviceSpec {
+    GenericDisplay,
+    LineDisplay {
+        characters_per_line: u16,
+        lines_per_page: u16,
+    },
+}
+
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
+pub struct Icrc21ConsentMessageSpec {
+    pub metadata: Icrc21ConsentMessageMetadata,
+    pub device_spec: Option<Icrc21DeviceSpec>,
+}
+
+impl Default for Icrc21ConsentMessageSpec {
+    fn default() -> Self {
+        Icrc21ConsentMessageSpec {
+            metadata: Icrc21ConsentMessageMetadata {
+                language: "en".to_string(),
+                utc_offset_minutes: None,
+            },
+            device_spec: Some(Icrc21DeviceSpec::GenericDisplay),
+        }
+    }
+}
+
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
+pub struct Icrc21ConsentMessageRequest {
+    pub method: String,
+    pub arg: Vec<u8>,
+    pub user_preferences: Icrc21ConsentMessageSpec,
+}
+
+impl Icrc21ConsentMessageRequest {
+    pub fn new(method: String, arg: Vec<u8>) -> Self {
+        Icrc21ConsentMessageRequest {
+            method,
+            arg,
+            user_preferences: Icrc21ConsentMessageSpec::default(),
+        }
+    }
+}
+
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
+pub struct Icrc21LineDisplayPage {
+    pub lines: Vec<String>,
+}
+
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
+pub enum Icrc21ConsentMessage {
+    GenericDisplayMessage(String),
+    LineDisplayMessage { pages: Vec<Icrc21LineDisplayPage> },
+}
+
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
+pub struct Icrc21ConsentInfo {
+    pub consent_message: Icrc21ConsentMessage,
+    pub metadata: Icrc21ConsentMessageMetadata,
+}
+
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
+pub struct Icrc21ErrorInfo {
+    pub description: String,
+}
+
+#[rustfmt::skip]
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
+pub enum Icrc21Error {
+    UnsupportedCanisterCall(Icrc21ErrorInfo),
+    ConsentMessageUnavailable(Icrc21ErrorInfo),
+    InsufficientPayment(Icrc21ErrorInfo),
+    GenericError { error_code: Nat, description: String },
+    CallError(String),
+}
+
+#[rustfmt::skip]
+impl fmt::Display for Icrc21Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Icrc21Error::UnsupportedCanisterCall(ref info) => write!(f, "Unsupported canister call: {}", info.description),
+            Icrc21Error::ConsentMessageUnavailable(ref info) => write!(f, "Consent message unavailable: {}", info.description),
+            Icrc21Error::InsufficientPayment(ref info) => write!(f, "Insufficient payment: {}", info.description),
+            Icrc21Error::GenericError { ref error_code, ref description } => write!(f, "Error {}: {}", error_code, description),
+            Icrc21Error::CallError(ref msg) => write!(f, "ICRC-21 is not supported by the canister: {}", msg),
+        }
+    }
+}
+
+pub type Icrc21ConsentMessageResponse = Result<Icrc21ConsentInfo, Icrc21Error>;
+
+#[derive(CandidType, Clone, Deserialize, PartialEq, Debug)]
+pub struct ICRC21(pub CanisterId);
+
+impl ICRC21 {
+    pub fn new(canister_id: CanisterId) -> Self {
+        ICRC21(canister_id)
+    }
+
+    pub async fn consent_message(
+        &self,
+        request: Icrc21ConsentMessageRequest,
+    ) -> Icrc21ConsentMessageResponse {
+        let (res,): (Icrc21ConsentMessageResponse,) =
+            call(self.0, "icrc21_canister_call_consent_message", (request,))
+                .await
+                .map_err(|e| Icrc21Error::CallError(e.1))?;
+
+        res
+    }
+}
+
+/// Ask the target canister of the operation to describe the call.
+/// Returns `None` when the operation doesn't call another canister.
+pub async fn fetch_canister_consent(request: &Operation) -> Option<Icrc21ConsentMessageResponse> {
+    let (canister_id, consent_request) = request.consent_request()?;
+
+    let response = ICRC21::new(canister_id)
+        .consent_message(consent_request)
+        .await;
+
+    Some(response)
+}
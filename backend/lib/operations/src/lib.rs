@@ -1,4 +1,5 @@
 pub mod error;
+pub mod icrc21;
 pub mod operation;
 pub mod pending;
 pub mod processed;
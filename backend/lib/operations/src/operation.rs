@@ -1,21 +1,29 @@
 use std::fmt;
 
-use crate::error::OperationError;
+use crate::{error::OperationError, icrc21::Icrc21ConsentMessageRequest};
 use async_trait::async_trait;
-use b3wallet_lib::error::WalletError;
+use b3_utils::types::CanisterId;
+use b3wallet_lib::{error::WalletError, ledger::evm::gas::EvmFeeRequest};
 use candid::{CandidType, Deserialize};
 use enum_dispatch::enum_dispatch;
 
 pub mod btc;
+pub mod canister;
+pub mod cycles;
 pub mod evm;
 pub mod global;
 pub mod icp;
+pub mod icrc7;
 pub mod inner;
+pub mod legacy;
 pub mod result;
 
 use btc::*;
+use canister::*;
+use cycles::*;
 use evm::*;
 use icp::*;
+use icrc7::*;
 use inner::*;
 
 mod state;
@@ -30,15 +38,45 @@ pub enum OperationEnum {
     // EVM
     EvmTransfer,
     EvmSignMessage,
+    EvmSignPersonalMessage,
+    EvmSignTypedData,
     EvmTransferErc20,
+    EvmTransferErc721,
+    EvmTransferErc1155,
     EvmDeployContract,
+    EvmContractCall,
     EvmSignTranscation,
     EvmSignRawTransaction,
+    EvmCancelNonce,
+    EvmSignUserOperation,
+    UpdateErc4337Config,
+    UpdateEvmRpc,
+    UpdateEvmGasPolicy,
+    UpdateEvmNetwork,
     // BTC
     BtcTransfer,
     // ICP
     IcpTransfer,
     TopUpTransfer,
+    StakeNeuron,
+    RefreshNeuron,
+    IncreaseNeuronDissolveDelay,
+    StartNeuronDissolving,
+    StopNeuronDissolving,
+    DisburseNeuron,
+    FollowNeuron,
+    VoteNeuron,
+    UpdateGovernanceCanister,
+    // CYCLES
+    DepositCycles,
+    CreateCanisterWithCycles,
+    UpdateCyclesLedger,
+    UpdateCyclesTopUp,
+    UpdateFleetTopUp,
+    WatchCanister,
+    UnwatchCanister,
+    // ICRC7
+    Icrc7Transfer,
     // INNER
     AddUser,
     RemoveUser,
@@ -49,6 +87,8 @@ pub enum OperationEnum {
     UnhideAccount,
     UpgradeCanister,
     UpdateCanisterSettings,
+    // CANISTER
+    CallCanister,
 }
 
 impl Operation {
@@ -58,15 +98,45 @@ impl Operation {
             // EVM
             Operation::EvmTransfer(_) => OperationEnum::EvmTransfer,
             Operation::EvmSignMessage(_) => OperationEnum::EvmSignMessage,
+            Operation::EvmSignPersonalMessage(_) => OperationEnum::EvmSignPersonalMessage,
+            Operation::EvmSignTypedData(_) => OperationEnum::EvmSignTypedData,
             Operation::EvmTransferErc20(_) => OperationEnum::EvmTransferErc20,
+            Operation::EvmTransferErc721(_) => OperationEnum::EvmTransferErc721,
+            Operation::EvmTransferErc1155(_) => OperationEnum::EvmTransferErc1155,
             Operation::EvmDeployContract(_) => OperationEnum::EvmDeployContract,
+            Operation::EvmContractCall(_) => OperationEnum::EvmContractCall,
             Operation::EvmSignTranscation(_) => OperationEnum::EvmSignTranscation,
             Operation::EvmSignRawTransaction(_) => OperationEnum::EvmSignRawTransaction,
+            Operation::EvmCancelNonce(_) => OperationEnum::EvmCancelNonce,
+            Operation::EvmSignUserOperation(_) => OperationEnum::EvmSignUserOperation,
+            Operation::UpdateErc4337Config(_) => OperationEnum::UpdateErc4337Config,
+            Operation::UpdateEvmRpc(_) => OperationEnum::UpdateEvmRpc,
+            Operation::UpdateEvmGasPolicy(_) => OperationEnum::UpdateEvmGasPolicy,
+            Operation::UpdateEvmNetwork(_) => OperationEnum::UpdateEvmNetwork,
             // BTC
             Operation::BtcTransfer(_) => OperationEnum::BtcTransfer,
             // ICP
             Operation::IcpTransfer(_) => OperationEnum::IcpTransfer,
             Operation::TopUpTransfer(_) => OperationEnum::TopUpTransfer,
+            Operation::StakeNeuron(_) => OperationEnum::StakeNeuron,
+            Operation::RefreshNeuron(_) => OperationEnum::RefreshNeuron,
+            Operation::IncreaseNeuronDissolveDelay(_) => OperationEnum::IncreaseNeuronDissolveDelay,
+            Operation::StartNeuronDissolving(_) => OperationEnum::StartNeuronDissolving,
+            Operation::StopNeuronDissolving(_) => OperationEnum::StopNeuronDissolving,
+            Operation::DisburseNeuron(_) => OperationEnum::DisburseNeuron,
+            Operation::FollowNeuron(_) => OperationEnum::FollowNeuron,
+            Operation::VoteNeuron(_) => OperationEnum::VoteNeuron,
+            Operation::UpdateGovernanceCanister(_) => OperationEnum::UpdateGovernanceCanister,
+            // CYCLES
+            Operation::DepositCycles(_) => OperationEnum::DepositCycles,
+            Operation::CreateCanisterWithCycles(_) => OperationEnum::CreateCanisterWithCycles,
+            Operation::UpdateCyclesLedger(_) => OperationEnum::UpdateCyclesLedger,
+            Operation::UpdateCyclesTopUp(_) => OperationEnum::UpdateCyclesTopUp,
+            Operation::UpdateFleetTopUp(_) => OperationEnum::UpdateFleetTopUp,
+            Operation::WatchCanister(_) => OperationEnum::WatchCanister,
+            Operation::UnwatchCanister(_) => OperationEnum::UnwatchCanister,
+            // ICRC7
+            Operation::Icrc7Transfer(_) => OperationEnum::Icrc7Transfer,
             // INNER
             Operation::AddUser(_) => OperationEnum::AddUser,
             Operation::RemoveUser(_) => OperationEnum::RemoveUser,
@@ -77,6 +147,17 @@ impl Operation {
             Operation::UnhideAccount(_) => OperationEnum::UnhideAccount,
             Operation::UpgradeCanister(_) => OperationEnum::UpgradeCanister,
             Operation::UpdateCanisterSettings(_) => OperationEnum::UpdateCanisterSettings,
+            // CANISTER
+            Operation::CallCanister(_) => OperationEnum::CallCanister,
+        }
+    }
+
+    /// The ENS name the EVM destination was resolved from, only the wallet sets it.
+    pub fn ens_name(&self) -> Option<&String> {
+        match self {
+            Operation::EvmTransfer(args) => args.ens_name.as_ref(),
+            Operation::EvmTransferErc20(args) => args.ens_name.as_ref(),
+            _ => None,
         }
     }
 }
@@ -89,6 +170,21 @@ pub trait OperationTrait {
     fn method_name(&self) -> String;
     fn validate_request(&self) -> Result<(), OperationError>;
     async fn execute(self) -> Result<OperationResult, WalletError>;
+    /// The canister and call to ask for an ICRC-21 consent message, if the
+    /// operation calls another canister.
+    fn consent_request(&self) -> Option<(CanisterId, Icrc21ConsentMessageRequest)> {
+        None
+    }
+    /// The transaction to quote the fees of in the consent message, if the
+    /// operation sends one on an EVM chain.
+    fn evm_fee_request(&self) -> Option<EvmFeeRequest> {
+        None
+    }
+    /// The transaction to simulate against the latest block before approval,
+    /// the one quoted for fees unless the operation signs a given transaction.
+    fn evm_simulation_request(&self) -> Option<EvmFeeRequest> {
+        self.evm_fee_request()
+    }
 }
 
 #[enum_dispatch(OperationTrait)]
@@ -98,15 +194,45 @@ pub enum Operation {
     // EVM
     EvmTransfer,
     EvmSignMessage,
+    EvmSignPersonalMessage,
+    EvmSignTypedData,
     EvmTransferErc20,
+    EvmTransferErc721,
+    EvmTransferErc1155,
     EvmDeployContract,
+    EvmContractCall,
     EvmSignTranscation,
     EvmSignRawTransaction,
+    EvmCancelNonce,
+    EvmSignUserOperation,
+    UpdateErc4337Config,
+    UpdateEvmRpc,
+    UpdateEvmGasPolicy,
+    UpdateEvmNetwork,
     // BTC
     BtcTransfer,
     // ICP
     IcpTransfer,
     TopUpTransfer,
+    StakeNeuron,
+    RefreshNeuron,
+    IncreaseNeuronDissolveDelay,
+    StartNeuronDissolving,
+    StopNeuronDissolving,
+    DisburseNeuron,
+    FollowNeuron,
+    VoteNeuron,
+    UpdateGovernanceCanister,
+    // CYCLES
+    DepositCycles,
+    CreateCanisterWithCycles,
+    UpdateCyclesLedger,
+    UpdateCyclesTopUp,
+    UpdateFleetTopUp,
+    WatchCanister,
+    UnwatchCanister,
+    // ICRC7
+    Icrc7Transfer,
     // INNER
     AddUser,
     RemoveUser,
@@ -117,6 +243,8 @@ pub enum Operation {
     UnhideAccount,
     UpgradeCanister,
     UpdateCanisterSettings,
+    // CANISTER
+    CallCanister,
 }
 
 impl fmt::Display for Operation {
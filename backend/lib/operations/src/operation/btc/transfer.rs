@@ -6,7 +6,7 @@ use crate::{
 use async_trait::async_trait;
 use b3_utils::ledger::currency::TokenAmount;
 use b3wallet_lib::ledger::types::ChainEnum;
-use b3wallet_lib::ledger::{chain::ChainTrait, types::SendResult};
+use b3wallet_lib::ledger::{chain::ChainTrait, options::SendOptions, types::SendResult};
 use b3wallet_lib::{error::WalletError, ledger::btc::network::BitcoinNetwork, store::with_chain};
 use candid::{CandidType, Deserialize};
 
@@ -25,7 +25,9 @@ impl OperationTrait for BtcTransfer {
             chain.clone()
         })?;
 
-        let result = chain.send(self.to.clone(), self.amount).await;
+        let result = chain
+            .send(self.to.clone(), self.amount.clone(), SendOptions::default())
+            .await;
 
         match result {
             Ok(SendResult::BTC(txid)) => Ok(BtcTransfered(self, txid).into()),
@@ -47,10 +49,19 @@ impl OperationTrait for BtcTransfer {
     }
 
     fn title(&self) -> String {
-        format!("Send {} {}", self.amount, self.network)
+        format!("Send {}", self.formatted_amount())
     }
 
     fn message(&self) -> String {
-        format!("Send {} {}", self.amount, self.network)
+        format!("Send {} to {}", self.formatted_amount(), self.to)
+    }
+}
+
+impl BtcTransfer {
+    pub fn formatted_amount(&self) -> String {
+        with_chain(&self.account_id, &ChainEnum::BTC(self.network), |chain| {
+            chain.token().format(&self.amount)
+        })
+        .unwrap_or_else(|_| format!("{} {}", self.amount, self.network))
     }
 }
@@ -0,0 +1,166 @@
+use async_trait::async_trait;
+use b3_utils::types::CanisterId;
+use b3wallet_lib::error::WalletError;
+use candid::{CandidType, Deserialize, Principal};
+use ic_cdk::api::call::call_raw128;
+
+use crate::{
+    error::OperationError,
+    icrc21::Icrc21ConsentMessageRequest,
+    operation::{
+        result::{CanisterCalled, OperationResult},
+        OperationTrait,
+    },
+};
+
+#[cfg(test)]
+use b3_utils::mocks::id_mock as ic_cdk_id;
+#[cfg(not(test))]
+use ic_cdk::api::id as ic_cdk_id;
+
+// CALL CANISTER
+/// Call any method of another canister on behalf of the wallet.
+/// The argument is the Candid encoded bytes of the method arguments.
+#[derive(CandidType, Clone, Deserialize, PartialEq, Debug)]
+pub struct CallCanister {
+    pub canister_id: CanisterId,
+    pub method_name: String,
+    pub arg: Vec<u8>,
+    pub cycles: Option<u128>,
+}
+
+impl CallCanister {
+    pub fn cycles(&self) -> u128 {
+        self.cycles.unwrap_or(0)
+    }
+
+    /// The canisters the wallet never calls directly.
+    fn check_target(&self) -> Result<(), OperationError> {
+        // the wallet itself should be managed through its own operations
+        if self.canister_id == ic_cdk_id() {
+            return Err(OperationError::CannotCallSelf);
+        }
+
+        // controllers, code and cycles of canisters go through the management canister
+        if self.canister_id == Principal::management_canister() {
+            return Err(OperationError::CannotCallManagementCanister);
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl OperationTrait for CallCanister {
+    async fn execute(self) -> Result<OperationResult, WalletError> {
+        // requests made before the target was checked may still be pending
+        self.check_target()
+            .map_err(|err| WalletError::CanisterCallError(err.to_string()))?;
+
+        let reply = call_raw128(
+            self.canister_id,
+            &self.method_name,
+            self.arg.clone(),
+            self.cycles(),
+        )
+        .await
+        .map_err(|err| WalletError::CanisterCallError(err.1))?;
+
+        Ok(CanisterCalled(self, reply).into())
+    }
+
+    fn validate_request(&self) -> Result<(), OperationError> {
+        if self.method_name.is_empty() {
+            return Err(OperationError::InvalidRequest);
+        }
+
+        self.check_target()
+    }
+
+    fn method_name(&self) -> String {
+        "call_canister".to_string()
+    }
+
+    fn title(&self) -> String {
+        format!("Call {} on {}", self.method_name, self.canister_id)
+    }
+
+    fn message(&self) -> String {
+        match self.cycles {
+            Some(cycles) if cycles > 0 => format!(
+                "Call {} on {} with {} bytes of arguments and {} cycles attached",
+                self.method_name,
+                self.canister_id,
+                self.arg.len(),
+                cycles
+            ),
+            _ => format!(
+                "Call {} on {} with {} bytes of arguments",
+                self.method_name,
+                self.canister_id,
+                self.arg.len()
+            ),
+        }
+    }
+
+    fn consent_request(&self) -> Option<(CanisterId, Icrc21ConsentMessageRequest)> {
+        let request = Icrc21ConsentMessageRequest::new(self.method_name.clone(), self.arg.clone());
+
+        Some((self.canister_id, request))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call_canister(canister_id: CanisterId, method_name: &str) -> CallCanister {
+        CallCanister {
+            canister_id,
+            method_name: method_name.to_string(),
+            arg: candid::encode_args(()).unwrap(),
+            cycles: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_request() {
+        let ledger = CanisterId::from_text("ryjl3-tyaaa-aaaaa-aaaba-cai").unwrap();
+
+        assert!(call_canister(ledger, "icrc1_transfer")
+            .validate_request()
+            .is_ok());
+    }
+
+    #[test]
+    fn test_validate_request_empty_method() {
+        let ledger = CanisterId::from_text("ryjl3-tyaaa-aaaaa-aaaba-cai").unwrap();
+
+        assert_eq!(
+            call_canister(ledger, "").validate_request(),
+            Err(OperationError::InvalidRequest)
+        );
+    }
+
+    #[test]
+    fn test_validate_request_self() {
+        assert_eq!(
+            call_canister(ic_cdk_id(), "add_signer").validate_request(),
+            Err(OperationError::CannotCallSelf)
+        );
+    }
+
+    #[test]
+    fn test_validate_request_management_canister() {
+        let management = CanisterId::from_text("aaaaa-aa").unwrap();
+
+        assert_eq!(
+            call_canister(management, "update_settings").validate_request(),
+            Err(OperationError::CannotCallManagementCanister)
+        );
+        assert_eq!(
+            call_canister(management, "install_code").validate_request(),
+            Err(OperationError::CannotCallManagementCanister)
+        );
+    }
+}
@@ -0,0 +1,3 @@
+pub mod call;
+
+pub use call::*;
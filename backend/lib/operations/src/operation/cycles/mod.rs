@@ -0,0 +1,5 @@
+pub mod top_up;
+pub mod transfer;
+
+pub use top_up::*;
+pub use transfer::*;
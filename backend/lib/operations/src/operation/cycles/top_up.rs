@@ -0,0 +1,230 @@
+use crate::error::OperationError;
+use crate::operation::result::OperationResult;
+use crate::operation::OperationTrait;
+use async_trait::async_trait;
+use b3_utils::types::CanisterId;
+use b3wallet_lib::error::WalletError;
+use b3wallet_lib::ledger::cycles::types::format_cycles;
+use b3wallet_lib::store::{with_account, with_setting, with_setting_mut};
+use b3wallet_lib::top_up::fleet::{configure_fleet_top_up, FleetTopUpPolicy, WatchedCanister};
+use b3wallet_lib::top_up::{configure_cycles_top_up, CyclesTopUpPolicy};
+use b3wallet_lib::types::AccountId;
+use candid::{CandidType, Deserialize};
+
+/// The account paying for the top-ups, by name when it still exists.
+fn funding_account(account_id: &AccountId) -> String {
+    with_account(account_id, |account| {
+        format!("{}({})", account.name(), account_id)
+    })
+    .unwrap_or_else(|_| account_id.clone())
+}
+
+/// A copy of the fleet policy, to try a watch-list change on before it's approved.
+fn fleet_policy() -> Result<FleetTopUpPolicy, WalletError> {
+    with_setting(|s| s.fleet_top_up().cloned())
+        .ok_or_else(|| WalletError::TopUpPolicyError("No fleet top up policy".to_string()))
+}
+
+// UPDATE CYCLES TOP UP
+/// Keep the wallet canister funded with the given policy, or stop when not set.
+/// The wallet restarts the timer of the cycles top-up once it's executed.
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
+pub struct UpdateCyclesTopUp {
+    pub policy: Option<CyclesTopUpPolicy>,
+}
+
+#[async_trait]
+impl OperationTrait for UpdateCyclesTopUp {
+    async fn execute(self) -> Result<OperationResult, WalletError> {
+        configure_cycles_top_up(self.policy.clone())?;
+
+        Ok(self.into())
+    }
+
+    fn validate_request(&self) -> Result<(), OperationError> {
+        if let Some(ref policy) = self.policy {
+            policy.validate()?;
+        }
+
+        Ok(())
+    }
+
+    fn method_name(&self) -> String {
+        "update_cycles_top_up".to_string()
+    }
+
+    fn title(&self) -> String {
+        "Update the cycles top up".to_string()
+    }
+
+    fn message(&self) -> String {
+        match self.policy {
+            Some(ref policy) => format!(
+                "Top up the wallet with {} ICP of {} when it has less than {}, \
+                checking every {} seconds",
+                policy.amount,
+                funding_account(&policy.account_id),
+                format_cycles(policy.min_cycles),
+                policy.interval_secs
+            ),
+            None => "Stop topping up the wallet".to_string(),
+        }
+    }
+}
+
+// UPDATE FLEET TOP UP
+/// Keep the watched canisters funded with the given policy, or stop when not set.
+/// The wallet restarts the timer of the fleet top-up once it's executed.
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
+pub struct UpdateFleetTopUp {
+    pub policy: Option<FleetTopUpPolicy>,
+}
+
+#[async_trait]
+impl OperationTrait for UpdateFleetTopUp {
+    async fn execute(self) -> Result<OperationResult, WalletError> {
+        configure_fleet_top_up(self.policy.clone())?;
+
+        Ok(self.into())
+    }
+
+    fn validate_request(&self) -> Result<(), OperationError> {
+        if let Some(ref policy) = self.policy {
+            policy.validate()?;
+        }
+
+        Ok(())
+    }
+
+    fn method_name(&self) -> String {
+        "update_fleet_top_up".to_string()
+    }
+
+    fn title(&self) -> String {
+        "Update the fleet top up".to_string()
+    }
+
+    fn message(&self) -> String {
+        let policy = match self.policy {
+            Some(ref policy) => policy,
+            None => return "Stop topping up the watched canisters".to_string(),
+        };
+
+        let canisters = policy
+            .canisters
+            .iter()
+            .map(|canister| {
+                format!(
+                    "{} to {} when below {}",
+                    canister.canister_id,
+                    format_cycles(canister.target_cycles),
+                    format_cycles(canister.min_cycles)
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let canisters = if canisters.is_empty() {
+            "no canister yet".to_string()
+        } else {
+            canisters.join(", ")
+        };
+
+        format!(
+            "Top up {} every {} seconds with the {} of {}, at most {} per canister \
+            and {} per run",
+            canisters,
+            policy.interval_secs,
+            with_setting(|s| s.chain_name(&policy.chain)),
+            funding_account(&policy.account_id),
+            format_cycles(policy.max_cycles_per_canister),
+            format_cycles(policy.max_cycles_per_run)
+        )
+    }
+}
+
+// WATCH CANISTER
+/// Add a canister to the fleet top-up, or change its levels if already watched.
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
+pub struct WatchCanister {
+    pub canister: WatchedCanister,
+}
+
+#[async_trait]
+impl OperationTrait for WatchCanister {
+    async fn execute(self) -> Result<OperationResult, WalletError> {
+        with_setting_mut(|s| s.watch_canister(self.canister.clone()))?;
+
+        Ok(self.into())
+    }
+
+    fn validate_request(&self) -> Result<(), OperationError> {
+        fleet_policy()?.watch(self.canister.clone())?;
+
+        Ok(())
+    }
+
+    fn method_name(&self) -> String {
+        "watch_canister".to_string()
+    }
+
+    fn title(&self) -> String {
+        format!("Watch canister {}", self.canister.canister_id)
+    }
+
+    fn message(&self) -> String {
+        let mut message = format!(
+            "Top up {} to {} when below {}",
+            self.canister.canister_id,
+            format_cycles(self.canister.target_cycles),
+            format_cycles(self.canister.min_cycles)
+        );
+
+        if let Some(ref method) = self.canister.balance_query {
+            message += &format!(", reading its balance from {}", method);
+        }
+
+        if let Some(policy) = with_setting(|s| s.fleet_top_up().cloned()) {
+            message += &format!(
+                ", paid with the {} of {}, at most {} per run",
+                with_setting(|s| s.chain_name(&policy.chain)),
+                funding_account(&policy.account_id),
+                format_cycles(policy.max_cycles_per_canister)
+            );
+        }
+
+        message
+    }
+}
+
+// UNWATCH CANISTER
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
+pub struct UnwatchCanister {
+    pub canister_id: CanisterId,
+}
+
+#[async_trait]
+impl OperationTrait for UnwatchCanister {
+    async fn execute(self) -> Result<OperationResult, WalletError> {
+        with_setting_mut(|s| s.unwatch_canister(&self.canister_id))?;
+
+        Ok(self.into())
+    }
+
+    fn validate_request(&self) -> Result<(), OperationError> {
+        fleet_policy()?.unwatch(&self.canister_id)?;
+
+        Ok(())
+    }
+
+    fn method_name(&self) -> String {
+        "unwatch_canister".to_string()
+    }
+
+    fn title(&self) -> String {
+        format!("Unwatch canister {}", self.canister_id)
+    }
+
+    fn message(&self) -> String {
+        format!("Stop topping up {}", self.canister_id)
+    }
+}
@@ -0,0 +1,173 @@
+use crate::error::OperationError;
+use crate::operation::result::{CanisterCreated, CyclesDeposited, OperationResult};
+use crate::operation::OperationTrait;
+use async_trait::async_trait;
+use b3_utils::types::CanisterId;
+use b3wallet_lib::error::WalletError;
+use b3wallet_lib::ledger::cycles::cycles::CyclesLedger;
+use b3wallet_lib::ledger::cycles::types::{
+    format_cycles, CanisterSettings, Cycles, SubnetSelection,
+};
+use b3wallet_lib::ledger::types::ChainEnum;
+use b3wallet_lib::store::{with_chain, with_setting, with_setting_mut};
+use candid::{CandidType, Deserialize};
+
+// DEPOSIT CYCLES
+/// Withdraw cycles from the account balance on the cycles ledger into any canister.
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
+pub struct DepositCycles {
+    pub account_id: String,
+    pub canister_id: CanisterId,
+    pub cycles: Cycles,
+}
+
+#[async_trait]
+impl OperationTrait for DepositCycles {
+    async fn execute(self) -> Result<OperationResult, WalletError> {
+        let cycles = with_chain(&self.account_id, &ChainEnum::CYCLES, |chain| chain.cycles())??;
+
+        let block_index = cycles.withdraw(self.canister_id, self.cycles).await?;
+
+        Ok(CyclesDeposited(self, block_index).into())
+    }
+
+    fn validate_request(&self) -> Result<(), OperationError> {
+        if self.cycles == 0 {
+            return Err(OperationError::AmountIsZero);
+        }
+
+        with_chain(&self.account_id, &ChainEnum::CYCLES, |_| Ok(()))?
+    }
+
+    fn method_name(&self) -> String {
+        "deposit_cycles".to_string()
+    }
+
+    fn title(&self) -> String {
+        format!("Deposit {}", format_cycles(self.cycles))
+    }
+
+    fn message(&self) -> String {
+        format!(
+            "Deposit {} from {} into canister {}",
+            format_cycles(self.cycles),
+            self.account_id,
+            self.canister_id
+        )
+    }
+}
+
+// CREATE CANISTER
+/// Create a new canister paid from the account balance on the cycles ledger.
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
+pub struct CreateCanisterWithCycles {
+    pub account_id: String,
+    pub cycles: Cycles,
+    pub settings: Option<CanisterSettings>,
+    pub subnet_selection: Option<SubnetSelection>,
+}
+
+#[async_trait]
+impl OperationTrait for CreateCanisterWithCycles {
+    async fn execute(self) -> Result<OperationResult, WalletError> {
+        let cycles = with_chain(&self.account_id, &ChainEnum::CYCLES, |chain| chain.cycles())??;
+
+        let created = cycles
+            .create_canister(
+                self.cycles,
+                self.settings.clone(),
+                self.subnet_selection.clone(),
+            )
+            .await?;
+
+        Ok(CanisterCreated(self, created).into())
+    }
+
+    fn validate_request(&self) -> Result<(), OperationError> {
+        if self.cycles == 0 {
+            return Err(OperationError::AmountIsZero);
+        }
+
+        with_chain(&self.account_id, &ChainEnum::CYCLES, |_| Ok(()))?
+    }
+
+    fn method_name(&self) -> String {
+        "create_canister_with_cycles".to_string()
+    }
+
+    fn title(&self) -> String {
+        format!("Create a canister with {}", format_cycles(self.cycles))
+    }
+
+    fn message(&self) -> String {
+        let controllers = self
+            .settings
+            .as_ref()
+            .and_then(|settings| settings.controllers.as_ref())
+            .map(|controllers| {
+                controllers
+                    .iter()
+                    .map(|controller| controller.to_text())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            });
+
+        match controllers {
+            Some(controllers) => format!(
+                "Create a canister controlled by {} with {} from {}",
+                controllers,
+                format_cycles(self.cycles),
+                self.account_id
+            ),
+            None => format!(
+                "Create a canister controlled by the wallet with {} from {}",
+                format_cycles(self.cycles),
+                self.account_id
+            ),
+        }
+    }
+}
+
+// UPDATE CYCLES LEDGER
+/// Create the cycles chains of the accounts on another cycles ledger, e.g. a
+/// local one, or on the mainnet one again when not set. Existing chains keep
+/// the ledger they were created with.
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
+pub struct UpdateCyclesLedger {
+    pub canister_id: Option<CanisterId>,
+}
+
+#[async_trait]
+impl OperationTrait for UpdateCyclesLedger {
+    async fn execute(self) -> Result<OperationResult, WalletError> {
+        with_setting_mut(|s| s.set_cycles_ledger_canister_id(self.canister_id));
+
+        Ok(self.into())
+    }
+
+    fn validate_request(&self) -> Result<(), OperationError> {
+        Ok(())
+    }
+
+    fn method_name(&self) -> String {
+        "update_cycles_ledger".to_string()
+    }
+
+    fn title(&self) -> String {
+        "Update the cycles ledger".to_string()
+    }
+
+    fn message(&self) -> String {
+        let current = with_setting(|s| s.cycles_ledger());
+
+        let ledger = match self.canister_id {
+            Some(canister_id) => canister_id,
+            None => CyclesLedger::default().0,
+        };
+
+        format!(
+            "Create new cycles chains on the ledger {} instead of {}",
+            ledger, current.0
+        )
+    }
+}
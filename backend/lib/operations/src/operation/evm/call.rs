@@ -0,0 +1,141 @@
+use crate::{
+    error::OperationError,
+    operation::{
+        result::{EvmContractCalled, OperationResult},
+        OperationTrait,
+    },
+};
+use async_trait::async_trait;
+use b3wallet_lib::{
+    error::WalletError,
+    ledger::{
+        evm::{
+            abi::{AbiFunction, AbiValue},
+            error::EvmError,
+            gas::EvmFeeRequest,
+            u256::U256,
+            utils::{validate_address, vec_u8_to_string},
+        },
+        types::ChainEnum,
+    },
+    store::{with_chain, with_setting},
+};
+use candid::{CandidType, Deserialize};
+
+// CONTRACT CALL
+/// Call a function of a contract, `function` being its signature such as
+/// `approve(address,uint256)` and `args` the values of its inputs.
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
+pub struct EvmContractCall {
+    pub account_id: String,
+    pub chain_id: u64,
+    pub contract_address: String,
+    pub function: String,
+    pub args: Vec<AbiValue>,
+    pub value: U256,
+    pub gas_limit: Option<u64>,
+    pub max_fee_per_gas: Option<u64>,
+    pub max_priority_fee_per_gas: Option<u64>,
+}
+
+impl EvmContractCall {
+    fn data(&self) -> Result<String, EvmError> {
+        let data = AbiFunction::parse(&self.function)?.encode_call(&self.args)?;
+
+        Ok("0x".to_owned() + &vec_u8_to_string(&data))
+    }
+
+    /// The call decoded back from the data that is sent, so the signers read
+    /// what the contract will receive.
+    fn decoded_call(&self) -> Result<String, EvmError> {
+        let function = AbiFunction::parse(&self.function)?;
+
+        let data = function.encode_call(&self.args)?;
+        let args = function.decode_call(&data)?;
+
+        Ok(function.display_call(&args))
+    }
+}
+
+#[async_trait]
+impl OperationTrait for EvmContractCall {
+    async fn execute(self) -> Result<OperationResult, WalletError> {
+        let chain_enum = ChainEnum::EVM(self.chain_id);
+
+        let evm = with_chain(&self.account_id, &chain_enum, |chain| chain.evm())??;
+
+        let data = self.data()?;
+
+        let fees = evm
+            .fees(
+                &self.contract_address,
+                &self.value,
+                &data,
+                self.gas_limit,
+                self.max_fee_per_gas,
+                self.max_priority_fee_per_gas,
+            )
+            .await?;
+
+        let pending = evm
+            .send_call(&self.contract_address, self.value.clone(), data, &fees)
+            .await?;
+
+        Ok(EvmContractCalled(self, pending).into())
+    }
+
+    fn validate_request(&self) -> Result<(), OperationError> {
+        // check if the chain id is initialized
+        with_chain(&self.account_id, &ChainEnum::EVM(self.chain_id), |_| {})?;
+
+        validate_address(&self.contract_address)?;
+
+        // check if the args match the function
+        self.data()?;
+
+        Ok(())
+    }
+
+    fn method_name(&self) -> String {
+        "evm_contract_call".to_string()
+    }
+
+    fn evm_fee_request(&self) -> Option<EvmFeeRequest> {
+        Some(EvmFeeRequest {
+            account_id: self.account_id.clone(),
+            chain_id: self.chain_id,
+            to: self.contract_address.clone(),
+            value: self.value.clone(),
+            data: self.data().ok()?,
+            gas_limit: self.gas_limit,
+            max_fee_per_gas: self.max_fee_per_gas,
+            max_priority_fee_per_gas: self.max_priority_fee_per_gas,
+        })
+    }
+
+    fn title(&self) -> String {
+        match AbiFunction::parse(&self.function) {
+            Ok(function) => format!("Call {} on EVM contract", function.name),
+            Err(_) => "Call EVM contract".to_string(),
+        }
+    }
+
+    fn message(&self) -> String {
+        let call = self
+            .decoded_call()
+            .unwrap_or_else(|err| format!("{} ({})", self.function, err));
+
+        let mut message = format!(
+            "Call {} on contract {} on {}",
+            call,
+            self.contract_address,
+            with_setting(|s| s.evm_network_name(self.chain_id))
+        );
+
+        if !self.value.is_zero() {
+            message += &format!(", sending {} wei", self.value);
+        }
+
+        message
+    }
+}
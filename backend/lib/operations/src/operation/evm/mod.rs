@@ -1,7 +1,41 @@
+pub mod call;
+pub mod network;
+pub mod nft;
+pub mod nonce;
 pub mod other;
 pub mod sign;
 pub mod transfer;
+pub mod user_operation;
 
+pub use call::*;
+pub use network::*;
+pub use nft::*;
+pub use nonce::*;
 pub use other::*;
 pub use sign::*;
 pub use transfer::*;
+pub use user_operation::*;
+
+use crate::operation::{Operation, OperationTrait};
+use b3wallet_lib::{
+    error::WalletError,
+    ledger::evm::{gas::EvmFees, simulation::EvmSimulation},
+};
+
+/// Quote the fees of the EVM transaction the operation sends.
+/// Returns `None` when the operation doesn't send one.
+pub async fn fetch_evm_fees(request: &Operation) -> Option<Result<EvmFees, WalletError>> {
+    let fee_request = request.evm_fee_request()?;
+
+    Some(fee_request.quote().await)
+}
+
+/// Run the EVM transaction the operation sends against the latest block.
+/// Returns `None` when the operation doesn't send one.
+pub async fn fetch_evm_simulation(
+    request: &Operation,
+) -> Option<Result<EvmSimulation, WalletError>> {
+    let simulation_request = request.evm_simulation_request()?;
+
+    Some(simulation_request.simulate().await)
+}
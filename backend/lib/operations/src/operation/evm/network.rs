@@ -0,0 +1,233 @@
+use crate::{
+    error::OperationError,
+    operation::{result::OperationResult, OperationTrait},
+};
+use async_trait::async_trait;
+use b3wallet_lib::{
+    error::WalletError,
+    ledger::{
+        cycles::types::format_cycles,
+        evm::{
+            error::EvmError,
+            gas::EvmGasPolicy,
+            network::EvmNetwork,
+            rpc::{EvmRpc, EVM_RPC_CYCLES},
+        },
+    },
+    store::{with_setting, with_setting_mut, with_wallet, with_wallet_mut},
+};
+use candid::{CandidType, Deserialize};
+
+/// The fee cap of a gas policy, for the signers to read.
+fn describe_cap(cap: Option<u64>) -> String {
+    match cap {
+        Some(cap) => format!("of at most {} wei per gas", cap),
+        None => "without a cap".to_string(),
+    }
+}
+
+// UPDATE EVM RPC
+/// Send the EVM calls through another RPC canister or providers, or through
+/// the defaults again when not set.
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
+pub struct UpdateEvmRpc {
+    pub evm_rpc: Option<EvmRpc>,
+}
+
+#[async_trait]
+impl OperationTrait for UpdateEvmRpc {
+    async fn execute(self) -> Result<OperationResult, WalletError> {
+        with_setting_mut(|s| s.set_evm_rpc(self.evm_rpc.clone()));
+
+        Ok(self.into())
+    }
+
+    fn validate_request(&self) -> Result<(), OperationError> {
+        if let Some(ref evm_rpc) = self.evm_rpc {
+            evm_rpc.validate()?;
+        }
+
+        Ok(())
+    }
+
+    fn method_name(&self) -> String {
+        "update_evm_rpc".to_string()
+    }
+
+    fn title(&self) -> String {
+        "Update the EVM RPC".to_string()
+    }
+
+    fn message(&self) -> String {
+        let evm_rpc = match self.evm_rpc {
+            Some(ref evm_rpc) => evm_rpc,
+            None => return "Use the default EVM RPC canister and providers".to_string(),
+        };
+
+        let mut message = format!(
+            "Use the EVM RPC canister {}, attaching {} to each call",
+            evm_rpc.canister_id,
+            format_cycles(evm_rpc.cycles.unwrap_or(EVM_RPC_CYCLES))
+        );
+
+        for (chain_id, providers) in evm_rpc.providers.iter() {
+            let urls = providers
+                .iter()
+                .map(|provider| provider.url.as_str())
+                .collect::<Vec<_>>();
+
+            message += &format!(
+                ", {} through {}",
+                with_setting(|s| s.evm_network_name(*chain_id)),
+                urls.join(", ")
+            );
+        }
+
+        message
+    }
+}
+
+// UPDATE EVM GAS POLICY
+/// Choose the fees of the EVM transactions with another policy, or with the
+/// standard one without a cap again when not set.
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
+pub struct UpdateEvmGasPolicy {
+    pub policy: Option<EvmGasPolicy>,
+}
+
+#[async_trait]
+impl OperationTrait for UpdateEvmGasPolicy {
+    async fn execute(self) -> Result<OperationResult, WalletError> {
+        with_setting_mut(|s| s.set_evm_gas_policy(self.policy.clone()));
+
+        Ok(self.into())
+    }
+
+    fn validate_request(&self) -> Result<(), OperationError> {
+        // a cap of zero would stop every transaction
+        let cap = self
+            .policy
+            .as_ref()
+            .and_then(|policy| policy.max_fee_per_gas_cap);
+
+        if cap == Some(0) {
+            return Err(EvmError::InvalidNumber("max fee per gas cap of 0".to_string()).into());
+        }
+
+        Ok(())
+    }
+
+    fn method_name(&self) -> String {
+        "update_evm_gas_policy".to_string()
+    }
+
+    fn title(&self) -> String {
+        "Update the EVM gas policy".to_string()
+    }
+
+    fn message(&self) -> String {
+        let policy = self.policy.clone().unwrap_or_default();
+
+        let current = with_setting(|s| s.evm_gas_policy());
+
+        format!(
+            "Pay {:?} fees on the EVM networks {} instead of {:?} fees {}",
+            policy.speed,
+            describe_cap(policy.max_fee_per_gas_cap),
+            current.speed,
+            describe_cap(current.max_fee_per_gas_cap)
+        )
+    }
+}
+
+// UPDATE EVM NETWORK
+/// Add the network to the registry or replace it, or remove it when not set.
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
+pub struct UpdateEvmNetwork {
+    pub chain_id: u64,
+    pub network: Option<EvmNetwork>,
+}
+
+#[async_trait]
+impl OperationTrait for UpdateEvmNetwork {
+    async fn execute(self) -> Result<OperationResult, WalletError> {
+        match self.network {
+            Some(ref network) => with_setting_mut(|s| s.add_evm_network(network.clone()))?,
+            // an account may have been given an address on it since the request
+            None => with_wallet_mut(|s| s.remove_evm_network(self.chain_id))?,
+        }
+
+        Ok(self.into())
+    }
+
+    fn validate_request(&self) -> Result<(), OperationError> {
+        match self.network {
+            Some(ref network) => {
+                if network.chain_id != self.chain_id {
+                    return Err(EvmError::InvalidChainId(self.chain_id, network.chain_id).into());
+                }
+
+                network.validate()?;
+            }
+            None => {
+                with_wallet(|s| s.check_evm_network_removal(self.chain_id))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn method_name(&self) -> String {
+        "update_evm_network".to_string()
+    }
+
+    fn title(&self) -> String {
+        "Update the EVM networks".to_string()
+    }
+
+    fn message(&self) -> String {
+        let network = match self.network {
+            Some(ref network) => network,
+            None => {
+                return format!(
+                    "Remove {} from the EVM networks",
+                    with_setting(|s| s.evm_network_name(self.chain_id))
+                )
+            }
+        };
+
+        let action = match with_setting(|s| s.evm_network(self.chain_id)) {
+            Ok(current) => format!("Replace {} with", current.name),
+            Err(_) => "Add".to_string(),
+        };
+
+        let mut message = format!(
+            "{} {} (chain id {}) paying in {} with {} decimals, {}",
+            action,
+            network.name,
+            network.chain_id,
+            network.symbol,
+            network.decimals,
+            if network.eip1559 {
+                "with EIP-1559 fees"
+            } else {
+                "with legacy fees"
+            }
+        );
+
+        if let Some(ref explorer_url) = network.explorer_url {
+            message += &format!(", explorer {}", explorer_url);
+        }
+
+        if let Some(ref providers) = network.providers {
+            let urls = providers
+                .iter()
+                .map(|provider| provider.url.as_str())
+                .collect::<Vec<_>>();
+
+            message += &format!(", through {}", urls.join(", "));
+        }
+
+        message
+    }
+}
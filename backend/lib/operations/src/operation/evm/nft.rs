@@ -0,0 +1,312 @@
+use crate::{
+    error::OperationError,
+    operation::{
+        result::{EvmErc1155Transfered, EvmErc721Transfered, OperationResult},
+        OperationTrait,
+    },
+};
+use async_trait::async_trait;
+use b3wallet_lib::{
+    error::WalletError,
+    ledger::{
+        evm::{
+            api::EvmChain,
+            error::EvmError,
+            gas::EvmFeeRequest,
+            types::{NftCollection, NftStandard},
+            u256::U256,
+            utils::{validate_address, vec_u8_to_string},
+        },
+        types::{ChainEnum, EvmPending},
+    },
+    store::{with_chain, with_setting},
+};
+use candid::{CandidType, Deserialize};
+
+/// The chain and the tracked collection of the given standard.
+fn nft_collection(
+    account_id: &str,
+    chain_id: u64,
+    contract_address: &str,
+    standard: NftStandard,
+) -> Result<(EvmChain, NftCollection), WalletError> {
+    let evm = with_chain(
+        &account_id.to_string(),
+        &ChainEnum::EVM(chain_id),
+        |chain| chain.evm(),
+    )??;
+
+    let collection = evm.nft_collection(contract_address)?.clone();
+
+    collection.expect_standard(standard)?;
+
+    Ok((evm, collection))
+}
+
+/// Send the `safeTransferFrom` call to the collection.
+async fn send_nft_transfer(
+    evm: &EvmChain,
+    collection: &NftCollection,
+    data: String,
+    gas_limit: Option<u64>,
+    max_fee_per_gas: Option<u64>,
+    max_priority_fee_per_gas: Option<u64>,
+) -> Result<EvmPending, WalletError> {
+    let fees = evm
+        .fees(
+            &collection.address,
+            &U256::zero(),
+            &data,
+            gas_limit,
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+        )
+        .await?;
+
+    let pending = evm
+        .send_call(&collection.address, U256::zero(), data, &fees)
+        .await?;
+
+    Ok(pending)
+}
+
+// EVM TRANSFER ERC721
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
+pub struct EvmTransferErc721 {
+    pub account_id: String,
+    pub chain_id: u64,
+    pub contract_address: String,
+    pub to: String,
+    pub token_id: U256,
+    pub gas_limit: Option<u64>,
+    pub max_fee_per_gas: Option<u64>,
+    pub max_priority_fee_per_gas: Option<u64>,
+}
+
+impl EvmTransferErc721 {
+    fn data(&self, from: &str, collection: &NftCollection) -> Result<String, EvmError> {
+        let data = collection.transfer_data(from, &self.to, &self.token_id, &U256::from(1u64))?;
+
+        Ok("0x".to_owned() + &vec_u8_to_string(&data))
+    }
+}
+
+#[async_trait]
+impl OperationTrait for EvmTransferErc721 {
+    async fn execute(self) -> Result<OperationResult, WalletError> {
+        let (evm, collection) = nft_collection(
+            &self.account_id,
+            self.chain_id,
+            &self.contract_address,
+            NftStandard::Erc721,
+        )?;
+
+        let owner = evm.nft_owner(&collection, &self.token_id).await?;
+
+        if !owner.eq_ignore_ascii_case(&evm.address) {
+            return Err(EvmError::NftNotOwned(self.token_id.to_string()).into());
+        }
+
+        let data = self.data(&evm.address, &collection)?;
+
+        let pending = send_nft_transfer(
+            &evm,
+            &collection,
+            data,
+            self.gas_limit,
+            self.max_fee_per_gas,
+            self.max_priority_fee_per_gas,
+        )
+        .await?;
+
+        Ok(EvmErc721Transfered(self, pending).into())
+    }
+
+    fn validate_request(&self) -> Result<(), OperationError> {
+        nft_collection(
+            &self.account_id,
+            self.chain_id,
+            &self.contract_address,
+            NftStandard::Erc721,
+        )?;
+
+        validate_address(&self.to)?;
+
+        Ok(())
+    }
+
+    fn method_name(&self) -> String {
+        "evm_transfer_erc721".to_string()
+    }
+
+    fn evm_fee_request(&self) -> Option<EvmFeeRequest> {
+        let (evm, collection) = nft_collection(
+            &self.account_id,
+            self.chain_id,
+            &self.contract_address,
+            NftStandard::Erc721,
+        )
+        .ok()?;
+
+        Some(EvmFeeRequest {
+            account_id: self.account_id.clone(),
+            chain_id: self.chain_id,
+            to: self.contract_address.clone(),
+            value: U256::zero(),
+            data: self.data(&evm.address, &collection).ok()?,
+            gas_limit: self.gas_limit,
+            max_fee_per_gas: self.max_fee_per_gas,
+            max_priority_fee_per_gas: self.max_priority_fee_per_gas,
+        })
+    }
+
+    fn title(&self) -> String {
+        format!("Transfer ERC721 token #{}", self.token_id)
+    }
+
+    fn message(&self) -> String {
+        let name = nft_collection(
+            &self.account_id,
+            self.chain_id,
+            &self.contract_address,
+            NftStandard::Erc721,
+        )
+        .map(|(_, collection)| collection.name)
+        .unwrap_or_else(|_| self.contract_address.clone());
+
+        format!(
+            "Transfer token #{} of {} to {} on {}",
+            self.token_id,
+            name,
+            self.to,
+            with_setting(|s| s.evm_network_name(self.chain_id))
+        )
+    }
+}
+
+// EVM TRANSFER ERC1155
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
+pub struct EvmTransferErc1155 {
+    pub account_id: String,
+    pub chain_id: u64,
+    pub contract_address: String,
+    pub to: String,
+    pub token_id: U256,
+    pub amount: U256,
+    pub gas_limit: Option<u64>,
+    pub max_fee_per_gas: Option<u64>,
+    pub max_priority_fee_per_gas: Option<u64>,
+}
+
+impl EvmTransferErc1155 {
+    fn data(&self, from: &str, collection: &NftCollection) -> Result<String, EvmError> {
+        let data = collection.transfer_data(from, &self.to, &self.token_id, &self.amount)?;
+
+        Ok("0x".to_owned() + &vec_u8_to_string(&data))
+    }
+}
+
+#[async_trait]
+impl OperationTrait for EvmTransferErc1155 {
+    async fn execute(self) -> Result<OperationResult, WalletError> {
+        let (evm, collection) = nft_collection(
+            &self.account_id,
+            self.chain_id,
+            &self.contract_address,
+            NftStandard::Erc1155,
+        )?;
+
+        let balance = evm.nft_balance(&collection, &self.token_id).await?;
+
+        if balance < self.amount {
+            return Err(EvmError::InsufficientNftBalance(
+                balance.to_string(),
+                self.amount.to_string(),
+            )
+            .into());
+        }
+
+        let data = self.data(&evm.address, &collection)?;
+
+        let pending = send_nft_transfer(
+            &evm,
+            &collection,
+            data,
+            self.gas_limit,
+            self.max_fee_per_gas,
+            self.max_priority_fee_per_gas,
+        )
+        .await?;
+
+        Ok(EvmErc1155Transfered(self, pending).into())
+    }
+
+    fn validate_request(&self) -> Result<(), OperationError> {
+        nft_collection(
+            &self.account_id,
+            self.chain_id,
+            &self.contract_address,
+            NftStandard::Erc1155,
+        )?;
+
+        validate_address(&self.to)?;
+
+        if self.amount.is_zero() {
+            return Err(EvmError::InvalidNumber(self.amount.to_string()).into());
+        }
+
+        Ok(())
+    }
+
+    fn method_name(&self) -> String {
+        "evm_transfer_erc1155".to_string()
+    }
+
+    fn evm_fee_request(&self) -> Option<EvmFeeRequest> {
+        let (evm, collection) = nft_collection(
+            &self.account_id,
+            self.chain_id,
+            &self.contract_address,
+            NftStandard::Erc1155,
+        )
+        .ok()?;
+
+        Some(EvmFeeRequest {
+            account_id: self.account_id.clone(),
+            chain_id: self.chain_id,
+            to: self.contract_address.clone(),
+            value: U256::zero(),
+            data: self.data(&evm.address, &collection).ok()?,
+            gas_limit: self.gas_limit,
+            max_fee_per_gas: self.max_fee_per_gas,
+            max_priority_fee_per_gas: self.max_priority_fee_per_gas,
+        })
+    }
+
+    fn title(&self) -> String {
+        format!(
+            "Transfer {} of ERC1155 token #{}",
+            self.amount, self.token_id
+        )
+    }
+
+    fn message(&self) -> String {
+        let name = nft_collection(
+            &self.account_id,
+            self.chain_id,
+            &self.contract_address,
+            NftStandard::Erc1155,
+        )
+        .map(|(_, collection)| collection.name)
+        .unwrap_or_else(|_| self.contract_address.clone());
+
+        format!(
+            "Transfer {} of token #{} of {} to {} on {}",
+            self.amount,
+            self.token_id,
+            name,
+            self.to,
+            with_setting(|s| s.evm_network_name(self.chain_id))
+        )
+    }
+}
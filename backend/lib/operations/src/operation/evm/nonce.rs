@@ -0,0 +1,98 @@
+use crate::{
+    error::OperationError,
+    operation::{
+        result::{EvmNonceCancelled, OperationResult},
+        OperationTrait,
+    },
+};
+use async_trait::async_trait;
+use b3wallet_lib::{
+    error::WalletError,
+    ledger::{
+        evm::{
+            api::{REPLACEMENT_FEE_MULTIPLIER, TRANSFER_GAS_LIMIT},
+            evm::EvmTransaction,
+            gas::EvmFees,
+            u256::U256,
+        },
+        types::ChainEnum,
+    },
+    store::{with_chain, with_chain_mut, with_setting},
+};
+use candid::{CandidType, Deserialize};
+
+// CANCEL NONCE
+/// Replace the stuck transaction at `nonce` with an empty transfer to the
+/// account itself, paying more so the nodes drop the stuck one.
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
+pub struct EvmCancelNonce {
+    pub account_id: String,
+    pub chain_id: u64,
+    pub nonce: u64,
+}
+
+#[async_trait]
+impl OperationTrait for EvmCancelNonce {
+    async fn execute(self) -> Result<OperationResult, WalletError> {
+        let chain_enum = ChainEnum::EVM(self.chain_id);
+
+        let evm = with_chain(&self.account_id, &chain_enum, |chain| chain.evm())??;
+
+        let (max_fee_per_gas, max_priority_fee_per_gas) = evm.suggested_fees().await?;
+
+        let fees = EvmFees {
+            gas_limit: TRANSFER_GAS_LIMIT,
+            max_fee_per_gas: max_fee_per_gas.saturating_mul(REPLACEMENT_FEE_MULTIPLIER),
+            max_priority_fee_per_gas: max_priority_fee_per_gas
+                .saturating_mul(REPLACEMENT_FEE_MULTIPLIER),
+        };
+
+        evm.gas_policy().check_max_fee(fees.max_fee_per_gas)?;
+
+        // sent as a legacy transaction where the network needs it
+        let mut transaction: EvmTransaction = evm
+            .call_transaction(
+                self.nonce,
+                &evm.address,
+                U256::zero(),
+                "".to_string(),
+                &fees,
+            )
+            .into();
+
+        let pending = evm.send_with_own_nonce(&mut transaction).await?;
+
+        with_chain_mut(&self.account_id, chain_enum, |chain| {
+            chain
+                .evm_mut()
+                .map(|evm| evm.replace_pending(pending.clone()))
+        })??;
+
+        Ok(EvmNonceCancelled(self, pending).into())
+    }
+
+    fn validate_request(&self) -> Result<(), OperationError> {
+        // only a transaction sent by the wallet can be cancelled
+        with_chain(&self.account_id, &ChainEnum::EVM(self.chain_id), |chain| {
+            chain.evm()?.pending_with_nonce(self.nonce)?;
+
+            Ok(())
+        })?
+    }
+
+    fn method_name(&self) -> String {
+        "evm_cancel_nonce".to_string()
+    }
+
+    fn title(&self) -> String {
+        format!("Cancel EVM transaction with nonce {}", self.nonce)
+    }
+
+    fn message(&self) -> String {
+        format!(
+            "Cancel the pending transaction with nonce {} on {}",
+            self.nonce,
+            with_setting(|s| s.evm_network_name(self.chain_id))
+        )
+    }
+}
@@ -8,14 +8,13 @@ use b3wallet_lib::{
     error::WalletError,
     ledger::{
         evm::{
-            evm::EvmSignTrait,
-            london::EvmTransaction1559,
+            gas::EvmFeeRequest,
+            u256::U256,
             utils::{create_address_from, vec_u8_to_string},
         },
-        subaccount::SubaccountEcdsaTrait,
         types::ChainEnum,
     },
-    store::{with_chain, with_ledger},
+    store::{with_chain, with_setting},
 };
 use candid::{CandidType, Deserialize};
 
@@ -24,53 +23,51 @@ use candid::{CandidType, Deserialize};
 pub struct EvmDeployContract {
     account_id: String,
     chain_id: u64,
-    nonce: u64,
     hex_byte_code: Vec<u8>,
     gas_limit: Option<u64>,
     max_fee_per_gas: Option<u64>,
     max_priority_fee_per_gas: Option<u64>,
 }
 
+impl EvmDeployContract {
+    fn data(&self) -> String {
+        "0x".to_owned() + &vec_u8_to_string(&self.hex_byte_code)
+    }
+}
+
 #[async_trait]
 impl OperationTrait for EvmDeployContract {
     async fn execute(self) -> Result<OperationResult, WalletError> {
-        let ledger = with_ledger(&self.account_id, |ledger| ledger.clone())?;
+        let chain_enum = ChainEnum::EVM(self.chain_id);
 
-        let public_key = ledger.public_key()?;
+        let evm = with_chain(&self.account_id, &chain_enum, |chain| chain.evm())??;
 
-        let contract_address = create_address_from(&public_key, self.nonce);
+        let data = self.data();
 
-        let data = "0x".to_owned() + &vec_u8_to_string(&self.hex_byte_code);
+        let fees = evm
+            .fees(
+                "",
+                &U256::zero(),
+                &data,
+                self.gas_limit,
+                self.max_fee_per_gas,
+                self.max_priority_fee_per_gas,
+            )
+            .await?;
 
-        // TODO: get default gas limit from user settings
-        let gas_limit = self.gas_limit.unwrap_or(0);
-        let max_fee_per_gas = self.max_fee_per_gas.unwrap_or(0);
-        let max_priority_fee_per_gas = self.max_priority_fee_per_gas.unwrap_or(0);
-
-        let mut transaction = EvmTransaction1559 {
-            nonce: self.nonce,
-            chain_id: self.chain_id,
-            gas_limit,
-            max_fee_per_gas,
-            max_priority_fee_per_gas,
-            to: "0x".to_string(),
-            value: 0,
-            data,
-            access_list: vec![],
-            v: "0x00".to_string(),
-            r: "0x00".to_string(),
-            s: "0x00".to_string(),
-        };
+        let pending = evm
+            .send_call("0x", U256::zero(), data.clone(), &fees)
+            .await?;
 
-        let raw_tx = transaction.serialized();
+        // the address of the contract depends on the nonce it was deployed with
+        let contract_address = create_address_from(&evm.ecdsa_public_key, pending.nonce);
 
-        let signature = ledger.subaccount.sign_with_ecdsa(raw_tx).await?;
-
-        transaction.sign(signature, *public_key)?;
+        let transaction = evm.call_transaction(pending.nonce, "0x", U256::zero(), data, &fees);
 
         Ok(EvmContractDeployed {
             transaction,
             contract_address,
+            tx_hash: pending.tx_hash,
         }
         .into())
     }
@@ -83,11 +80,30 @@ impl OperationTrait for EvmDeployContract {
         "evm_deploy_contract".to_string()
     }
 
+    fn evm_fee_request(&self) -> Option<EvmFeeRequest> {
+        Some(EvmFeeRequest {
+            account_id: self.account_id.clone(),
+            chain_id: self.chain_id,
+            to: "".to_string(),
+            value: U256::zero(),
+            data: self.data(),
+            gas_limit: self.gas_limit,
+            max_fee_per_gas: self.max_fee_per_gas,
+            max_priority_fee_per_gas: self.max_priority_fee_per_gas,
+        })
+    }
+
     fn title(&self) -> String {
-        format!("Deploy contract on EVM chain {}", self.chain_id)
+        format!(
+            "Deploy contract on {}",
+            with_setting(|s| s.evm_network_name(self.chain_id))
+        )
     }
 
     fn message(&self) -> String {
-        format!("Deploy contract on EVM chain {}", self.chain_id)
+        format!(
+            "Deploy contract on {}",
+            with_setting(|s| s.evm_network_name(self.chain_id))
+        )
     }
 }
@@ -2,23 +2,123 @@ use crate::{
     error::OperationError,
     operation::{
         result::{
-            EvmMessageSigned, EvmRawTransactionSigned, EvmTransactionSigned, OperationResult,
+            EvmMessageSigned, EvmPersonalMessageSigned, EvmRawTransactionSigned,
+            EvmTransactionSigned, EvmTypedDataSigned, OperationResult,
         },
         OperationTrait,
     },
 };
 use async_trait::async_trait;
+use b3_utils::vec_to_hex_string_with_0x;
 use b3wallet_lib::{
     error::WalletError,
     ledger::{
-        evm::evm::{get_evm_transaction, EvmSignTrait, EvmTransaction},
+        chain::ChainTrait,
+        evm::{
+            abi::AbiFunction,
+            eip712::TypedData,
+            error::EvmError,
+            evm::{get_evm_transaction, EvmSignTrait, EvmTransaction},
+            gas::EvmFeeRequest,
+            utils::{hash_personal_message, string_to_vec_u8},
+        },
         subaccount::SubaccountEcdsaTrait,
-        types::ChainEnum,
+        types::{ChainEnum, PendingEnum},
     },
-    store::{with_chain, with_ledger},
+    store::{with_chain, with_chain_mut, with_ledger, with_setting},
 };
 use candid::{CandidType, Deserialize};
 
+/// The token calls the data of a transaction is decoded as for the signers.
+const KNOWN_CALLS: [&str; 3] = [
+    "transfer(address,uint256)",
+    "approve(address,uint256)",
+    "transferFrom(address,address,uint256)",
+];
+
+/// The data of the transaction as the call it makes when it is a known one,
+/// the raw hex otherwise.
+fn describe_data(data: &str) -> String {
+    let hex = data.strip_prefix("0x").unwrap_or(data);
+
+    if hex.is_empty() {
+        return "no data".to_string();
+    }
+
+    if hex.len() % 2 != 0 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return format!("data {}", data);
+    }
+
+    let bytes = string_to_vec_u8(hex);
+
+    KNOWN_CALLS
+        .iter()
+        .find_map(|signature| {
+            let function = AbiFunction::parse(signature).ok()?;
+            let args = function.decode_call(&bytes).ok()?;
+
+            Some(format!("call {}", function.display_call(&args)))
+        })
+        .unwrap_or_else(|| format!("data 0x{}", hex))
+}
+
+/// What the transaction does and what it pays, for the signers to read.
+fn describe_transaction(chain_id: u64, transaction: &EvmTransaction) -> String {
+    let destination = match transaction.to() {
+        "" => "deploy a contract".to_string(),
+        to => format!("send {} wei to {}", transaction.value(), to),
+    };
+
+    let mut message = format!(
+        "{} on {} with {}, nonce {}, gas limit {}, max fee {} wei per gas",
+        destination,
+        with_setting(|s| s.evm_network_name(chain_id)),
+        describe_data(transaction.data()),
+        transaction.nonce(),
+        transaction.gas_limit(),
+        transaction.max_fee_per_gas()
+    );
+
+    if let Some(priority_fee) = transaction.max_priority_fee_per_gas() {
+        message += &format!(", priority fee {} wei per gas", priority_fee);
+    }
+
+    message
+}
+
+/// The transaction quoted with the fees it carries, so the cap applies to them.
+fn fee_request(account_id: &str, chain_id: u64, transaction: &EvmTransaction) -> EvmFeeRequest {
+    let max_fee_per_gas = transaction.max_fee_per_gas().to_u64().unwrap_or(u64::MAX);
+    let max_priority_fee_per_gas = transaction
+        .max_priority_fee_per_gas()
+        .map_or(Some(max_fee_per_gas), |fee| fee.to_u64());
+
+    EvmFeeRequest {
+        gas_limit: Some(transaction.gas_limit()),
+        max_fee_per_gas: Some(max_fee_per_gas),
+        max_priority_fee_per_gas: Some(max_priority_fee_per_gas.unwrap_or(u64::MAX)),
+        ..simulation_request(account_id, chain_id, transaction)
+    }
+}
+
+/// The call the given transaction makes, its own fees are left to it.
+fn simulation_request(
+    account_id: &str,
+    chain_id: u64,
+    transaction: &EvmTransaction,
+) -> EvmFeeRequest {
+    EvmFeeRequest {
+        account_id: account_id.to_string(),
+        chain_id,
+        to: transaction.to().to_string(),
+        value: transaction.value().clone(),
+        data: transaction.data().to_string(),
+        gas_limit: None,
+        max_fee_per_gas: None,
+        max_priority_fee_per_gas: None,
+    }
+}
+
 // EVM TRANSACTION
 #[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
 pub struct EvmSignTranscation {
@@ -30,38 +130,65 @@ pub struct EvmSignTranscation {
 #[async_trait]
 impl OperationTrait for EvmSignTranscation {
     async fn execute(self) -> Result<OperationResult, WalletError> {
-        let ledger = with_ledger(&self.account_id, |ledger| ledger.clone())?;
-        let public_key = ledger.public_key()?;
+        let chain_enum = ChainEnum::EVM(self.chain_id);
 
-        let mut transaction = self.transaction.clone();
+        let evm = with_chain(&self.account_id, &chain_enum, |chain| chain.evm())??;
 
-        transaction.unsigned_serialized();
+        // the fee cap may have been lowered since the request was made
+        evm.check_transaction(&self.transaction)?;
 
-        let signature = ledger
-            .subaccount
-            .sign_with_ecdsa(transaction.serialized())
-            .await?;
+        let mut transaction = self.transaction.clone();
+
+        let pending = evm.send_with_own_nonce(&mut transaction).await?;
 
-        transaction.sign(signature, *public_key)?;
+        with_chain_mut(&self.account_id, chain_enum, |chain| {
+            chain.add_pending(PendingEnum::EVM(pending.clone()))
+        })?;
 
-        Ok(EvmTransactionSigned(self, transaction.tx_id()).into())
+        Ok(EvmTransactionSigned(self, pending).into())
     }
 
     fn validate_request(&self) -> Result<(), OperationError> {
         // check if the chain id is initialized
-        with_chain(&self.account_id, &ChainEnum::EVM(self.chain_id), |_| Ok(()))?
+        let evm = with_chain(&self.account_id, &ChainEnum::EVM(self.chain_id), |chain| {
+            chain.evm()
+        })??;
+
+        // check if the transaction is for this chain and within the fee cap
+        evm.check_transaction(&self.transaction)?;
+
+        Ok(())
     }
 
     fn method_name(&self) -> String {
         "evm_sign_transaction".to_string()
     }
 
+    fn evm_fee_request(&self) -> Option<EvmFeeRequest> {
+        Some(fee_request(
+            &self.account_id,
+            self.chain_id,
+            &self.transaction,
+        ))
+    }
+
+    fn evm_simulation_request(&self) -> Option<EvmFeeRequest> {
+        Some(simulation_request(
+            &self.account_id,
+            self.chain_id,
+            &self.transaction,
+        ))
+    }
+
     fn title(&self) -> String {
         format!("Sign EVM Transaction {}", self.chain_id)
     }
 
     fn message(&self) -> String {
-        format!("Sign EVM Transaction {}", self.chain_id)
+        format!(
+            "Sign and send a transaction to {}",
+            describe_transaction(self.chain_id, &self.transaction)
+        )
     }
 }
 
@@ -90,20 +217,13 @@ impl TryFrom<EvmSignRawTransaction> for EvmSignTranscation {
 #[async_trait]
 impl OperationTrait for EvmSignRawTransaction {
     async fn execute(self) -> Result<OperationResult, WalletError> {
-        let ledger = with_ledger(&self.account_id, |ledger| ledger.clone())?;
-
-        let public_key = ledger.public_key()?;
+        let evm = with_chain(&self.account_id, &ChainEnum::EVM(self.chain_id), |chain| {
+            chain.evm()
+        })??;
 
         let mut transaction = get_evm_transaction(&self.hex_raw_tx, self.chain_id)?;
 
-        transaction.unsigned_serialized();
-
-        let signature = ledger
-            .subaccount
-            .sign_with_ecdsa(transaction.serialized())
-            .await?;
-
-        transaction.sign(signature, *public_key)?;
+        evm.sign_transaction(&mut transaction).await?;
 
         Ok(EvmRawTransactionSigned(self, transaction.tx_id()).into())
     }
@@ -112,16 +232,8 @@ impl OperationTrait for EvmSignRawTransaction {
         // check if the chain id is initialized
         with_chain(&self.account_id, &ChainEnum::EVM(self.chain_id), |_| {})?;
 
-        // check if the hex_raw_tx is valid
-        let transaction = get_evm_transaction(&self.hex_raw_tx, self.chain_id)
-            .map_err(|_| OperationError::InvalidTransaction)?;
-
-        let chain_id = transaction.chain_id();
-
-        // check if the transaction is valid
-        if chain_id != self.chain_id {
-            return Err(OperationError::InvalidChainId(chain_id, self.chain_id));
-        }
+        // check if the hex_raw_tx is a valid transaction for the chain
+        get_evm_transaction(&self.hex_raw_tx, self.chain_id)?;
 
         Ok(())
     }
@@ -130,12 +242,28 @@ impl OperationTrait for EvmSignRawTransaction {
         "evm_sign_transaction".to_string()
     }
 
+    fn evm_simulation_request(&self) -> Option<EvmFeeRequest> {
+        let transaction = get_evm_transaction(&self.hex_raw_tx, self.chain_id).ok()?;
+
+        Some(simulation_request(
+            &self.account_id,
+            self.chain_id,
+            &transaction,
+        ))
+    }
+
     fn title(&self) -> String {
         format!("Sign EVM Transaction {}", self.chain_id)
     }
 
     fn message(&self) -> String {
-        format!("Sign EVM Transaction {}", self.chain_id)
+        match get_evm_transaction(&self.hex_raw_tx, self.chain_id) {
+            Ok(transaction) => format!(
+                "Sign without sending a transaction to {}",
+                describe_transaction(self.chain_id, &transaction)
+            ),
+            Err(_) => format!("Sign EVM Transaction {}", self.chain_id),
+        }
     }
 }
 
@@ -164,10 +292,10 @@ impl OperationTrait for EvmSignMessage {
         // check if the chain id is initialized
         with_chain(&self.account_id, &ChainEnum::EVM(self.chain_id), |_| {})?;
 
-        // check if the message is not sneaky transaction
+        // check if the message is not sneaky transaction, for this chain or any other
         let transaction = get_evm_transaction(&self.message, self.chain_id);
 
-        if transaction.is_ok() {
+        if matches!(transaction, Ok(_) | Err(EvmError::InvalidChainId(..))) {
             return Err(OperationError::SneakyMessage);
         }
 
@@ -186,3 +314,150 @@ impl OperationTrait for EvmSignMessage {
         format!("Sign EVM Message {}", self.chain_id)
     }
 }
+
+// EVM PERSONAL SIGN
+/// Sign a message the EIP-191 way of `personal_sign`, prefixed and hashed.
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
+pub struct EvmSignPersonalMessage {
+    pub account_id: String,
+    pub chain_id: u64,
+    pub message: Vec<u8>,
+}
+
+#[async_trait]
+impl OperationTrait for EvmSignPersonalMessage {
+    async fn execute(self) -> Result<OperationResult, WalletError> {
+        let evm = with_chain(&self.account_id, &ChainEnum::EVM(self.chain_id), |chain| {
+            chain.evm()
+        })??;
+
+        let signature = evm.sign_hash(hash_personal_message(&self.message)).await?;
+
+        Ok(EvmPersonalMessageSigned(self, signature).into())
+    }
+
+    fn validate_request(&self) -> Result<(), OperationError> {
+        // check if the chain id is initialized
+        with_chain(&self.account_id, &ChainEnum::EVM(self.chain_id), |_| {})?;
+
+        Ok(())
+    }
+
+    fn method_name(&self) -> String {
+        "evm_sign_personal_message".to_string()
+    }
+
+    fn title(&self) -> String {
+        format!("Sign EVM Personal Message {}", self.chain_id)
+    }
+
+    fn message(&self) -> String {
+        // dapps mostly ask to sign text, show the bytes when it is not
+        let message = match std::str::from_utf8(&self.message) {
+            Ok(text) => text.to_string(),
+            Err(_) => vec_to_hex_string_with_0x(&self.message),
+        };
+
+        format!(
+            "Sign the message on {}:\n{}",
+            with_setting(|s| s.evm_network_name(self.chain_id)),
+            message
+        )
+    }
+}
+
+// EVM SIGN TYPED DATA
+/// A signature for another chain could be replayed there, the domain has to
+/// be for the chain of the request when it names one.
+fn check_typed_data_chain(typed_data: &TypedData, chain_id: u64) -> Result<(), EvmError> {
+    match typed_data.chain_id()? {
+        Some(domain_chain_id) if domain_chain_id != chain_id => {
+            Err(EvmError::InvalidChainId(chain_id, domain_chain_id))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Sign EIP-712 typed data the way of `eth_signTypedData_v4`,
+/// `typed_data` being the JSON of the domain, types and message.
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
+pub struct EvmSignTypedData {
+    pub account_id: String,
+    pub chain_id: u64,
+    pub typed_data: String,
+}
+
+#[async_trait]
+impl OperationTrait for EvmSignTypedData {
+    async fn execute(self) -> Result<OperationResult, WalletError> {
+        let evm = with_chain(&self.account_id, &ChainEnum::EVM(self.chain_id), |chain| {
+            chain.evm()
+        })??;
+
+        let typed_data = TypedData::from_json(&self.typed_data)?;
+
+        check_typed_data_chain(&typed_data, self.chain_id)?;
+
+        let hash = typed_data.hash()?;
+
+        let signature = evm.sign_hash(hash).await?;
+
+        Ok(EvmTypedDataSigned(self, signature).into())
+    }
+
+    fn validate_request(&self) -> Result<(), OperationError> {
+        // check if the chain id is initialized
+        with_chain(&self.account_id, &ChainEnum::EVM(self.chain_id), |_| {})?;
+
+        let typed_data = TypedData::from_json(&self.typed_data)?;
+
+        typed_data.hash()?;
+
+        check_typed_data_chain(&typed_data, self.chain_id)?;
+
+        Ok(())
+    }
+
+    fn method_name(&self) -> String {
+        "evm_sign_typed_data".to_string()
+    }
+
+    fn title(&self) -> String {
+        format!("Sign EVM Typed Data {}", self.chain_id)
+    }
+
+    fn message(&self) -> String {
+        match TypedData::from_json(&self.typed_data) {
+            Ok(typed_data) => format!(
+                "Sign the typed data on {}:\n{}",
+                with_setting(|s| s.evm_network_name(self.chain_id)),
+                typed_data
+            ),
+            Err(_) => format!(
+                "Sign the typed data on {}:\n{}",
+                with_setting(|s| s.evm_network_name(self.chain_id)),
+                self.typed_data
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use b3wallet_lib::ledger::evm::{u256::U256, utils::get_transfer_data};
+
+    #[test]
+    fn test_describe_data() {
+        let to = "0x7a9d2f53fea15e31f0a89d7f5d9e0e82b0b88ad6";
+        let data = get_transfer_data(to, &U256::from(12345u64)).unwrap();
+
+        assert_eq!(
+            describe_data(&data),
+            "call transfer(0x7a9d2f53fea15e31f0a89d7f5d9e0e82b0b88ad6, 12345)"
+        );
+        assert_eq!(describe_data("0x"), "no data");
+        assert_eq!(describe_data("0xdeadbeef"), "data 0xdeadbeef");
+        assert_eq!(describe_data("0xzz"), "data 0xzz");
+    }
+}
@@ -8,78 +8,167 @@ use async_trait::async_trait;
 use b3wallet_lib::{
     error::WalletError,
     ledger::{
-        evm::{evm::EvmSignTrait, london::EvmTransaction1559, utils::get_transfer_data},
-        subaccount::SubaccountEcdsaTrait,
+        evm::{
+            ens::{is_ens_name, normalize_ens_name, resolve_ens_name},
+            error::EvmError,
+            gas::EvmFeeRequest,
+            u256::U256,
+            utils::{get_transfer_data, validate_address},
+        },
         types::ChainEnum,
     },
-    store::{with_chain, with_ledger},
+    store::{with_chain, with_setting},
 };
 use candid::{CandidType, Deserialize};
 
+/// Resolve the destination when it is an ENS name, the name is kept next to
+/// the address so the signers see both. The name is only ever set from a
+/// resolution, a request naming one itself is rejected.
+async fn resolve_destination(
+    chain_id: u64,
+    to: String,
+    ens_name: Option<String>,
+) -> Result<(String, Option<String>), EvmError> {
+    if let Some(name) = ens_name {
+        return Err(EvmError::UnresolvedEnsName(name));
+    }
+
+    if !is_ens_name(&to) {
+        return Ok((to, None));
+    }
+
+    let name = normalize_ens_name(&to)?;
+
+    let address = resolve_ens_name(chain_id, &name).await?;
+
+    Ok((address, Some(name)))
+}
+
+/// Resolve the name again before sending, it has to still be the destination
+/// the signers approved.
+async fn check_destination(
+    chain_id: u64,
+    to: &str,
+    ens_name: &Option<String>,
+) -> Result<(), EvmError> {
+    let name = match ens_name {
+        Some(name) => name,
+        None => return Ok(()),
+    };
+
+    let address = resolve_ens_name(chain_id, name).await?;
+
+    if !address.eq_ignore_ascii_case(to) {
+        return Err(EvmError::EnsNameMismatch(name.clone(), address));
+    }
+
+    Ok(())
+}
+
+/// The destination as shown to the signers, with the ENS name it was resolved from.
+fn destination(to: &str, ens_name: &Option<String>) -> String {
+    match ens_name {
+        Some(name) => format!("{} ({})", name, to),
+        None => to.to_string(),
+    }
+}
+
 // TRANSFER ETH
 #[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
 pub struct EvmTransfer {
     pub account_id: String,
     pub chain_id: u64,
-    pub nonce: u64,
     pub to: String,
-    pub value: u64,
+    /// The ENS name `to` was resolved from, set by `resolve_ens` when the request is made.
+    pub ens_name: Option<String>,
+    pub value: U256,
     pub gas_limit: Option<u64>,
     pub max_fee_per_gas: Option<u64>,
     pub max_priority_fee_per_gas: Option<u64>,
 }
 
+impl EvmTransfer {
+    /// Replace an ENS name destination with the address it resolves to now.
+    pub async fn resolve_ens(self) -> Result<Self, EvmError> {
+        let (to, ens_name) = resolve_destination(self.chain_id, self.to, self.ens_name).await?;
+
+        Ok(EvmTransfer {
+            to,
+            ens_name,
+            ..self
+        })
+    }
+}
+
 #[async_trait]
 impl OperationTrait for EvmTransfer {
     async fn execute(self) -> Result<OperationResult, WalletError> {
-        let ledger = with_ledger(&self.account_id, |ledger| ledger.clone())?;
-
-        let public_key = ledger.public_key()?;
-
-        // TODO: get default gas limit from user settings
-        let gas_limit = self.gas_limit.unwrap_or(0);
-        let max_fee_per_gas = self.max_fee_per_gas.unwrap_or(0);
-        let max_priority_fee_per_gas = self.max_priority_fee_per_gas.unwrap_or(0);
+        let chain_enum = ChainEnum::EVM(self.chain_id);
 
-        let mut transaction = EvmTransaction1559 {
-            nonce: self.nonce,
-            chain_id: self.chain_id,
-            to: self.to.clone(),
-            value: self.value,
-            data: "0x00".to_string(),
-            max_priority_fee_per_gas,
-            max_fee_per_gas,
-            gas_limit,
-            access_list: vec![],
-            v: "0x00".to_string(),
-            r: "0x00".to_string(),
-            s: "0x00".to_string(),
-        };
+        let evm = with_chain(&self.account_id, &chain_enum, |chain| chain.evm())??;
 
-        let raw_tx = transaction.unsigned_serialized();
+        check_destination(self.chain_id, &self.to, &self.ens_name).await?;
 
-        let _signed = ledger.subaccount.sign_with_ecdsa(raw_tx).await?;
+        let fees = evm
+            .fees(
+                &self.to,
+                &self.value,
+                "",
+                self.gas_limit,
+                self.max_fee_per_gas,
+                self.max_priority_fee_per_gas,
+            )
+            .await?;
 
-        transaction.sign(_signed, *public_key)?;
+        let pending = evm
+            .send_call(&self.to, self.value.clone(), "".to_string(), &fees)
+            .await?;
 
-        Ok(EvmTransfered(self, transaction.tx_id()).into())
+        Ok(EvmTransfered(self, pending).into())
     }
 
     fn validate_request(&self) -> Result<(), OperationError> {
         // check if the chain id is initialized
-        with_chain(&self.account_id, &ChainEnum::EVM(self.chain_id), |_| Ok(()))?
+        with_chain(&self.account_id, &ChainEnum::EVM(self.chain_id), |_| {})?;
+
+        // ENS names are resolved when the request is made
+        validate_address(&self.to)?;
+
+        Ok(())
     }
 
     fn method_name(&self) -> String {
         "evm_transfer_eth".to_string()
     }
 
+    fn evm_fee_request(&self) -> Option<EvmFeeRequest> {
+        Some(EvmFeeRequest {
+            account_id: self.account_id.clone(),
+            chain_id: self.chain_id,
+            to: self.to.clone(),
+            value: self.value.clone(),
+            data: "".to_string(),
+            gas_limit: self.gas_limit,
+            max_fee_per_gas: self.max_fee_per_gas,
+            max_priority_fee_per_gas: self.max_priority_fee_per_gas,
+        })
+    }
+
     fn title(&self) -> String {
         format!("Transfer {} ETH", self.value)
     }
 
     fn message(&self) -> String {
-        format!("Transfer {} ETH", self.value)
+        let symbol = with_setting(|s| s.evm_network(self.chain_id))
+            .map_or("ETH".to_string(), |network| network.symbol);
+
+        format!(
+            "Transfer {} wei of {} to {} on {}",
+            self.value,
+            symbol,
+            destination(&self.to, &self.ens_name),
+            with_setting(|s| s.evm_network_name(self.chain_id))
+        )
     }
 }
 
@@ -88,67 +177,98 @@ impl OperationTrait for EvmTransfer {
 pub struct EvmTransferErc20 {
     pub account_id: String,
     pub chain_id: u64,
-    pub nonce: u64,
     pub to: String,
-    pub value: u64,
+    /// The ENS name `to` was resolved from, set by `resolve_ens` when the request is made.
+    pub ens_name: Option<String>,
+    pub value: U256,
     pub contract_address: String,
     pub gas_limit: Option<u64>,
     pub max_fee_per_gas: Option<u64>,
     pub max_priority_fee_per_gas: Option<u64>,
 }
 
+impl EvmTransferErc20 {
+    /// Replace an ENS name destination with the address it resolves to now.
+    pub async fn resolve_ens(self) -> Result<Self, EvmError> {
+        let (to, ens_name) = resolve_destination(self.chain_id, self.to, self.ens_name).await?;
+
+        Ok(EvmTransferErc20 {
+            to,
+            ens_name,
+            ..self
+        })
+    }
+}
+
 #[async_trait]
 impl OperationTrait for EvmTransferErc20 {
     async fn execute(self) -> Result<OperationResult, WalletError> {
-        let ledger = with_ledger(&self.account_id, |ledger| ledger.clone())?;
+        let chain_enum = ChainEnum::EVM(self.chain_id);
 
-        let public_key = ledger.public_key()?;
+        let evm = with_chain(&self.account_id, &chain_enum, |chain| chain.evm())??;
 
-        let data = "0x".to_owned() + &get_transfer_data(&self.to, self.value)?;
+        check_destination(self.chain_id, &self.to, &self.ens_name).await?;
 
-        // TODO: get default gas limit from user settings
-        let gas_limit = self.gas_limit.unwrap_or(0);
-        let max_fee_per_gas = self.max_fee_per_gas.unwrap_or(0);
-        let max_priority_fee_per_gas = self.max_priority_fee_per_gas.unwrap_or(0);
+        let data = "0x".to_owned() + &get_transfer_data(&self.to, &self.value)?;
 
-        let mut transaction = EvmTransaction1559 {
-            nonce: self.nonce,
-            chain_id: self.chain_id,
-            max_priority_fee_per_gas,
-            gas_limit,
-            max_fee_per_gas,
-            to: self.contract_address.clone(),
-            value: 0,
-            data,
-            access_list: vec![],
-            v: "0x00".to_string(),
-            r: "0x00".to_string(),
-            s: "0x00".to_string(),
-        };
-
-        let raw_tx = transaction.unsigned_serialized();
-
-        let signature = ledger.subaccount.sign_with_ecdsa(raw_tx).await?;
+        let fees = evm
+            .fees(
+                &self.contract_address,
+                &U256::zero(),
+                &data,
+                self.gas_limit,
+                self.max_fee_per_gas,
+                self.max_priority_fee_per_gas,
+            )
+            .await?;
 
-        transaction.sign(signature, *public_key)?;
+        let pending = evm
+            .send_call(&self.contract_address, U256::zero(), data, &fees)
+            .await?;
 
-        Ok(EvmErc20Transfered(self, transaction.tx_id()).into())
+        Ok(EvmErc20Transfered(self, pending).into())
     }
 
     fn validate_request(&self) -> Result<(), OperationError> {
         // check if the chain id is initialized
-        with_chain(&self.account_id, &ChainEnum::EVM(self.chain_id), |_| Ok(()))?
+        with_chain(&self.account_id, &ChainEnum::EVM(self.chain_id), |_| {})?;
+
+        // ENS names are resolved when the request is made
+        validate_address(&self.to)?;
+
+        Ok(())
     }
 
     fn method_name(&self) -> String {
         self.contract_address.clone()
     }
 
+    fn evm_fee_request(&self) -> Option<EvmFeeRequest> {
+        let data = "0x".to_owned() + &get_transfer_data(&self.to, &self.value).ok()?;
+
+        Some(EvmFeeRequest {
+            account_id: self.account_id.clone(),
+            chain_id: self.chain_id,
+            to: self.contract_address.clone(),
+            value: U256::zero(),
+            data,
+            gas_limit: self.gas_limit,
+            max_fee_per_gas: self.max_fee_per_gas,
+            max_priority_fee_per_gas: self.max_priority_fee_per_gas,
+        })
+    }
+
     fn title(&self) -> String {
         format!("Transfer {} ERC20", self.value)
     }
 
     fn message(&self) -> String {
-        format!("Transfer {} ERC20", self.value)
+        format!(
+            "Transfer {} of ERC20 {} to {} on {}",
+            self.value,
+            self.contract_address,
+            destination(&self.to, &self.ens_name),
+            with_setting(|s| s.evm_network_name(self.chain_id))
+        )
     }
 }
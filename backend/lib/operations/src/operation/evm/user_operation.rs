@@ -0,0 +1,292 @@
+use crate::{
+    error::OperationError,
+    operation::{
+        result::{EvmUserOperationSigned, OperationResult},
+        OperationTrait,
+    },
+};
+use async_trait::async_trait;
+use b3_utils::vec_to_hex_string_with_0x;
+use b3wallet_lib::{
+    error::WalletError,
+    ledger::{
+        chain::ChainTrait,
+        evm::{
+            abi::AbiFunction,
+            error::EvmError,
+            u256::U256,
+            user_operation::{Erc4337Config, SignedUserOperation, UserOperation},
+            utils::vec_u8_to_string,
+        },
+        types::ChainEnum,
+    },
+    store::{with_chain, with_setting, with_setting_mut},
+};
+use candid::{CandidType, Deserialize};
+
+// EVM USER OPERATION
+/// Sign an ERC-4337 `UserOperation` as the owner of a smart account, and send
+/// it to the bundler of the network if one is configured.
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
+pub struct EvmSignUserOperation {
+    pub account_id: String,
+    pub chain_id: u64,
+    /// Picks one of the smart accounts the key owns through the factory.
+    pub salt: U256,
+    /// The call the smart account makes, e.g. `execute(address,uint256,bytes)`.
+    pub call_data: Vec<u8>,
+    pub call_gas_limit: u64,
+    pub verification_gas_limit: u64,
+    pub pre_verification_gas: u64,
+    pub max_fee_per_gas: Option<u64>,
+    pub max_priority_fee_per_gas: Option<u64>,
+    pub paymaster_and_data: Vec<u8>,
+    /// The smart account and the EntryPoint the operation goes through, set by
+    /// `resolve_smart_account` when the request is made.
+    pub sender: Option<String>,
+    pub entry_point: Option<String>,
+}
+
+impl EvmSignUserOperation {
+    /// Look up the smart account of the salt and the EntryPoint now, so the
+    /// signers approve the ones the operation is sent through.
+    pub async fn resolve_smart_account(self) -> Result<Self, WalletError> {
+        let evm = with_chain(&self.account_id, &ChainEnum::EVM(self.chain_id), |chain| {
+            chain.evm()
+        })??;
+
+        let config = with_setting(|s| s.erc4337_config(self.chain_id))?;
+
+        let sender = evm.smart_account_address(&config, &self.salt).await?;
+
+        Ok(EvmSignUserOperation {
+            sender: Some(sender),
+            entry_point: Some(config.entry_point),
+            ..self
+        })
+    }
+
+    /// The call of the smart account as it reads in Solidity, when it is an `execute`.
+    fn decoded_call(&self) -> String {
+        let execute = AbiFunction::parse("execute(address,uint256,bytes)").and_then(|function| {
+            function
+                .decode_call(&self.call_data)
+                .map(|args| (function, args))
+        });
+
+        match execute {
+            Ok((function, args)) => function.display_call(&args),
+            Err(_) => vec_to_hex_string_with_0x(&self.call_data),
+        }
+    }
+}
+
+#[async_trait]
+impl OperationTrait for EvmSignUserOperation {
+    async fn execute(self) -> Result<OperationResult, WalletError> {
+        let evm = with_chain(&self.account_id, &ChainEnum::EVM(self.chain_id), |chain| {
+            chain.evm()
+        })??;
+
+        let config = with_setting(|s| s.erc4337_config(self.chain_id))?;
+
+        let sender = evm.smart_account_address(&config, &self.salt).await?;
+
+        // the factory or the EntryPoint changed since the signers approved
+        if self.sender.as_ref() != Some(&sender)
+            || self.entry_point.as_ref() != Some(&config.entry_point)
+        {
+            return Err(EvmError::Erc4337ConfigChanged(self.chain_id).into());
+        }
+
+        // the EntryPoint deploys the smart account with its first operation
+        let init_code = if evm.is_deployed(&sender).await? {
+            vec![]
+        } else {
+            config.init_code(&evm.address, &self.salt)?
+        };
+
+        let nonce = evm.smart_account_nonce(&config, &sender).await?;
+
+        let (max_fee_per_gas, max_priority_fee_per_gas) =
+            match (self.max_fee_per_gas, self.max_priority_fee_per_gas) {
+                (Some(max_fee), Some(priority_fee)) => (max_fee, priority_fee),
+                (max_fee, priority_fee) => {
+                    let (suggested_max_fee, suggested_priority_fee) = evm.suggested_fees().await?;
+
+                    (
+                        max_fee.unwrap_or(suggested_max_fee),
+                        priority_fee.unwrap_or(suggested_priority_fee),
+                    )
+                }
+            };
+
+        evm.gas_policy().check_max_fee(max_fee_per_gas)?;
+
+        let mut user_operation = UserOperation {
+            sender,
+            nonce,
+            init_code,
+            call_data: self.call_data.clone(),
+            call_gas_limit: self.call_gas_limit.into(),
+            verification_gas_limit: self.verification_gas_limit.into(),
+            pre_verification_gas: self.pre_verification_gas.into(),
+            max_fee_per_gas: max_fee_per_gas.into(),
+            max_priority_fee_per_gas: max_priority_fee_per_gas.min(max_fee_per_gas).into(),
+            paymaster_and_data: self.paymaster_and_data.clone(),
+            signature: vec![],
+        };
+
+        let hash = evm
+            .sign_user_operation(&config, &mut user_operation)
+            .await?;
+
+        let bundler = match config.bundler {
+            Some(ref bundler) => {
+                evm.rpc()
+                    .send_user_operation(
+                        self.chain_id,
+                        bundler,
+                        &user_operation,
+                        &config.entry_point,
+                    )
+                    .await?;
+
+                Some(bundler.url.clone())
+            }
+            None => None,
+        };
+
+        let signed = SignedUserOperation {
+            user_operation,
+            hash: "0x".to_owned() + &vec_u8_to_string(&hash.to_vec()),
+            bundler,
+        };
+
+        Ok(EvmUserOperationSigned(self, signed).into())
+    }
+
+    fn validate_request(&self) -> Result<(), OperationError> {
+        // check if the chain id is initialized
+        with_chain(&self.account_id, &ChainEnum::EVM(self.chain_id), |_| {})?;
+
+        with_setting(|s| s.erc4337_config(self.chain_id))?;
+
+        if self.call_data.is_empty() {
+            return Err(EvmError::InvalidAbi("empty call data".to_string()).into());
+        }
+
+        if self.call_gas_limit == 0 || self.verification_gas_limit == 0 {
+            return Err(EvmError::InvalidNumber("gas limit 0".to_string()).into());
+        }
+
+        Ok(())
+    }
+
+    fn method_name(&self) -> String {
+        "evm_sign_user_operation".to_string()
+    }
+
+    fn title(&self) -> String {
+        "Sign EVM UserOperation".to_string()
+    }
+
+    fn message(&self) -> String {
+        let fee = |fee: Option<u64>| {
+            fee.map_or("the suggested fee".to_string(), |fee| {
+                format!("{} wei", fee)
+            })
+        };
+
+        let mut message = format!(
+            "Make smart account {} (#{} of {}) call {} through the EntryPoint {} on {}, \
+            with gas limits of {} for the call, {} for verification and {} before, \
+            paying at most {} per gas with {} of priority fee",
+            self.sender.as_deref().unwrap_or("unknown"),
+            self.salt,
+            self.account_id,
+            self.decoded_call(),
+            self.entry_point.as_deref().unwrap_or("unknown"),
+            with_setting(|s| s.evm_network_name(self.chain_id)),
+            self.call_gas_limit,
+            self.verification_gas_limit,
+            self.pre_verification_gas,
+            fee(self.max_fee_per_gas),
+            fee(self.max_priority_fee_per_gas),
+        );
+
+        // the paymaster address comes first, its data after
+        if self.paymaster_and_data.len() >= 20 {
+            let paymaster = self.paymaster_and_data[..20].to_vec();
+
+            message += &format!(", with gas paid by 0x{}", vec_u8_to_string(&paymaster));
+        }
+
+        message
+    }
+}
+
+// UPDATE ERC-4337 CONFIG
+/// Set the ERC-4337 contracts and bundler the smart accounts use on a network,
+/// or stop signing operations there when not set.
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
+pub struct UpdateErc4337Config {
+    pub chain_id: u64,
+    pub config: Option<Erc4337Config>,
+}
+
+#[async_trait]
+impl OperationTrait for UpdateErc4337Config {
+    async fn execute(self) -> Result<OperationResult, WalletError> {
+        with_setting_mut(|s| match self.config {
+            Some(ref config) => s.set_erc4337_config(config.clone()),
+            None => s.remove_erc4337_config(self.chain_id),
+        })?;
+
+        Ok(self.into())
+    }
+
+    fn validate_request(&self) -> Result<(), OperationError> {
+        match self.config {
+            Some(ref config) => {
+                if config.chain_id != self.chain_id {
+                    return Err(EvmError::InvalidChainId(self.chain_id, config.chain_id).into());
+                }
+
+                config.validate()?;
+            }
+            None => {
+                with_setting(|s| s.erc4337_config(self.chain_id))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn method_name(&self) -> String {
+        "update_erc4337_config".to_string()
+    }
+
+    fn title(&self) -> String {
+        "Update the ERC-4337 config".to_string()
+    }
+
+    fn message(&self) -> String {
+        let network = with_setting(|s| s.evm_network_name(self.chain_id));
+
+        match self.config {
+            Some(ref config) => format!(
+                "Sign UserOperations on {} for the EntryPoint {} with smart accounts \
+                of the factory {}, {}",
+                network,
+                config.entry_point,
+                config.factory,
+                config.bundler.as_ref().map_or(
+                    "without sending them".to_string(),
+                    |bundler| format!("sending them to {}", bundler.url)
+                )
+            ),
+            None => format!("Stop signing UserOperations on {}", network),
+        }
+    }
+}
@@ -1,10 +1,10 @@
 use crate::operation::result::OperationResult;
 use async_trait::async_trait;
-use b3_utils::ledger::currency::TokenAmount;
+use b3_utils::{ledger::currency::TokenAmount, vec_to_hex_string_with_0x};
 use b3wallet_lib::{
     error::WalletError,
-    ledger::{chain::ChainTrait, types::ChainEnum},
-    store::{with_account, with_chain},
+    ledger::{chain::ChainTrait, options::SendOptions, types::ChainEnum},
+    store::{with_account, with_chain, with_chain_mut, with_setting},
 };
 use candid::{CandidType, Deserialize};
 
@@ -18,6 +18,7 @@ pub struct SendToken {
     pub chain: ChainEnum,
     pub amount: TokenAmount,
     pub account_id: String,
+    pub options: Option<SendOptions>,
 }
 
 #[async_trait]
@@ -25,10 +26,22 @@ impl OperationTrait for SendToken {
     async fn execute(self) -> Result<OperationResult, WalletError> {
         let chain = with_chain(&self.account_id, &self.chain, |chain| chain.clone())?;
 
-        let result = chain.send(self.to.clone(), self.amount).await;
+        let options = self.options.clone().unwrap_or_default();
+
+        let result = chain
+            .send(self.to.clone(), self.amount.clone(), options)
+            .await;
 
         match result {
-            Ok(result) => Ok(TokenSent(self, result).into()),
+            Ok(result) => {
+                if let Some(pending) = result.pending() {
+                    with_chain_mut(&self.account_id, self.chain.clone(), |chain| {
+                        chain.add_pending(pending)
+                    })?;
+                }
+
+                Ok(TokenSent(self, result).into())
+            }
             Err(err) => return Err(WalletError::ExecutionError(err.to_string())),
         }
     }
@@ -43,13 +56,13 @@ impl OperationTrait for SendToken {
                 return Err(OperationError::AccountIsHidden);
             }
 
-            account
-                .ledger()
-                .chain(&self.chain)
-                .map(|_| ())
-                .map_err(|_| {
-                    OperationError::ChainNotFound(self.chain.to_string(), self.account_id.clone())
-                })?;
+            let chain = account.ledger().chain(&self.chain).map_err(|_| {
+                OperationError::ChainNotFound(self.chain.to_string(), self.account_id.clone())
+            })?;
+
+            if let Some(options) = &self.options {
+                chain.check_send_options(&self.to, options)?;
+            }
 
             Ok(())
         })
@@ -61,20 +74,54 @@ impl OperationTrait for SendToken {
     }
 
     fn title(&self) -> String {
-        format!("Send {} {}", self.amount, self.chain)
+        format!(
+            "Send {} on {}",
+            self.formatted_amount(),
+            with_setting(|s| s.chain_name(&self.chain))
+        )
     }
 
     fn message(&self) -> String {
         // we already checked that the account exists on validate_request
         let account = with_account(&self.account_id, |account| account.clone()).unwrap();
 
-        format!(
-            "Send {} {} from {}({}) to {}",
-            self.amount,
-            self.chain,
+        let mut message = format!(
+            "Send {} on {} from {}({}) to {}",
+            self.formatted_amount(),
+            with_setting(|s| s.chain_name(&self.chain)),
             account.name(),
             self.account_id,
             self.to
-        )
+        );
+
+        if let Some(options) = &self.options {
+            if let Some(memo) = &options.memo {
+                message += &format!(", memo {}", vec_to_hex_string_with_0x(memo));
+            }
+
+            if let Some(fee) = &options.fee {
+                message += &format!(", paying a fee of {}", self.formatted(fee));
+            }
+
+            if let Some(created_at_time) = options.created_at_time {
+                message += &format!(", created at {} ns", created_at_time);
+            }
+        }
+
+        message
+    }
+}
+
+impl SendToken {
+    /// The amount with the chain's token symbol, e.g. `1.5 ckBTC`.
+    pub fn formatted_amount(&self) -> String {
+        self.formatted(&self.amount)
+    }
+
+    fn formatted(&self, amount: &TokenAmount) -> String {
+        with_chain(&self.account_id, &self.chain, |chain| {
+            chain.token().format(amount)
+        })
+        .unwrap_or_else(|_| amount.to_string())
     }
 }
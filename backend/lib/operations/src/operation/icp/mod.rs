@@ -1,3 +1,5 @@
+pub mod neuron;
 pub mod transfer;
 
+pub use neuron::*;
 pub use transfer::*;
@@ -0,0 +1,447 @@
+use crate::error::OperationError;
+use crate::operation::result::{NeuronDisbursed, NeuronRefreshed, NeuronStaked, OperationResult};
+use crate::operation::OperationTrait;
+use async_trait::async_trait;
+use b3_utils::ledger::currency::ICPToken;
+use b3_utils::ledger::AccountIdentifier;
+use b3_utils::types::CanisterId;
+use b3wallet_lib::error::WalletError;
+use b3wallet_lib::ledger::chain::ChainTrait;
+use b3wallet_lib::ledger::icp::governance::{
+    Governance, IncreaseDissolveDelay, NeuronIdValue, NeuronOperation, StartDissolving,
+    StopDissolving, Vote,
+};
+use b3wallet_lib::ledger::types::ChainEnum;
+use b3wallet_lib::store::{with_chain, with_setting, with_setting_mut};
+use candid::{CandidType, Deserialize};
+use std::str::FromStr;
+
+#[cfg(test)]
+use b3_utils::mocks::id_mock as ic_cdk_id;
+#[cfg(not(test))]
+use ic_cdk::api::id as ic_cdk_id;
+
+// STAKE NEURON
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
+pub struct StakeNeuron {
+    pub account_id: String,
+    pub amount: ICPToken,
+    pub memo: u64,
+}
+
+impl StakeNeuron {
+    /// The governance canister and its account the stake is sent to.
+    fn neuron_account(&self) -> (Governance, AccountIdentifier) {
+        let governance = with_setting(|s| s.governance());
+
+        let account = governance.neuron_account(&ic_cdk_id(), self.memo);
+
+        (governance, account)
+    }
+}
+
+#[async_trait]
+impl OperationTrait for StakeNeuron {
+    async fn execute(self) -> Result<OperationResult, WalletError> {
+        let icp = with_chain(&self.account_id, &ChainEnum::ICP, |chain| chain.icp())??;
+
+        let governance = with_setting(|s| s.governance());
+
+        let neuron_id = icp
+            .stake_neuron(&governance, self.amount.clone(), self.memo)
+            .await?;
+
+        Ok(NeuronStaked(self, neuron_id).into())
+    }
+
+    fn validate_request(&self) -> Result<(), OperationError> {
+        if self.amount.is_zero() {
+            return Err(OperationError::AmountIsZero);
+        }
+
+        with_chain(&self.account_id, &ChainEnum::ICP, |_| Ok(()))?
+    }
+
+    fn method_name(&self) -> String {
+        "stake_neuron".to_string()
+    }
+
+    fn title(&self) -> String {
+        let (governance, _) = self.neuron_account();
+
+        format!("Stake {} ICP in a neuron of {}", self.amount, governance.0)
+    }
+
+    fn message(&self) -> String {
+        let (governance, account) = self.neuron_account();
+
+        format!(
+            "Stake {} ICP from {} in the neuron with memo {}, sending it to {} of the governance canister {}",
+            self.amount, self.account_id, self.memo, account, governance.0
+        )
+    }
+}
+
+// UPDATE GOVERNANCE CANISTER
+/// Send the neuron operations to another governance canister, e.g. a local
+/// stand-in, or back to the NNS one when not set.
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
+pub struct UpdateGovernanceCanister {
+    pub canister_id: Option<CanisterId>,
+}
+
+#[async_trait]
+impl OperationTrait for UpdateGovernanceCanister {
+    async fn execute(self) -> Result<OperationResult, WalletError> {
+        with_setting_mut(|s| s.set_governance_canister_id(self.canister_id));
+
+        Ok(self.into())
+    }
+
+    fn validate_request(&self) -> Result<(), OperationError> {
+        if self.canister_id == Some(ic_cdk_id()) {
+            return Err(OperationError::CannotCallSelf);
+        }
+
+        Ok(())
+    }
+
+    fn method_name(&self) -> String {
+        "update_governance_canister".to_string()
+    }
+
+    fn title(&self) -> String {
+        "Update the governance canister".to_string()
+    }
+
+    fn message(&self) -> String {
+        let current = with_setting(|s| s.governance());
+
+        let governance = self
+            .canister_id
+            .map_or_else(Governance::default, Governance::new);
+
+        format!(
+            "Send the neuron operations and stakes to the governance canister {} instead of {}",
+            governance.0, current.0
+        )
+    }
+}
+
+// REFRESH NEURON
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
+pub struct RefreshNeuron {
+    pub memo: u64,
+}
+
+#[async_trait]
+impl OperationTrait for RefreshNeuron {
+    async fn execute(self) -> Result<OperationResult, WalletError> {
+        let governance = with_setting(|s| s.governance());
+
+        let neuron_id = governance.claim_or_refresh(ic_cdk_id(), self.memo).await?;
+
+        Ok(NeuronRefreshed(self, neuron_id).into())
+    }
+
+    fn validate_request(&self) -> Result<(), OperationError> {
+        Ok(())
+    }
+
+    fn method_name(&self) -> String {
+        "refresh_neuron".to_string()
+    }
+
+    fn title(&self) -> String {
+        format!("Claim or refresh the neuron with memo {}", self.memo)
+    }
+
+    fn message(&self) -> String {
+        format!(
+            "Claim or refresh the stake of the neuron with memo {}",
+            self.memo
+        )
+    }
+}
+
+// INCREASE DISSOLVE DELAY
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
+pub struct IncreaseNeuronDissolveDelay {
+    pub neuron_id: NeuronIdValue,
+    pub additional_dissolve_delay_seconds: u32,
+}
+
+#[async_trait]
+impl OperationTrait for IncreaseNeuronDissolveDelay {
+    async fn execute(self) -> Result<OperationResult, WalletError> {
+        let governance = with_setting(|s| s.governance());
+
+        let operation = NeuronOperation::IncreaseDissolveDelay(IncreaseDissolveDelay {
+            additional_dissolve_delay_seconds: self.additional_dissolve_delay_seconds,
+        });
+
+        governance.configure(self.neuron_id, operation).await?;
+
+        Ok(self.into())
+    }
+
+    fn validate_request(&self) -> Result<(), OperationError> {
+        if self.additional_dissolve_delay_seconds == 0 {
+            return Err(OperationError::InvalidRequest);
+        }
+
+        Ok(())
+    }
+
+    fn method_name(&self) -> String {
+        "increase_neuron_dissolve_delay".to_string()
+    }
+
+    fn title(&self) -> String {
+        format!("Increase dissolve delay of neuron {}", self.neuron_id)
+    }
+
+    fn message(&self) -> String {
+        format!(
+            "Increase the dissolve delay of neuron {} by {} seconds",
+            self.neuron_id, self.additional_dissolve_delay_seconds
+        )
+    }
+}
+
+// START DISSOLVING
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
+pub struct StartNeuronDissolving {
+    pub neuron_id: NeuronIdValue,
+}
+
+#[async_trait]
+impl OperationTrait for StartNeuronDissolving {
+    async fn execute(self) -> Result<OperationResult, WalletError> {
+        let governance = with_setting(|s| s.governance());
+
+        let operation = NeuronOperation::StartDissolving(StartDissolving {});
+
+        governance.configure(self.neuron_id, operation).await?;
+
+        Ok(self.into())
+    }
+
+    fn validate_request(&self) -> Result<(), OperationError> {
+        Ok(())
+    }
+
+    fn method_name(&self) -> String {
+        "start_neuron_dissolving".to_string()
+    }
+
+    fn title(&self) -> String {
+        format!("Start dissolving neuron {}", self.neuron_id)
+    }
+
+    fn message(&self) -> String {
+        format!("Start dissolving neuron {}", self.neuron_id)
+    }
+}
+
+// STOP DISSOLVING
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
+pub struct StopNeuronDissolving {
+    pub neuron_id: NeuronIdValue,
+}
+
+#[async_trait]
+impl OperationTrait for StopNeuronDissolving {
+    async fn execute(self) -> Result<OperationResult, WalletError> {
+        let governance = with_setting(|s| s.governance());
+
+        let operation = NeuronOperation::StopDissolving(StopDissolving {});
+
+        governance.configure(self.neuron_id, operation).await?;
+
+        Ok(self.into())
+    }
+
+    fn validate_request(&self) -> Result<(), OperationError> {
+        Ok(())
+    }
+
+    fn method_name(&self) -> String {
+        "stop_neuron_dissolving".to_string()
+    }
+
+    fn title(&self) -> String {
+        format!("Stop dissolving neuron {}", self.neuron_id)
+    }
+
+    fn message(&self) -> String {
+        format!("Stop dissolving neuron {}", self.neuron_id)
+    }
+}
+
+// DISBURSE NEURON
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
+pub struct DisburseNeuron {
+    pub account_id: String,
+    pub neuron_id: NeuronIdValue,
+    /// Account identifier hex to disburse to, the ICP address of the account if not set.
+    pub to: Option<String>,
+    /// The whole stake if not set.
+    pub amount: Option<ICPToken>,
+}
+
+#[async_trait]
+impl OperationTrait for DisburseNeuron {
+    async fn execute(self) -> Result<OperationResult, WalletError> {
+        let to = match self.to {
+            Some(ref to) => AccountIdentifier::from_str(to)
+                .map_err(|err| WalletError::ExecutionError(err.to_string()))?,
+            None => with_chain(&self.account_id, &ChainEnum::ICP, |chain| chain.icp())??
+                .account_identifier(),
+        };
+
+        let governance = with_setting(|s| s.governance());
+
+        let block_height = governance
+            .disburse(self.neuron_id, Some(to), self.amount.clone())
+            .await?;
+
+        Ok(NeuronDisbursed(self, block_height).into())
+    }
+
+    fn validate_request(&self) -> Result<(), OperationError> {
+        if let Some(ref to) = self.to {
+            AccountIdentifier::from_str(to)
+                .map_err(|err| OperationError::InvalidAddress(err.to_string()))?;
+        }
+
+        if let Some(ref amount) = self.amount {
+            if amount.is_zero() {
+                return Err(OperationError::AmountIsZero);
+            }
+        }
+
+        with_chain(&self.account_id, &ChainEnum::ICP, |_| Ok(()))?
+    }
+
+    fn method_name(&self) -> String {
+        "disburse_neuron".to_string()
+    }
+
+    fn title(&self) -> String {
+        format!("Disburse neuron {}", self.neuron_id)
+    }
+
+    fn message(&self) -> String {
+        let amount = match self.amount {
+            Some(ref amount) => format!("{} ICP", amount),
+            None => "the whole stake".to_string(),
+        };
+
+        let to = match self.to {
+            Some(ref to) => to.clone(),
+            None => with_chain(&self.account_id, &ChainEnum::ICP, |chain| chain.address())
+                .unwrap_or_else(|_| self.account_id.clone()),
+        };
+
+        format!("Disburse {} of neuron {} to {}", amount, self.neuron_id, to)
+    }
+}
+
+// FOLLOW
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
+pub struct FollowNeuron {
+    pub neuron_id: NeuronIdValue,
+    pub topic: i32,
+    pub followees: Vec<NeuronIdValue>,
+}
+
+#[async_trait]
+impl OperationTrait for FollowNeuron {
+    async fn execute(self) -> Result<OperationResult, WalletError> {
+        let governance = with_setting(|s| s.governance());
+
+        governance
+            .follow(self.neuron_id, self.topic, self.followees.clone())
+            .await?;
+
+        Ok(self.into())
+    }
+
+    fn validate_request(&self) -> Result<(), OperationError> {
+        if self.followees.contains(&self.neuron_id) {
+            return Err(OperationError::InvalidRequest);
+        }
+
+        Ok(())
+    }
+
+    fn method_name(&self) -> String {
+        "follow_neuron".to_string()
+    }
+
+    fn title(&self) -> String {
+        format!("Set followees of neuron {}", self.neuron_id)
+    }
+
+    fn message(&self) -> String {
+        if self.followees.is_empty() {
+            return format!(
+                "Remove the followees of neuron {} on topic {}",
+                self.neuron_id, self.topic
+            );
+        }
+
+        let followees = self
+            .followees
+            .iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        format!(
+            "Neuron {} follows {} on topic {}",
+            self.neuron_id, followees, self.topic
+        )
+    }
+}
+
+// VOTE
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
+pub struct VoteNeuron {
+    pub neuron_id: NeuronIdValue,
+    pub proposal_id: u64,
+    pub vote: Vote,
+}
+
+#[async_trait]
+impl OperationTrait for VoteNeuron {
+    async fn execute(self) -> Result<OperationResult, WalletError> {
+        let governance = with_setting(|s| s.governance());
+
+        governance
+            .register_vote(self.neuron_id, self.proposal_id, self.vote)
+            .await?;
+
+        Ok(self.into())
+    }
+
+    fn validate_request(&self) -> Result<(), OperationError> {
+        Ok(())
+    }
+
+    fn method_name(&self) -> String {
+        "vote_neuron".to_string()
+    }
+
+    fn title(&self) -> String {
+        format!("Vote on proposal {}", self.proposal_id)
+    }
+
+    fn message(&self) -> String {
+        format!(
+            "Neuron {} votes {:?} on proposal {}",
+            self.neuron_id, self.vote, self.proposal_id
+        )
+    }
+}
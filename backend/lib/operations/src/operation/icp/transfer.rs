@@ -7,11 +7,12 @@ use crate::operation::OperationTrait;
 use async_trait::async_trait;
 use b3_utils::ledger::currency::ICPToken;
 use b3_utils::ledger::AccountIdentifier;
-use b3_utils::ledger::ICPTransferResult;
+use b3_utils::ledger::ICRCAccount;
 use b3_utils::ledger::NotifyTopUpResult;
 use b3_utils::ledger::TransferMemo;
 use b3_utils::types::CanisterId;
 use b3wallet_lib::error::WalletError;
+use b3wallet_lib::ledger::icp::types::IcpRecipient;
 use b3wallet_lib::ledger::types::ChainEnum;
 use b3wallet_lib::store::with_chain;
 use candid::{CandidType, Deserialize};
@@ -24,6 +25,35 @@ pub struct IcpTransfer {
     pub amount: ICPToken,
     pub fee: Option<ICPToken>,
     pub memo: Option<TransferMemo>,
+    /// Send to this ICRC-1 account with `icrc1_transfer` instead of `to`.
+    pub to_account: Option<ICRCAccount>,
+}
+
+impl IcpTransfer {
+    /// Where the ICP goes, the ICRC-1 account when one is given.
+    pub fn recipient(&self) -> IcpRecipient {
+        match self.to_account {
+            Some(ref account) => IcpRecipient::ICRCAccount(account.clone()),
+            None => IcpRecipient::AccountIdentifier(self.to.clone()),
+        }
+    }
+
+    /// When both are given, `to` has to be the account identifier of `to_account`
+    /// so the signers read the same recipient either way.
+    fn check_recipient(&self) -> Result<(), OperationError> {
+        if let Some(ref account) = self.to_account {
+            let identifier = AccountIdentifier::new(account.owner(), account.subaccount());
+
+            if identifier != self.to {
+                return Err(OperationError::RecipientMismatch(
+                    self.to.to_string(),
+                    account.to_string(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -31,19 +61,16 @@ impl OperationTrait for IcpTransfer {
     async fn execute(self) -> Result<OperationResult, WalletError> {
         let icp = with_chain(&self.account_id, &ChainEnum::ICP, |chain| chain.icp())??;
 
-        let result = icp
-            .transfer(
-                self.to.clone(),
+        let block_index = icp
+            .transfer_to(
+                self.recipient(),
                 self.amount.clone(),
                 self.fee.clone(),
                 self.memo.clone(),
             )
             .await?;
 
-        match result {
-            ICPTransferResult::Ok(block_number) => Ok(IcpTransfered(self, block_number).into()),
-            ICPTransferResult::Err(err) => Err(WalletError::NotifyTopUpError(err.to_string())),
-        }
+        Ok(IcpTransfered(self, block_index).into())
     }
 
     fn validate_request(&self) -> Result<(), OperationError> {
@@ -55,7 +82,7 @@ impl OperationTrait for IcpTransfer {
             return Err(OperationError::FeeIsZero);
         }
 
-        Ok(())
+        self.check_recipient()
     }
 
     fn method_name(&self) -> String {
@@ -67,7 +94,7 @@ impl OperationTrait for IcpTransfer {
     }
 
     fn message(&self) -> String {
-        format!("Transfer {} ICP", self.amount)
+        format!("Transfer {} ICP to {}", self.amount, self.recipient())
     }
 }
 
@@ -154,3 +181,43 @@ impl OperationTrait for NotifyTopUp {
         format!("Top up canister {}", self.canister_id)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use b3_utils::{Environment, Subaccount};
+
+    fn transfer(to: AccountIdentifier, to_account: Option<ICRCAccount>) -> IcpTransfer {
+        IcpTransfer {
+            account_id: "-default".to_string(),
+            to,
+            amount: ICPToken::from_e8s(1_000),
+            fee: None,
+            memo: None,
+            to_account,
+        }
+    }
+
+    #[test]
+    fn test_validate_request_recipient() {
+        let owner = CanisterId::from_text("bkyz2-fmaaa-aaaaa-qaaaq-cai").unwrap();
+        let subaccount = Subaccount::new(Environment::Production, 1);
+
+        let account = ICRCAccount::new(owner, Some(subaccount.clone()));
+        let identifier = AccountIdentifier::new(owner, Some(subaccount));
+
+        assert!(transfer(identifier.clone(), None)
+            .validate_request()
+            .is_ok());
+        assert!(transfer(identifier, Some(account.clone()))
+            .validate_request()
+            .is_ok());
+
+        let other = AccountIdentifier::new(owner, None);
+
+        assert!(matches!(
+            transfer(other, Some(account)).validate_request(),
+            Err(OperationError::RecipientMismatch(..))
+        ));
+    }
+}
@@ -0,0 +1,66 @@
+use crate::{
+    error::OperationError,
+    operation::result::OperationResult,
+    operation::{result::Icrc7Transfered, OperationTrait},
+};
+use async_trait::async_trait;
+use b3_utils::{ledger::ICRCAccount, types::CanisterId};
+use b3wallet_lib::{
+    error::WalletError,
+    ledger::{icrc7::types::TokenId, types::ChainEnum},
+    store::with_chain,
+};
+use candid::{CandidType, Deserialize};
+use std::str::FromStr;
+
+// TRANSFER ICRC7 NFT
+#[derive(CandidType, Clone, Deserialize, Debug, PartialEq)]
+pub struct Icrc7Transfer {
+    pub account_id: String,
+    pub collection: CanisterId,
+    pub to: String,
+    pub token_id: TokenId,
+    pub memo: Option<Vec<u8>>,
+}
+
+#[async_trait]
+impl OperationTrait for Icrc7Transfer {
+    async fn execute(self) -> Result<OperationResult, WalletError> {
+        let chain_enum = ChainEnum::ICRC7(self.collection);
+
+        let icrc7 = with_chain(&self.account_id, &chain_enum, |chain| chain.icrc7())??;
+
+        let to = ICRCAccount::from_str(&self.to)
+            .map_err(|err| WalletError::ExecutionError(err.to_string()))?;
+
+        let tx_index = icrc7
+            .transfer(to, self.token_id.clone(), self.memo.clone())
+            .await?;
+
+        Ok(Icrc7Transfered(self, tx_index).into())
+    }
+
+    fn validate_request(&self) -> Result<(), OperationError> {
+        ICRCAccount::from_str(&self.to)
+            .map_err(|err| OperationError::InvalidAddress(err.to_string()))?;
+
+        with_chain(&self.account_id, &ChainEnum::ICRC7(self.collection), |_| {
+            Ok(())
+        })?
+    }
+
+    fn method_name(&self) -> String {
+        "icrc7_transfer".to_string()
+    }
+
+    fn title(&self) -> String {
+        format!("Transfer NFT #{}", self.token_id)
+    }
+
+    fn message(&self) -> String {
+        format!(
+            "Transfer NFT #{} of collection {} to {}",
+            self.token_id, self.collection, self.to
+        )
+    }
+}
@@ -106,7 +106,7 @@ impl OperationTrait for RenameAccount {
 
     fn validate_request(&self) -> Result<(), OperationError> {
         with_wallet(|s| {
-            if s.account(&self.new_name).is_ok() {
+            if s.account(&self.account_id).is_ok() {
                 Ok(())
             } else {
                 Err(OperationError::AccountDoesNotExist)
@@ -0,0 +1,435 @@
+//! The operation state as wallets stored it before the EVM amounts and fees
+//! became 256-bit. A `nat64` can't be read as a `nat`, so the pending operations
+//! of those wallets are read with these types on upgrade and converted.
+
+use super::{
+    btc::BtcTransfer,
+    evm::{
+        EvmDeployContract, EvmSignMessage, EvmSignRawTransaction, EvmSignTranscation, EvmTransfer,
+        EvmTransferErc20,
+    },
+    global::SendToken,
+    icp::{IcpTransfer, TopUpTransfer},
+    inner::{
+        AddUser, CreateAccount, HideAccount, RemoveAccount, RemoveUser, RenameAccount,
+        UnhideAccount, UpdateCanisterSettings, UpgradeCanister,
+    },
+    Operation, OperationState,
+};
+use crate::{
+    pending::PendingOperation,
+    processed::OperationStatus,
+    types::{ConsentMessage, PendingOperationMap, ResponseMap, UserIds},
+};
+use b3_utils::{
+    api::AppVersion, nonce::Nonce, principal::StoredPrincipal, types::OperationId, NanoTimeStamp,
+};
+use b3wallet_lib::ledger::evm::{
+    berlin::EvmTransaction2930, evm::EvmTransaction, legacy::EvmTransactionLegacy,
+    london::EvmTransaction1559,
+};
+use candid::{CandidType, Deserialize};
+use std::collections::BTreeMap;
+
+#[derive(CandidType, Deserialize, Clone)]
+pub struct LegacyOperationState {
+    pub pendings: BTreeMap<OperationId, LegacyPendingOperation>,
+    pub nonce: Nonce,
+}
+
+impl From<LegacyOperationState> for OperationState {
+    fn from(state: LegacyOperationState) -> Self {
+        let pendings: PendingOperationMap = state
+            .pendings
+            .into_iter()
+            .map(|(id, pending)| (id, pending.into()))
+            .collect();
+
+        OperationState::restore(pendings, state.nonce)
+    }
+}
+
+#[derive(CandidType, Deserialize, Clone)]
+pub struct LegacyPendingOperation {
+    pub id: OperationId,
+    pub request: LegacyOperation,
+    pub status: OperationStatus,
+    pub responses: ResponseMap,
+    pub deadline: NanoTimeStamp,
+    pub created_at: NanoTimeStamp,
+    pub created_by: StoredPrincipal,
+    pub allowed_signers: UserIds,
+    pub consent_message: LegacyConsentMessage,
+    pub version: AppVersion,
+}
+
+impl From<LegacyPendingOperation> for PendingOperation {
+    fn from(pending: LegacyPendingOperation) -> Self {
+        PendingOperation {
+            id: pending.id,
+            request: pending.request.into(),
+            status: pending.status,
+            responses: pending.responses,
+            deadline: pending.deadline,
+            created_at: pending.created_at,
+            created_by: pending.created_by,
+            allowed_signers: pending.allowed_signers,
+            consent_message: pending.consent_message.into(),
+            evm_simulation: None,
+            simulated_at: None,
+            version: pending.version,
+        }
+    }
+}
+
+#[derive(CandidType, Deserialize, Clone)]
+pub struct LegacyConsentMessage {
+    pub message: String,
+    pub reason: String,
+    pub title: String,
+}
+
+impl From<LegacyConsentMessage> for ConsentMessage {
+    fn from(consent: LegacyConsentMessage) -> Self {
+        ConsentMessage {
+            message: consent.message,
+            reason: consent.reason,
+            title: consent.title,
+            canister_consent: None,
+            evm_fees: None,
+            warning: None,
+        }
+    }
+}
+
+/// The operations there were then, the ones that didn't change are read as they are now.
+#[derive(CandidType, Deserialize, Clone)]
+pub enum LegacyOperation {
+    SendToken(SendToken),
+    EvmTransfer(LegacyEvmTransfer),
+    EvmSignMessage(EvmSignMessage),
+    EvmTransferErc20(LegacyEvmTransferErc20),
+    EvmDeployContract(EvmDeployContract),
+    EvmSignTranscation(LegacyEvmSignTranscation),
+    EvmSignRawTransaction(EvmSignRawTransaction),
+    BtcTransfer(BtcTransfer),
+    IcpTransfer(IcpTransfer),
+    TopUpTransfer(TopUpTransfer),
+    AddUser(AddUser),
+    RemoveUser(RemoveUser),
+    CreateAccount(CreateAccount),
+    RemoveAccount(RemoveAccount),
+    RenameAccount(RenameAccount),
+    HideAccount(HideAccount),
+    UnhideAccount(UnhideAccount),
+    UpgradeCanister(UpgradeCanister),
+    UpdateCanisterSettings(UpdateCanisterSettings),
+}
+
+impl From<LegacyOperation> for Operation {
+    fn from(operation: LegacyOperation) -> Self {
+        match operation {
+            LegacyOperation::SendToken(args) => args.into(),
+            LegacyOperation::EvmTransfer(args) => EvmTransfer::from(args).into(),
+            LegacyOperation::EvmSignMessage(args) => args.into(),
+            LegacyOperation::EvmTransferErc20(args) => EvmTransferErc20::from(args).into(),
+            LegacyOperation::EvmDeployContract(args) => args.into(),
+            LegacyOperation::EvmSignTranscation(args) => EvmSignTranscation::from(args).into(),
+            LegacyOperation::EvmSignRawTransaction(args) => args.into(),
+            LegacyOperation::BtcTransfer(args) => args.into(),
+            LegacyOperation::IcpTransfer(args) => args.into(),
+            LegacyOperation::TopUpTransfer(args) => args.into(),
+            LegacyOperation::AddUser(args) => args.into(),
+            LegacyOperation::RemoveUser(args) => args.into(),
+            LegacyOperation::CreateAccount(args) => args.into(),
+            LegacyOperation::RemoveAccount(args) => args.into(),
+            LegacyOperation::RenameAccount(args) => args.into(),
+            LegacyOperation::HideAccount(args) => args.into(),
+            LegacyOperation::UnhideAccount(args) => args.into(),
+            LegacyOperation::UpgradeCanister(args) => args.into(),
+            LegacyOperation::UpdateCanisterSettings(args) => args.into(),
+        }
+    }
+}
+
+#[derive(CandidType, Deserialize, Clone)]
+pub struct LegacyEvmTransfer {
+    pub account_id: String,
+    pub chain_id: u64,
+    pub nonce: u64,
+    pub to: String,
+    pub value: u64,
+    pub gas_limit: Option<u64>,
+    pub max_fee_per_gas: Option<u64>,
+    pub max_priority_fee_per_gas: Option<u64>,
+}
+
+impl From<LegacyEvmTransfer> for EvmTransfer {
+    fn from(args: LegacyEvmTransfer) -> Self {
+        EvmTransfer {
+            account_id: args.account_id,
+            chain_id: args.chain_id,
+            to: args.to,
+            ens_name: None,
+            value: args.value.into(),
+            gas_limit: args.gas_limit,
+            max_fee_per_gas: args.max_fee_per_gas,
+            max_priority_fee_per_gas: args.max_priority_fee_per_gas,
+        }
+    }
+}
+
+#[derive(CandidType, Deserialize, Clone)]
+pub struct LegacyEvmTransferErc20 {
+    pub account_id: String,
+    pub chain_id: u64,
+    pub nonce: u64,
+    pub to: String,
+    pub value: u64,
+    pub contract_address: String,
+    pub gas_limit: Option<u64>,
+    pub max_fee_per_gas: Option<u64>,
+    pub max_priority_fee_per_gas: Option<u64>,
+}
+
+impl From<LegacyEvmTransferErc20> for EvmTransferErc20 {
+    fn from(args: LegacyEvmTransferErc20) -> Self {
+        EvmTransferErc20 {
+            account_id: args.account_id,
+            chain_id: args.chain_id,
+            to: args.to,
+            ens_name: None,
+            value: args.value.into(),
+            contract_address: args.contract_address,
+            gas_limit: args.gas_limit,
+            max_fee_per_gas: args.max_fee_per_gas,
+            max_priority_fee_per_gas: args.max_priority_fee_per_gas,
+        }
+    }
+}
+
+#[derive(CandidType, Deserialize, Clone)]
+pub struct LegacyEvmSignTranscation {
+    pub account_id: String,
+    pub chain_id: u64,
+    pub transaction: LegacyEvmTransaction,
+}
+
+impl From<LegacyEvmSignTranscation> for EvmSignTranscation {
+    fn from(args: LegacyEvmSignTranscation) -> Self {
+        EvmSignTranscation {
+            account_id: args.account_id,
+            chain_id: args.chain_id,
+            transaction: args.transaction.into(),
+        }
+    }
+}
+
+#[derive(CandidType, Deserialize, Clone)]
+pub enum LegacyEvmTransaction {
+    EvmTransactionLegacy(LegacyEvmTransactionLegacy),
+    EvmTransaction1559(LegacyEvmTransaction1559),
+    EvmTransaction2930(LegacyEvmTransaction2930),
+}
+
+impl From<LegacyEvmTransaction> for EvmTransaction {
+    fn from(transaction: LegacyEvmTransaction) -> Self {
+        match transaction {
+            LegacyEvmTransaction::EvmTransactionLegacy(tx) => EvmTransactionLegacy {
+                chain_id: tx.chain_id,
+                nonce: tx.nonce,
+                gas_price: tx.gas_price.into(),
+                gas_limit: tx.gas_limit,
+                to: tx.to,
+                value: tx.value.into(),
+                data: tx.data,
+                v: tx.v,
+                r: tx.r,
+                s: tx.s,
+            }
+            .into(),
+            LegacyEvmTransaction::EvmTransaction1559(tx) => EvmTransaction1559 {
+                chain_id: tx.chain_id,
+                nonce: tx.nonce,
+                max_priority_fee_per_gas: tx.max_priority_fee_per_gas.into(),
+                gas_limit: tx.gas_limit,
+                max_fee_per_gas: tx.max_fee_per_gas.into(),
+                to: tx.to,
+                value: tx.value.into(),
+                data: tx.data,
+                access_list: tx.access_list,
+                v: tx.v,
+                r: tx.r,
+                s: tx.s,
+            }
+            .into(),
+            LegacyEvmTransaction::EvmTransaction2930(tx) => EvmTransaction2930 {
+                chain_id: tx.chain_id,
+                nonce: tx.nonce,
+                gas_price: tx.gas_price.into(),
+                gas_limit: tx.gas_limit,
+                to: tx.to,
+                value: tx.value.into(),
+                data: tx.data,
+                access_list: tx.access_list,
+                v: tx.v,
+                r: tx.r,
+                s: tx.s,
+            }
+            .into(),
+        }
+    }
+}
+
+#[derive(CandidType, Deserialize, Clone)]
+pub struct LegacyEvmTransactionLegacy {
+    pub chain_id: u64,
+    pub nonce: u64,
+    pub gas_price: u64,
+    pub gas_limit: u64,
+    pub to: String,
+    pub value: u64,
+    pub data: String,
+    pub v: String,
+    pub r: String,
+    pub s: String,
+}
+
+#[derive(CandidType, Deserialize, Clone)]
+pub struct LegacyEvmTransaction1559 {
+    pub chain_id: u64,
+    pub nonce: u64,
+    pub max_priority_fee_per_gas: u64,
+    pub gas_limit: u64,
+    pub max_fee_per_gas: u64,
+    pub to: String,
+    pub value: u64,
+    pub data: String,
+    pub access_list: Vec<(String, Vec<String>)>,
+    pub v: String,
+    pub r: String,
+    pub s: String,
+}
+
+#[derive(CandidType, Deserialize, Clone)]
+pub struct LegacyEvmTransaction2930 {
+    pub chain_id: u64,
+    pub nonce: u64,
+    pub gas_price: u64,
+    pub gas_limit: u64,
+    pub to: String,
+    pub value: u64,
+    pub data: String,
+    pub access_list: Vec<(String, Vec<String>)>,
+    pub v: String,
+    pub r: String,
+    pub s: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use b3wallet_lib::ledger::evm::{evm::EvmSignTrait, u256::U256};
+    use candid::Principal;
+
+    const TO: &str = "0x7a9d2f53fea15e31f0a89d7f5d9e0e82b0b88ad6";
+
+    fn legacy_pending(id: OperationId, request: LegacyOperation) -> LegacyPendingOperation {
+        LegacyPendingOperation {
+            id,
+            request,
+            status: OperationStatus::Pending,
+            responses: ResponseMap::new(),
+            deadline: NanoTimeStamp(2),
+            created_at: NanoTimeStamp(1),
+            created_by: Principal::anonymous().into(),
+            allowed_signers: UserIds::new(),
+            consent_message: LegacyConsentMessage {
+                message: "Transfer".to_string(),
+                reason: "Pay".to_string(),
+                title: "Transfer".to_string(),
+            },
+            version: "0.0.1".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_restore_legacy_operation_state() {
+        let transfer = LegacyOperation::EvmTransfer(LegacyEvmTransfer {
+            account_id: "-default".to_string(),
+            chain_id: 1,
+            nonce: 3,
+            to: TO.to_string(),
+            value: 1_000_000,
+            gas_limit: None,
+            max_fee_per_gas: Some(30_000_000_000),
+            max_priority_fee_per_gas: None,
+        });
+
+        let sign = LegacyOperation::EvmSignTranscation(LegacyEvmSignTranscation {
+            account_id: "-default".to_string(),
+            chain_id: 1,
+            transaction: LegacyEvmTransaction::EvmTransaction1559(LegacyEvmTransaction1559 {
+                chain_id: 1,
+                nonce: 4,
+                max_priority_fee_per_gas: 1_000_000_000,
+                gas_limit: 21_000,
+                max_fee_per_gas: 30_000_000_000,
+                to: TO.to_string(),
+                value: 5,
+                data: "0x".to_string(),
+                access_list: vec![],
+                v: "0x00".to_string(),
+                r: "0x00".to_string(),
+                s: "0x00".to_string(),
+            }),
+        });
+
+        let mut nonce = Nonce::new(None);
+        nonce.increment();
+        nonce.increment();
+
+        let legacy = LegacyOperationState {
+            pendings: BTreeMap::from([
+                (0, legacy_pending(0, transfer)),
+                (1, legacy_pending(1, sign)),
+            ]),
+            nonce,
+        };
+
+        let bytes = candid::encode_one(&legacy).unwrap();
+
+        // the current types can't read it
+        assert!(candid::decode_one::<OperationState>(&bytes).is_err());
+
+        let state: OperationState = candid::decode_one::<LegacyOperationState>(&bytes)
+            .unwrap()
+            .into();
+
+        assert_eq!(state.request_counter(), 2);
+        assert_eq!(state.pending_list().len(), 2);
+
+        match &state.pending(&0).unwrap().request {
+            Operation::EvmTransfer(transfer) => {
+                assert_eq!(transfer.value, U256::from(1_000_000u64));
+                assert_eq!(transfer.ens_name, None);
+                assert_eq!(transfer.max_fee_per_gas, Some(30_000_000_000));
+            }
+            request => panic!("unexpected request {:?}", request),
+        }
+
+        match &state.pending(&1).unwrap().request {
+            Operation::EvmSignTranscation(sign) => {
+                assert_eq!(sign.transaction.value(), &U256::from(5u64));
+                assert_eq!(sign.transaction.nonce(), 4);
+            }
+            request => panic!("unexpected request {:?}", request),
+        }
+
+        // and it is stored in the current shape from then on
+        let bytes = candid::encode_one(&state).unwrap();
+        let restored = candid::decode_one::<OperationState>(&bytes).unwrap();
+
+        assert_eq!(restored.pending_list().len(), 2);
+    }
+}
@@ -1,10 +1,26 @@
 use std::fmt;
 
 use super::btc::transfer::BtcTransfer;
-use super::evm::sign::{EvmSignMessage, EvmSignRawTransaction, EvmSignTranscation};
+use super::canister::call::CallCanister;
+use super::cycles::top_up::{UnwatchCanister, UpdateCyclesTopUp, UpdateFleetTopUp, WatchCanister};
+use super::cycles::transfer::{CreateCanisterWithCycles, DepositCycles, UpdateCyclesLedger};
+use super::evm::call::EvmContractCall;
+use super::evm::network::{UpdateEvmGasPolicy, UpdateEvmNetwork, UpdateEvmRpc};
+use super::evm::nft::{EvmTransferErc1155, EvmTransferErc721};
+use super::evm::nonce::EvmCancelNonce;
+use super::evm::sign::{
+    EvmSignMessage, EvmSignPersonalMessage, EvmSignRawTransaction, EvmSignTranscation,
+    EvmSignTypedData,
+};
 use super::evm::transfer::{EvmTransfer, EvmTransferErc20};
+use super::evm::user_operation::{EvmSignUserOperation, UpdateErc4337Config};
 use super::global::SendToken;
+use super::icp::neuron::{
+    DisburseNeuron, FollowNeuron, IncreaseNeuronDissolveDelay, RefreshNeuron, StakeNeuron,
+    StartNeuronDissolving, StopNeuronDissolving, UpdateGovernanceCanister, VoteNeuron,
+};
 use super::icp::transfer::{IcpTransfer, NotifyTopUp, TopUpTransfer};
+use super::icrc7::transfer::Icrc7Transfer;
 use super::inner::account::{
     CreateAccount, HideAccount, RemoveAccount, RenameAccount, UnhideAccount,
 };
@@ -12,8 +28,13 @@ use super::inner::setting::{UpdateCanisterSettings, UpgradeCanister};
 use super::inner::user::{AddUser, RemoveUser};
 
 use b3_utils::ledger::TransferBlockIndex;
+use b3_utils::vec_to_hex_string_with_0x;
+use b3wallet_lib::ledger::cycles::types::CreateCanisterSuccess;
 use b3wallet_lib::ledger::evm::london::EvmTransaction1559;
-use b3wallet_lib::ledger::types::SendResult;
+use b3wallet_lib::ledger::evm::user_operation::SignedUserOperation;
+use b3wallet_lib::ledger::icp::governance::NeuronIdValue;
+use b3wallet_lib::ledger::icrc::types::TxIndex;
+use b3wallet_lib::ledger::types::{EvmPending, SendResult};
 use candid::{CandidType, Deserialize};
 use enum_dispatch::enum_dispatch;
 
@@ -28,9 +49,29 @@ pub enum OperationResult {
     IcpTransfered(IcpTransfered),
     EvmTransfered(EvmTransfered),
     EvmErc20Transfered(EvmErc20Transfered),
+    EvmErc721Transfered(EvmErc721Transfered),
+    EvmErc1155Transfered(EvmErc1155Transfered),
     TopUpTransfered(TopUpTransfered),
     CanisterTopUped(CanisterTopUped),
     BtcTransfered(BtcTransfered),
+    Icrc7Transfered(Icrc7Transfered),
+    NeuronStaked(NeuronStaked),
+    NeuronRefreshed(NeuronRefreshed),
+    NeuronDissolveDelayIncreased(IncreaseNeuronDissolveDelay),
+    NeuronDissolvingStarted(StartNeuronDissolving),
+    NeuronDissolvingStopped(StopNeuronDissolving),
+    NeuronDisbursed(NeuronDisbursed),
+    NeuronFollowed(FollowNeuron),
+    NeuronVoted(VoteNeuron),
+    GovernanceCanisterUpdated(UpdateGovernanceCanister),
+    CyclesDeposited(CyclesDeposited),
+    CanisterCreated(CanisterCreated),
+    CyclesLedgerUpdated(UpdateCyclesLedger),
+    CyclesTopUpUpdated(UpdateCyclesTopUp),
+    FleetTopUpUpdated(UpdateFleetTopUp),
+    CanisterWatched(WatchCanister),
+    CanisterUnwatched(UnwatchCanister),
+    CanisterCalled(CanisterCalled),
     SignerAdded(AddUser),
     SignerRemoved(RemoveUser),
     CanisterUpgraded(UpgradeCanister),
@@ -43,7 +84,16 @@ pub enum OperationResult {
     EvmTransactionSigned(EvmTransactionSigned),
     EvmRawTransactionSigned(EvmRawTransactionSigned),
     EvmContractDeployed(EvmContractDeployed),
+    EvmContractCalled(EvmContractCalled),
     EvmMessageSigned(EvmMessageSigned),
+    EvmPersonalMessageSigned(EvmPersonalMessageSigned),
+    EvmTypedDataSigned(EvmTypedDataSigned),
+    EvmNonceCancelled(EvmNonceCancelled),
+    EvmUserOperationSigned(EvmUserOperationSigned),
+    Erc4337ConfigUpdated(UpdateErc4337Config),
+    EvmRpcUpdated(UpdateEvmRpc),
+    EvmGasPolicyUpdated(UpdateEvmGasPolicy),
+    EvmNetworkUpdated(UpdateEvmNetwork),
 }
 
 #[rustfmt::skip]
@@ -52,12 +102,32 @@ impl fmt::Display for OperationResult {
         match self {
             OperationResult::Empty(_) => write!(f, "Empty"),
             OperationResult::TokenSent(TokenSent(ref args, ref tx_id)) => write!(f, "TokenSent: from {} in {} to {} at tx {}", args.account_id, args.chain, args.to, tx_id),
-            OperationResult::IcpTransfered(IcpTransfered(args, block_index)) => write!(f, "IcpTransfered: from {} to {} at block {}", args.account_id, args.to, block_index),
-            OperationResult::EvmTransfered(EvmTransfered(args, tx_hash)) => write!(f, "EvmTransfered: from {} to {} at tx {}", args.account_id, args.to, tx_hash),
-            OperationResult::EvmErc20Transfered(EvmErc20Transfered(args, tx_hash)) => write!(f, "EvmErc20Transfered: from {} to {} at tx {}", args.account_id, args.to, tx_hash),
+            OperationResult::IcpTransfered(IcpTransfered(args, block_index)) => write!(f, "IcpTransfered: from {} to {} at block {}", args.account_id, args.recipient(), block_index),
+            OperationResult::EvmTransfered(EvmTransfered(args, pending)) => write!(f, "EvmTransfered: from {} to {} at tx {} with nonce {}", args.account_id, args.to, pending.tx_hash, pending.nonce),
+            OperationResult::EvmErc20Transfered(EvmErc20Transfered(args, pending)) => write!(f, "EvmErc20Transfered: from {} to {} at tx {} with nonce {}", args.account_id, args.to, pending.tx_hash, pending.nonce),
+            OperationResult::EvmErc721Transfered(EvmErc721Transfered(args, pending)) => write!(f, "EvmErc721Transfered: token {} from {} to {} at tx {} with nonce {}", args.token_id, args.account_id, args.to, pending.tx_hash, pending.nonce),
+            OperationResult::EvmErc1155Transfered(EvmErc1155Transfered(args, pending)) => write!(f, "EvmErc1155Transfered: {} of token {} from {} to {} at tx {} with nonce {}", args.amount, args.token_id, args.account_id, args.to, pending.tx_hash, pending.nonce),
             OperationResult::BtcTransfered(BtcTransfered(args, tx_id)) => write!(f, "BtcTransfered: from {} to {} at tx {}", args.account_id, args.to, tx_id),
             OperationResult::TopUpTransfered(TopUpTransfered(args, block_index)) => write!(f, "TopUpTransfered: from {} to {} at block {}", args.account_id, args.canister_id, block_index),
             OperationResult::CanisterTopUped(CanisterTopUped(args, cycles)) => write!(f, "CanisterTopUped: from {} top up {} cycles for {}", args.account_id, cycles, args.canister_id),
+            OperationResult::Icrc7Transfered(Icrc7Transfered(args, tx_index)) => write!(f, "Icrc7Transfered: token {} from {} to {} at tx {}", args.token_id, args.account_id, args.to, tx_index),
+            OperationResult::NeuronStaked(NeuronStaked(args, neuron_id)) => write!(f, "NeuronStaked: {} ICP from {} in neuron {}", args.amount, args.account_id, neuron_id),
+            OperationResult::NeuronRefreshed(NeuronRefreshed(args, neuron_id)) => write!(f, "NeuronRefreshed: neuron {} with memo {}", neuron_id, args.memo),
+            OperationResult::NeuronDissolveDelayIncreased(args) => write!(f, "NeuronDissolveDelayIncreased: neuron {} by {} seconds", args.neuron_id, args.additional_dissolve_delay_seconds),
+            OperationResult::NeuronDissolvingStarted(args) => write!(f, "NeuronDissolvingStarted: neuron {}", args.neuron_id),
+            OperationResult::NeuronDissolvingStopped(args) => write!(f, "NeuronDissolvingStopped: neuron {}", args.neuron_id),
+            OperationResult::NeuronDisbursed(NeuronDisbursed(args, block_height)) => write!(f, "NeuronDisbursed: neuron {} at block {}", args.neuron_id, block_height),
+            OperationResult::NeuronFollowed(args) => write!(f, "NeuronFollowed: neuron {} on topic {}", args.neuron_id, args.topic),
+            OperationResult::NeuronVoted(args) => write!(f, "NeuronVoted: neuron {} on proposal {}", args.neuron_id, args.proposal_id),
+            OperationResult::GovernanceCanisterUpdated(args) => write!(f, "GovernanceCanisterUpdated: {}", args.canister_id.map_or("NNS".to_string(), |canister_id| canister_id.to_string())),
+            OperationResult::CyclesDeposited(CyclesDeposited(args, block_index)) => write!(f, "CyclesDeposited: {} cycles from {} to {} at block {}", args.cycles, args.account_id, args.canister_id, block_index),
+            OperationResult::CanisterCreated(CanisterCreated(args, created)) => write!(f, "CanisterCreated: {} with {} cycles from {} at block {}", created.canister_id, args.cycles, args.account_id, created.block_id),
+            OperationResult::CyclesLedgerUpdated(args) => write!(f, "CyclesLedgerUpdated: {}", args.canister_id.map_or("default".to_string(), |canister_id| canister_id.to_string())),
+            OperationResult::CyclesTopUpUpdated(args) => write!(f, "CyclesTopUpUpdated: {}", args.policy.as_ref().map_or("stopped".to_string(), |policy| format!("{} ICP from {}", policy.amount, policy.account_id))),
+            OperationResult::FleetTopUpUpdated(args) => write!(f, "FleetTopUpUpdated: {}", args.policy.as_ref().map_or("stopped".to_string(), |policy| format!("{} canisters from {}", policy.canisters.len(), policy.account_id))),
+            OperationResult::CanisterWatched(args) => write!(f, "CanisterWatched: {}", args.canister.canister_id),
+            OperationResult::CanisterUnwatched(args) => write!(f, "CanisterUnwatched: {}", args.canister_id),
+            OperationResult::CanisterCalled(CanisterCalled(args, reply)) => write!(f, "CanisterCalled: {} on {} replied with {} bytes", args.method_name, args.canister_id, reply.len()),
             OperationResult::SignerAdded(_) => write!(f, "SignerAdded"),
             OperationResult::SignerRemoved(_) => write!(f, "SignerRemoved"),
             OperationResult::CanisterUpgraded(_) => write!(f, "CanisterUpgraded"),
@@ -68,13 +138,42 @@ impl fmt::Display for OperationResult {
             OperationResult::AccountHidden(_) => write!(f, "AccountHidden"),
             OperationResult::AccountUnhidden(_) => write!(f, "AccountUnhidden"),
             OperationResult::EvmRawTransactionSigned(_) => write!(f, "EvmRawTransactionSigned"),
-            OperationResult::EvmTransactionSigned(_) => write!(f, "EvmTransactionSigned"),
-            OperationResult::EvmContractDeployed(_) => write!(f, "EvmContractDeployed"),
+            OperationResult::EvmTransactionSigned(EvmTransactionSigned(args, pending)) => write!(f, "EvmTransactionSigned: from {} at tx {} with nonce {}", args.account_id, pending.tx_hash, pending.nonce),
+            OperationResult::EvmContractDeployed(result) => write!(f, "EvmContractDeployed: {} at tx {}", result.contract_address, result.tx_hash),
+            OperationResult::EvmContractCalled(EvmContractCalled(args, pending)) => write!(f, "EvmContractCalled: {} on {} at tx {} with nonce {}", args.function, args.contract_address, pending.tx_hash, pending.nonce),
             OperationResult::EvmMessageSigned(_) => write!(f, "EvmMessageSigned"),
+            OperationResult::EvmPersonalMessageSigned(EvmPersonalMessageSigned(args, signature)) => write!(f, "EvmPersonalMessageSigned: by {} on chain {} with {}", args.account_id, args.chain_id, vec_to_hex_string_with_0x(signature)),
+            OperationResult::EvmTypedDataSigned(EvmTypedDataSigned(args, signature)) => write!(f, "EvmTypedDataSigned: by {} on chain {} with {}", args.account_id, args.chain_id, vec_to_hex_string_with_0x(signature)),
+            OperationResult::EvmNonceCancelled(EvmNonceCancelled(args, pending)) => write!(f, "EvmNonceCancelled: nonce {} of {} at tx {}", args.nonce, args.account_id, pending.tx_hash),
+            OperationResult::EvmUserOperationSigned(EvmUserOperationSigned(args, signed)) => write!(f, "EvmUserOperationSigned: by {} for {} with hash {}", args.account_id, signed.user_operation.sender, signed.hash),
+            OperationResult::Erc4337ConfigUpdated(args) => write!(f, "Erc4337ConfigUpdated: chain {} {}", args.chain_id, args.config.as_ref().map_or("removed".to_string(), |config| config.entry_point.clone())),
+            OperationResult::EvmGasPolicyUpdated(args) => write!(f, "EvmGasPolicyUpdated: {}", args.policy.as_ref().map_or("default".to_string(), |policy| format!("{:?} with cap {:?}", policy.speed, policy.max_fee_per_gas_cap))),
+            OperationResult::EvmNetworkUpdated(args) => write!(f, "EvmNetworkUpdated: chain {} {}", args.chain_id, args.network.as_ref().map_or("removed".to_string(), |network| network.name.clone())),
+            OperationResult::EvmRpcUpdated(args) => write!(f, "EvmRpcUpdated: {}", args.evm_rpc.as_ref().map_or("default".to_string(), |evm_rpc| evm_rpc.canister_id.to_string())),
         }
     }
 }
 
+impl OperationResult {
+    /// The hash of the EVM transaction the operation broadcast, if any.
+    pub fn evm_tx_hash(&self) -> Option<&str> {
+        let pending = match self {
+            OperationResult::TokenSent(TokenSent(_, SendResult::EVM(pending))) => pending,
+            OperationResult::EvmTransfered(EvmTransfered(_, pending)) => pending,
+            OperationResult::EvmErc20Transfered(EvmErc20Transfered(_, pending)) => pending,
+            OperationResult::EvmErc721Transfered(EvmErc721Transfered(_, pending)) => pending,
+            OperationResult::EvmErc1155Transfered(EvmErc1155Transfered(_, pending)) => pending,
+            OperationResult::EvmContractCalled(EvmContractCalled(_, pending)) => pending,
+            OperationResult::EvmTransactionSigned(EvmTransactionSigned(_, pending)) => pending,
+            OperationResult::EvmNonceCancelled(EvmNonceCancelled(_, pending)) => pending,
+            OperationResult::EvmContractDeployed(result) => return Some(&result.tx_hash),
+            _ => return None,
+        };
+
+        Some(&pending.tx_hash)
+    }
+}
+
 #[derive(CandidType, Clone, Deserialize, Debug)]
 pub struct TokenSent(pub SendToken, pub SendResult);
 
@@ -82,10 +181,16 @@ pub struct TokenSent(pub SendToken, pub SendResult);
 pub struct IcpTransfered(pub IcpTransfer, pub TransferBlockIndex);
 
 #[derive(CandidType, Clone, Deserialize, Debug)]
-pub struct EvmTransfered(pub EvmTransfer, pub String);
+pub struct EvmTransfered(pub EvmTransfer, pub EvmPending);
+
+#[derive(CandidType, Clone, Deserialize, Debug)]
+pub struct EvmErc20Transfered(pub EvmTransferErc20, pub EvmPending);
 
 #[derive(CandidType, Clone, Deserialize, Debug)]
-pub struct EvmErc20Transfered(pub EvmTransferErc20, pub String);
+pub struct EvmErc721Transfered(pub EvmTransferErc721, pub EvmPending);
+
+#[derive(CandidType, Clone, Deserialize, Debug)]
+pub struct EvmErc1155Transfered(pub EvmTransferErc1155, pub EvmPending);
 
 #[derive(CandidType, Clone, Deserialize, Debug)]
 pub struct TopUpTransfered(pub TopUpTransfer, pub TransferBlockIndex);
@@ -96,21 +201,58 @@ pub struct CanisterTopUped(pub NotifyTopUp, pub u128);
 #[derive(CandidType, Clone, Deserialize, Debug)]
 pub struct BtcTransfered(pub BtcTransfer, pub String);
 
+#[derive(CandidType, Clone, Deserialize, Debug)]
+pub struct Icrc7Transfered(pub Icrc7Transfer, pub TxIndex);
+
+#[derive(CandidType, Clone, Deserialize, Debug)]
+pub struct NeuronStaked(pub StakeNeuron, pub NeuronIdValue);
+
+#[derive(CandidType, Clone, Deserialize, Debug)]
+pub struct NeuronRefreshed(pub RefreshNeuron, pub NeuronIdValue);
+
+#[derive(CandidType, Clone, Deserialize, Debug)]
+pub struct NeuronDisbursed(pub DisburseNeuron, pub u64);
+
+#[derive(CandidType, Clone, Deserialize, Debug)]
+pub struct CyclesDeposited(pub DepositCycles, pub TxIndex);
+
+#[derive(CandidType, Clone, Deserialize, Debug)]
+pub struct CanisterCreated(pub CreateCanisterWithCycles, pub CreateCanisterSuccess);
+
+#[derive(CandidType, Clone, Deserialize, Debug)]
+pub struct CanisterCalled(pub CallCanister, pub Vec<u8>);
+
 #[derive(CandidType, Clone, Deserialize, Debug)]
 pub struct EvmContractDeployed {
     pub contract_address: String,
     pub transaction: EvmTransaction1559,
+    pub tx_hash: String,
 }
 
+#[derive(CandidType, Clone, Deserialize, Debug)]
+pub struct EvmContractCalled(pub EvmContractCall, pub EvmPending);
+
 #[derive(CandidType, Clone, Deserialize, Debug)]
 pub struct EvmMessageSigned(pub EvmSignMessage, pub Vec<u8>);
 
 #[derive(CandidType, Clone, Deserialize, Debug)]
-pub struct EvmTransactionSigned(pub EvmSignTranscation, pub String);
+pub struct EvmPersonalMessageSigned(pub EvmSignPersonalMessage, pub Vec<u8>);
+
+#[derive(CandidType, Clone, Deserialize, Debug)]
+pub struct EvmTypedDataSigned(pub EvmSignTypedData, pub Vec<u8>);
+
+#[derive(CandidType, Clone, Deserialize, Debug)]
+pub struct EvmTransactionSigned(pub EvmSignTranscation, pub EvmPending);
+
+#[derive(CandidType, Clone, Deserialize, Debug)]
+pub struct EvmNonceCancelled(pub EvmCancelNonce, pub EvmPending);
 
 #[derive(CandidType, Clone, Deserialize, Debug)]
 pub struct EvmRawTransactionSigned(pub EvmSignRawTransaction, pub String);
 
+#[derive(CandidType, Clone, Deserialize, Debug)]
+pub struct EvmUserOperationSigned(pub EvmSignUserOperation, pub SignedUserOperation);
+
 #[derive(CandidType, Clone, Deserialize, Debug)]
 pub struct Empty;
 
@@ -1,5 +1,6 @@
 use crate::{
     error::OperationError,
+    operation::OperationTrait,
     pending::{PendingOperation, RequestArgs},
     types::{PendingOperationMap, PendingOperations},
 };
@@ -22,10 +23,22 @@ impl Default for OperationState {
 }
 
 impl OperationState {
-    pub fn new_request(&self, signer_id: StoredPrincipal, args: RequestArgs) -> PendingOperation {
+    /// The state with the given pending operations, when they are read from an older shape.
+    pub(crate) fn restore(pendings: PendingOperationMap, nonce: Nonce) -> Self {
+        OperationState { pendings, nonce }
+    }
+
+    /// Check the operation and make the pending request of it, it still has to be added.
+    pub fn new_request(
+        &self,
+        signer_id: StoredPrincipal,
+        args: RequestArgs,
+    ) -> Result<PendingOperation, OperationError> {
+        args.request.validate_request()?;
+
         let id = self.request_counter();
 
-        PendingOperation::new(id, signer_id, args)
+        Ok(PendingOperation::new(id, signer_id, args))
     }
 
     pub fn add(&mut self, sign_request: PendingOperation) -> OperationId {
@@ -76,3 +89,41 @@ impl OperationState {
         &mut self.pendings
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{operation::canister::call::CallCanister, types::UserIds};
+    use candid::Principal;
+
+    #[test]
+    fn test_new_request_rejects_invalid_operation() {
+        let mut state = OperationState::default();
+
+        let request = CallCanister {
+            canister_id: Principal::management_canister(),
+            method_name: "install_code".to_string(),
+            arg: candid::encode_args(()).unwrap(),
+            cycles: None,
+        };
+
+        let args = RequestArgs {
+            request: request.into(),
+            reason: "Install code".to_string(),
+            version: "0.0.0".to_string(),
+            allowed_signers: UserIds::new(),
+            deadline: None,
+        };
+
+        let result = state
+            .new_request(Principal::anonymous().into(), args)
+            .map(|pending| state.add(pending));
+
+        assert_eq!(
+            result.unwrap_err(),
+            OperationError::CannotCallManagementCanister
+        );
+        assert!(state.pending_list().is_empty());
+        assert_eq!(state.request_counter(), 0);
+    }
+}
@@ -6,8 +6,12 @@ use crate::{
     types::{ConsentMessage, ResponseMap, UserIds},
 };
 use b3_utils::{api::AppVersion, principal::StoredPrincipal, types::OperationId, NanoTimeStamp};
+use b3wallet_lib::{error::WalletError, ledger::evm::simulation::EvmSimulation};
 use candid::{CandidType, Deserialize};
 
+/// How long a simulation is reused before the providers are asked again, in nanoseconds.
+pub const SIMULATION_CACHE_NANOS: u64 = 30_000_000_000;
+
 #[derive(CandidType, Clone, Deserialize, Debug)]
 pub struct PendingOperation {
     pub id: OperationId,
@@ -19,6 +23,10 @@ pub struct PendingOperation {
     pub created_by: StoredPrincipal,
     pub allowed_signers: UserIds,
     pub consent_message: ConsentMessage,
+    /// The outcome of the EVM transaction at the latest block, when it was simulated.
+    pub evm_simulation: Option<EvmSimulation>,
+    /// When the EVM transaction was last simulated.
+    pub simulated_at: Option<NanoTimeStamp>,
     pub version: AppVersion,
 }
 
@@ -55,6 +63,8 @@ impl PendingOperation {
             deadline,
             created_at: NanoTimeStamp::now(),
             consent_message,
+            evm_simulation: None,
+            simulated_at: None,
             version: args.version,
         }
     }
@@ -70,6 +80,37 @@ impl PendingOperation {
         }
     }
 
+    /// Keep the simulation of the EVM transaction, the signers are warned when
+    /// it would revert or couldn't be run. Replaces the warning of an earlier one.
+    pub fn set_evm_simulation(&mut self, simulation: Result<EvmSimulation, WalletError>) {
+        let simulation =
+            simulation.unwrap_or_else(|err| EvmSimulation::Unavailable(err.to_string()));
+
+        let previous_warning = self.evm_simulation.as_ref().and_then(simulation_warning);
+
+        if previous_warning.is_some() && self.consent_message.warning == previous_warning {
+            self.consent_message.warning = None;
+        }
+
+        if let Some(warning) = simulation_warning(&simulation) {
+            self.consent_message.warning = Some(warning);
+        }
+
+        self.evm_simulation = Some(simulation);
+        self.simulated_at = Some(NanoTimeStamp::now());
+    }
+
+    /// The last simulation, if it was run less than `SIMULATION_CACHE_NANOS` before `now`.
+    pub fn fresh_evm_simulation(&self, now: &NanoTimeStamp) -> Option<&EvmSimulation> {
+        let simulated_at = self.simulated_at.as_ref()?;
+
+        if now.0.saturating_sub(simulated_at.0) >= SIMULATION_CACHE_NANOS {
+            return None;
+        }
+
+        self.evm_simulation.as_ref()
+    }
+
     pub fn method(&self) -> String {
         self.request.method_name()
     }
@@ -142,3 +183,17 @@ impl PendingOperation {
         Ok(())
     }
 }
+
+fn simulation_warning(simulation: &EvmSimulation) -> Option<String> {
+    match simulation {
+        EvmSimulation::Success { .. } => None,
+        EvmSimulation::Reverted(_) => Some(format!(
+            "This transaction {}, approving it would only spend the gas!",
+            simulation
+        )),
+        EvmSimulation::Unavailable(_) => Some(format!(
+            "This transaction {}, review it carefully before approving!",
+            simulation
+        )),
+    }
+}
@@ -8,6 +8,7 @@ use crate::{
     operation::result::{Empty, OperationResult},
     pending::PendingOperation,
 };
+use b3wallet_lib::ledger::evm::types::EvmReceipt;
 use candid::{CandidType, Deserialize};
 
 mod state;
@@ -29,6 +30,7 @@ pub struct ProcessedOperation {
     status: OperationStatus,
     result: OperationResult,
     operation: PendingOperation,
+    evm_receipt: Option<EvmReceipt>,
 }
 
 impl From<ProcessedOperation> for PendingOperation {
@@ -54,6 +56,7 @@ impl From<PendingOperation> for ProcessedOperation {
             result: OperationResult::Empty(Empty),
             status,
             operation: request,
+            evm_receipt: None,
         }
     }
 }
@@ -67,6 +70,7 @@ impl ProcessedOperation {
             method: request.method(),
             operation: request.clone(),
             status: OperationStatus::Pending,
+            evm_receipt: None,
         }
     }
 
@@ -111,4 +115,13 @@ impl ProcessedOperation {
     pub fn get_result(&self) -> &OperationResult {
         &self.result
     }
+
+    pub fn get_evm_receipt(&self) -> Option<&EvmReceipt> {
+        self.evm_receipt.as_ref()
+    }
+
+    /// Record how the EVM transaction sent by the operation ended up on chain.
+    pub fn set_evm_receipt(&mut self, receipt: EvmReceipt) {
+        self.evm_receipt = Some(receipt);
+    }
 }
@@ -4,6 +4,7 @@ use crate::{
     types::{ProcessedOperationMap, ProcessedOperations},
 };
 use b3_utils::types::OperationId;
+use b3wallet_lib::ledger::evm::types::EvmReceipt;
 use candid::{CandidType, Deserialize};
 
 #[derive(CandidType, Deserialize, Clone)]
@@ -58,6 +59,23 @@ impl ProccessedState {
         Ok(())
     }
 
+    /// Attach the receipt to the operation that sent the transaction,
+    /// returns its id or `None` when no operation sent it.
+    pub fn attach_evm_receipt(&mut self, receipt: EvmReceipt) -> Option<OperationId> {
+        let (operation_id, processed) = self.processeds.iter_mut().find(|(_, processed)| {
+            processed
+                .get_result()
+                .evm_tx_hash()
+                .map_or(false, |tx_hash| {
+                    tx_hash.eq_ignore_ascii_case(&receipt.tx_hash)
+                })
+        })?;
+
+        processed.set_evm_receipt(receipt);
+
+        Some(operation_id.clone())
+    }
+
     pub fn processeds(&self) -> &ProcessedOperationMap {
         &self.processeds
     }
@@ -1,5 +1,6 @@
 use crate::{
     error::OperationError,
+    icrc21::{Icrc21ConsentInfo, Icrc21ConsentMessageResponse},
     operation::{Operation, OperationTrait},
     pending::PendingOperation,
     processed::ProcessedOperation,
@@ -11,7 +12,7 @@ use b3_utils::{
     principal::StoredPrincipal,
     types::{OperationId, RoleId},
 };
-use b3wallet_lib::setting::WalletSettings;
+use b3wallet_lib::{error::WalletError, ledger::evm::gas::EvmFees, setting::WalletSettings};
 use candid::{CandidType, Deserialize};
 use std::collections::{BTreeMap, HashMap};
 
@@ -46,6 +47,9 @@ pub struct ConsentMessage {
     pub message: String,
     pub reason: String,
     pub title: String,
+    pub canister_consent: Option<Icrc21ConsentInfo>,
+    pub evm_fees: Option<EvmFees>,
+    pub warning: Option<String>,
 }
 
 impl ConsentMessage {
@@ -57,6 +61,45 @@ impl ConsentMessage {
             message,
             reason,
             title,
+            canister_consent: None,
+            evm_fees: None,
+            warning: None,
+        }
+    }
+
+    /// Attach the ICRC-21 consent message of the target canister, or a warning
+    /// when the canister couldn't describe the call.
+    pub fn set_canister_consent(&mut self, response: Icrc21ConsentMessageResponse) {
+        match response {
+            Ok(consent_info) => {
+                self.canister_consent = Some(consent_info);
+                self.warning = None;
+            }
+            Err(err) => {
+                self.canister_consent = None;
+                self.warning = Some(format!(
+                    "The target canister did not provide a consent message ({}), review the call arguments carefully before approving!",
+                    err
+                ));
+            }
+        }
+    }
+
+    /// Show the fees the EVM transaction would be sent with now. They are
+    /// quoted again when the operation is executed, within the same gas policy.
+    pub fn set_evm_fees(&mut self, quote: Result<EvmFees, WalletError>) {
+        match quote {
+            Ok(fees) => {
+                self.message = format!("{}, with {}", self.message, fees);
+                self.evm_fees = Some(fees);
+            }
+            Err(err) => {
+                self.evm_fees = None;
+                self.warning = Some(format!(
+                    "The fees of the transaction could not be estimated ({}), it may fail when executed!",
+                    err
+                ));
+            }
         }
     }
 }